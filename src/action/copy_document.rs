@@ -0,0 +1,283 @@
+//! Defines an action for copying a document to a new document id within the
+//! same database.
+
+use {DocumentId, Error, IntoDocumentPath, Revision, std};
+use action::query_keys::*;
+use document::WriteDocumentResponse;
+use transport::{JsonResponse, JsonResponseDecoder, Request, StatusCode, Transport};
+
+/// Copies a document on the CouchDB server to a new document id within the
+/// same database.
+///
+/// Chill copies the document by sending an HTTP request to `COPY` the source
+/// document's path, with the destination document id in the `Destination`
+/// header. The server copies the document's content and attachments without
+/// the client having to read or upload them, and it responds with the
+/// destination document's id and new revision.
+///
+/// By default, the destination document must not already exist. To overwrite
+/// an existing destination document, use the `with_destination_revision`
+/// method to specify the destination document's current revision.
+///
+/// # Errors
+///
+/// The following are _some_ errors that may occur when copying a document.
+///
+/// <table>
+/// <tr>
+///  <td><code>Error::DocumentConflict</code></td>
+///  <td>The destination document already exists and the action specifies no
+///  revision for it, or the destination revision is not current.</td>
+/// </tr>
+/// <tr>
+///  <td><code>Error::NotFound</code></td>
+///  <td>The source document does not exist.</td>
+/// </tr>
+/// <tr>
+///  <td><code>Error::Unauthorized</code></td>
+///  <td>The client lacks permission to copy the document.</td>
+/// </tr>
+/// </table>
+///
+/// # Examples
+///
+/// ```no_run
+/// extern crate chill;
+///
+/// let client = chill::Client::new("http://localhost:5984").unwrap();
+///
+/// let (doc_id, rev) = client
+///     .copy_document("/baseball/template", "babe_ruth")
+///     .run()
+///     .unwrap();
+///
+/// println!("Created document {} at revision {}", doc_id, rev);
+/// ```
+///
+pub struct CopyDocument<'a, T: Transport + 'a, P: IntoDocumentPath> {
+    transport: &'a T,
+    source_path: Option<P>,
+    destination: DocumentId,
+    source_revision: Option<&'a Revision>,
+    destination_revision: Option<&'a Revision>,
+}
+
+impl<'a, T: Transport + 'a, P: IntoDocumentPath> CopyDocument<'a, T, P> {
+    #[doc(hidden)]
+    pub fn new<D>(transport: &'a T, source_path: P, destination: D) -> Self
+    where
+        D: Into<DocumentId>,
+    {
+        CopyDocument {
+            transport: transport,
+            source_path: Some(source_path),
+            destination: destination.into(),
+            source_revision: None,
+            destination_revision: None,
+        }
+    }
+
+    /// Modifies the action to copy the given revision of the source document
+    /// instead of its current revision.
+    pub fn with_source_revision(mut self, revision: &'a Revision) -> Self {
+        self.source_revision = Some(revision);
+        self
+    }
+
+    /// Modifies the action to overwrite the destination document, which must
+    /// exist and be at the given revision.
+    pub fn with_destination_revision(mut self, revision: &'a Revision) -> Self {
+        self.destination_revision = Some(revision);
+        self
+    }
+
+    /// Executes the action and waits for the result.
+    pub fn run(mut self) -> Result<(DocumentId, Revision), Error> {
+        self.transport.send(
+            try!(self.make_request()),
+            JsonResponseDecoder::new(handle_response),
+        )
+    }
+
+    fn make_request(&mut self) -> Result<Request, Error> {
+
+        let source_path = try!(
+            std::mem::replace(&mut self.source_path, None)
+                .unwrap()
+                .into_document_path()
+        );
+
+        let destination = match self.destination_revision {
+            None => self.destination.percent_encoded(),
+            Some(revision) => format!("{}?rev={}", self.destination.percent_encoded(), revision),
+        };
+
+        let request = self.transport
+            .copy(source_path.iter())
+            .with_accept_json()
+            .with_destination(destination);
+
+        let request = match self.source_revision {
+            None => request,
+            Some(revision) => request.with_query(RevisionQueryKey, revision),
+        };
+
+        Ok(request)
+    }
+}
+
+fn handle_response(response: JsonResponse) -> Result<(DocumentId, Revision), Error> {
+    match response.status_code() {
+        StatusCode::Created | StatusCode::Accepted => {
+            let content: WriteDocumentResponse = try!(response.decode_content());
+            Ok((content.doc_id, content.revision))
+        }
+        StatusCode::Conflict => Err(Error::document_conflict(&response)),
+        StatusCode::NotFound => Err(Error::not_found(&response)),
+        StatusCode::Unauthorized => Err(Error::unauthorized(&response)),
+        _ => Err(Error::server_response(&response)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use {DocumentId, Error, Revision};
+    use transport::{JsonResponseBuilder, MockTransport, StatusCode, Transport};
+
+    #[test]
+    fn make_request_default() {
+
+        let transport = MockTransport::new();
+        let expected = transport
+            .copy(vec!["foo", "bar"])
+            .with_accept_json()
+            .with_destination("qux");
+
+        let got = {
+            let mut action = CopyDocument::new(&transport, "/foo/bar", "qux");
+            action.make_request().unwrap()
+        };
+
+        assert_eq!(expected, got);
+    }
+
+    #[test]
+    fn make_request_percent_encodes_destination() {
+
+        let transport = MockTransport::new();
+        let expected = transport
+            .copy(vec!["foo", "bar"])
+            .with_accept_json()
+            .with_destination("_design/qux%3F%20quux");
+
+        let got = {
+            let mut action = CopyDocument::new(&transport, "/foo/bar", "_design/qux? quux");
+            action.make_request().unwrap()
+        };
+
+        assert_eq!(expected, got);
+    }
+
+    #[test]
+    fn make_request_with_source_revision() {
+
+        let transport = MockTransport::new();
+        let expected = transport
+            .copy(vec!["foo", "bar"])
+            .with_accept_json()
+            .with_destination("qux")
+            .with_query_literal("rev", "1-1234567890abcdef1234567890abcdef");
+
+        let got = {
+            let rev = Revision::parse("1-1234567890abcdef1234567890abcdef").unwrap();
+            let mut action = CopyDocument::new(&transport, "/foo/bar", "qux").with_source_revision(&rev);
+            action.make_request().unwrap()
+        };
+
+        assert_eq!(expected, got);
+    }
+
+    #[test]
+    fn make_request_with_destination_revision() {
+
+        let transport = MockTransport::new();
+        let expected = transport
+            .copy(vec!["foo", "bar"])
+            .with_accept_json()
+            .with_destination("qux?rev=2-abcdef1234567890abcdef1234567890");
+
+        let got = {
+            let rev = Revision::parse("2-abcdef1234567890abcdef1234567890").unwrap();
+            let mut action = CopyDocument::new(&transport, "/foo/bar", "qux").with_destination_revision(&rev);
+            action.make_request().unwrap()
+        };
+
+        assert_eq!(expected, got);
+    }
+
+    #[test]
+    fn handle_response_created() {
+
+        let response = JsonResponseBuilder::new(StatusCode::Created)
+            .with_json_content_raw(
+                r#"{"ok":true,"id":"qux","rev":"1-1234567890abcdef1234567890abcdef"}"#,
+            )
+            .unwrap();
+
+        let expected = (
+            DocumentId::from("qux"),
+            Revision::parse("1-1234567890abcdef1234567890abcdef").unwrap(),
+        );
+        let got = super::handle_response(response).unwrap();
+        assert_eq!(expected, got);
+    }
+
+    #[test]
+    fn handle_response_conflict() {
+
+        let response = JsonResponseBuilder::new(StatusCode::Conflict)
+            .with_json_content_raw(
+                r#"{"error":"conflict","reason":"Document update conflict."}"#,
+            )
+            .unwrap();
+
+        match super::handle_response(response) {
+            Err(Error::DocumentConflict(ref error_response))
+                if error_response.error() == "conflict" && error_response.reason() == "Document update conflict." => (),
+            x @ _ => unexpected_result!(x),
+        }
+    }
+
+    #[test]
+    fn handle_response_not_found() {
+
+        let response = JsonResponseBuilder::new(StatusCode::NotFound)
+            .with_json_content_raw(r#"{"error":"not_found","reason":"missing"}"#)
+            .unwrap();
+
+        match super::handle_response(response) {
+            Err(Error::NotFound(ref error_response))
+                if error_response.error() == "not_found" && error_response.reason() == "missing" => (),
+            x @ _ => unexpected_result!(x),
+        }
+    }
+
+    #[test]
+    fn handle_response_unauthorized() {
+
+        let response = JsonResponseBuilder::new(StatusCode::Unauthorized)
+            .with_json_content_raw(
+                r#"{"error":"unauthorized","reason":"Authentication required."}"#,
+            )
+            .unwrap();
+
+        match super::handle_response(response) {
+            Err(Error::Unauthorized(ref error_response))
+                if error_response.error() == "unauthorized" && error_response.reason() == "Authentication required." =>
+                (),
+            x @ _ => unexpected_result!(x),
+        }
+    }
+}
@@ -1,4 +1,4 @@
-use {DocumentId, DocumentPath, Error, IntoDatabasePath, Revision, serde, std};
+use {DocumentId, DocumentIdGenerator, DocumentPath, Error, IntoDatabasePath, Revision, UuidPool, serde, std};
 use document::WriteDocumentResponse;
 use transport::{JsonResponse, JsonResponseDecoder, Request, StatusCode, Transport};
 
@@ -12,6 +12,7 @@ where
     db_path: Option<P>,
     content: &'a C,
     doc_id: Option<DocumentId>,
+    uuid_pool: Option<&'a UuidPool>,
 }
 
 impl<'a, C, P, T> CreateDocument<'a, T, P, C>
@@ -27,6 +28,7 @@ where
             db_path: Some(db_path),
             content: content,
             doc_id: None,
+            uuid_pool: None,
         }
     }
 
@@ -38,7 +40,78 @@ where
         self
     }
 
+    /// Modifies the action to take the document's id from the given pool if
+    /// the id is not set explicitly.
+    ///
+    /// The action takes the id from the pool when it runs or, beforehand, when
+    /// the application calls the `assign_document_id` method. Either may read
+    /// UUIDs from the server.
+    ///
+    /// With a document id, the action creates the document via `PUT` instead
+    /// of `POST`. See `UuidPool` for how this helps with retrying.
+    ///
+    pub fn with_uuid_pool(mut self, uuid_pool: &'a UuidPool) -> Self {
+        self.uuid_pool = Some(uuid_pool);
+        self
+    }
+
+    /// Modifies the action to take the document's id from the given generator
+    /// if the id is not set explicitly.
+    ///
+    /// Unlike a `UuidPool`, a generator creates ids locally, without a round
+    /// trip to the server. If the action has both a generator and a pool then
+    /// the generator takes precedence.
+    ///
+    /// The action takes the id immediately, when this method is called, and
+    /// the application may read the id via the `document_id` method—e.g., to
+    /// retry with the same id.
+    ///
+    pub fn with_document_id_generator(mut self, doc_id_generator: &'a DocumentIdGenerator) -> Self {
+        if self.doc_id.is_none() {
+            self.doc_id = Some(doc_id_generator.next_id());
+        }
+        self
+    }
+
+    /// Returns the id the action will create the document with, if the id is
+    /// already assigned.
+    ///
+    /// The id is assigned if the application sets it explicitly, if the
+    /// action takes it from a `DocumentIdGenerator`, or if the application
+    /// calls the `assign_document_id` method.
+    ///
+    pub fn document_id(&self) -> Option<&DocumentId> {
+        self.doc_id.as_ref()
+    }
+
+    /// Assigns the document's id now, before running the action, and returns
+    /// the id.
+    ///
+    /// If the id isn't yet assigned then the action takes it from its
+    /// `UuidPool`, which may read UUIDs from the server—and fail. The result
+    /// is `None` if the action has no id and no pool, in which case the server
+    /// assigns the id when the action runs.
+    ///
+    /// Knowing the id beforehand lets the application retry safely. After a
+    /// failure, such as a network error, the application may retry creating
+    /// the document with the same id, via the `with_document_id` method. Such
+    /// a retry either creates the document or fails with
+    /// `Error::DocumentConflict` because the first attempt created the
+    /// document after all.
+    ///
+    pub fn assign_document_id(&mut self) -> Result<Option<DocumentId>, Error> {
+        if self.doc_id.is_none() {
+            if let Some(uuid_pool) = self.uuid_pool {
+                self.doc_id = Some(DocumentId::from(try!(uuid_pool.next_uuid(self.transport))));
+            }
+        }
+        Ok(self.doc_id.clone())
+    }
+
     pub fn run(mut self) -> Result<(DocumentId, Revision), Error> {
+
+        try!(self.assign_document_id());
+
         self.transport.send(
             try!(self.make_request()),
             JsonResponseDecoder::new(handle_response),
@@ -84,7 +157,7 @@ fn handle_response(response: JsonResponse) -> Result<(DocumentId, Revision), Err
 mod tests {
 
     use super::*;
-    use {DocumentId, Error, Revision, serde_json};
+    use {DocumentId, DocumentIdGenerator, Error, NormalDocumentName, Revision, serde_json};
     use transport::{JsonResponseBuilder, MockTransport, StatusCode, Transport};
 
     #[test]
@@ -132,6 +205,58 @@ mod tests {
         assert_eq!(expected, got);
     }
 
+    #[test]
+    fn document_id_default() {
+        let doc_content = serde_json::builder::ObjectBuilder::new().build();
+        let transport = MockTransport::new();
+        let action = CreateDocument::new(&transport, "/foo", &doc_content);
+        assert_eq!(None, action.document_id());
+    }
+
+    #[test]
+    fn document_id_explicit() {
+        let doc_content = serde_json::builder::ObjectBuilder::new().build();
+        let transport = MockTransport::new();
+        let action = CreateDocument::new(&transport, "/foo", &doc_content).with_document_id("bar");
+        assert_eq!(Some(&DocumentId::from("bar")), action.document_id());
+    }
+
+    #[test]
+    fn document_id_generated() {
+
+        struct FixedGenerator;
+
+        impl DocumentIdGenerator for FixedGenerator {
+            fn next_name(&self) -> NormalDocumentName {
+                NormalDocumentName::from("bar")
+            }
+        }
+
+        let doc_content = serde_json::builder::ObjectBuilder::new().build();
+        let transport = MockTransport::new();
+        let generator = FixedGenerator;
+
+        let action = CreateDocument::new(&transport, "/foo", &doc_content).with_document_id_generator(&generator);
+        assert_eq!(Some(&DocumentId::from("bar")), action.document_id());
+
+        let action = CreateDocument::new(&transport, "/foo", &doc_content)
+            .with_document_id("qux")
+            .with_document_id_generator(&generator);
+        assert_eq!(Some(&DocumentId::from("qux")), action.document_id());
+    }
+
+    #[test]
+    fn assign_document_id_without_pool() {
+        let doc_content = serde_json::builder::ObjectBuilder::new().build();
+        let transport = MockTransport::new();
+
+        let mut action = CreateDocument::new(&transport, "/foo", &doc_content);
+        assert_eq!(None, action.assign_document_id().unwrap());
+
+        let mut action = CreateDocument::new(&transport, "/foo", &doc_content).with_document_id("bar");
+        assert_eq!(Some(DocumentId::from("bar")), action.assign_document_id().unwrap());
+    }
+
     #[test]
     fn handle_response_created() {
 
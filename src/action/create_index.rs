@@ -0,0 +1,250 @@
+//! Defines an action for creating a Mango index.
+
+use {DesignDocumentName, Error, IntoDatabasePath, serde_json, std};
+use mango::{IndexCreation, IndexDefinition};
+use transport::{JsonResponse, JsonResponseDecoder, Request, StatusCode, Transport};
+
+/// Creates a Mango index on the CouchDB server.
+///
+/// Chill creates the index by sending an HTTP request to the CouchDB server to
+/// `POST` to the database's `_index` path. If an identical index already
+/// exists then CouchDB leaves it as is. Either way, the result is an
+/// `IndexCreation` identifying the index.
+///
+/// By default, CouchDB chooses the index's name and design document. The
+/// `with_name` and `with_design_document` methods set these explicitly.
+///
+/// # Errors
+///
+/// The following are _some_ errors that may occur when creating an index.
+///
+/// <table>
+/// <tr>
+///  <td><code>Error::NotFound</code></td>
+///  <td>The database does not exist.</td>
+/// </tr>
+/// <tr>
+///  <td><code>Error::Unauthorized</code></td>
+///  <td>The client lacks permission to create the index.</td>
+/// </tr>
+/// </table>
+///
+/// # Examples
+///
+/// ```no_run
+/// extern crate chill;
+///
+/// use chill::mango::{IndexDefinition, SortOrder};
+///
+/// let client = chill::Client::new("http://localhost:5984").unwrap();
+///
+/// let definition = IndexDefinition::new().with_sort_field("home_runs", SortOrder::Descending);
+///
+/// let creation = client.create_index("/baseball", definition)
+///                      .with_name("by_home_runs")
+///                      .with_design_document("stats")
+///                      .run()
+///                      .unwrap();
+///
+/// assert_eq!("by_home_runs", creation.index_name());
+/// ```
+///
+pub struct CreateIndex<'a, T: Transport + 'a, P: IntoDatabasePath> {
+    transport: &'a T,
+    db_path: Option<P>,
+    definition: IndexDefinition,
+    name: Option<String>,
+    ddoc_name: Option<DesignDocumentName>,
+}
+
+impl<'a, P: IntoDatabasePath, T: Transport + 'a> CreateIndex<'a, T, P> {
+    #[doc(hidden)]
+    pub fn new(transport: &'a T, db_path: P, definition: IndexDefinition) -> Self {
+        CreateIndex {
+            transport: transport,
+            db_path: Some(db_path),
+            definition: definition,
+            name: None,
+            ddoc_name: None,
+        }
+    }
+
+    /// Modifies the action to give the index the given name.
+    pub fn with_name<N: Into<String>>(mut self, name: N) -> Self {
+        self.name = Some(name.into());
+        self
+    }
+
+    /// Modifies the action to store the index in the given design document.
+    pub fn with_design_document<D: Into<DesignDocumentName>>(mut self, ddoc_name: D) -> Self {
+        self.ddoc_name = Some(ddoc_name.into());
+        self
+    }
+
+    pub fn run(mut self) -> Result<IndexCreation, Error> {
+        self.transport.send(
+            try!(self.make_request()),
+            JsonResponseDecoder::new(handle_response),
+        )
+    }
+
+    fn make_request(&mut self) -> Result<Request, Error> {
+
+        let db_path = try!(
+            std::mem::replace(&mut self.db_path, None)
+                .unwrap()
+                .into_database_path()
+        );
+
+        let body = serde_json::builder::ObjectBuilder::new()
+            .insert("index", &self.definition)
+            .insert("type", "json");
+
+        let body = match self.name {
+            None => body,
+            Some(ref name) => body.insert("name", name),
+        };
+
+        let body = match self.ddoc_name {
+            None => body,
+            Some(ref ddoc_name) => body.insert("ddoc", ddoc_name),
+        };
+
+        let mut path = db_path.iter().collect::<Vec<_>>();
+        path.push("_index");
+
+        self.transport
+            .post(path)
+            .with_accept_json()
+            .with_json_content(&body.build())
+    }
+}
+
+fn handle_response(response: JsonResponse) -> Result<IndexCreation, Error> {
+    match response.status_code() {
+        StatusCode::Ok => Ok(try!(response.decode_content())),
+        StatusCode::NotFound => Err(Error::not_found(&response)),
+        StatusCode::Unauthorized => Err(Error::unauthorized(&response)),
+        _ => Err(Error::server_response(&response)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use {DesignDocumentName, Error, serde_json};
+    use mango::{IndexCreation, IndexDefinition};
+    use transport::{JsonResponseBuilder, MockTransport, StatusCode, Transport};
+
+    #[test]
+    fn make_request_default() {
+
+        let transport = MockTransport::new();
+
+        let body = serde_json::builder::ObjectBuilder::new()
+            .insert_object("index", |x| {
+                x.insert_array("fields", |x| x.push_object(|x| x.insert("name", "asc")))
+            })
+            .insert("type", "json")
+            .build();
+
+        let expected = transport
+            .post(vec!["baseball", "_index"])
+            .with_accept_json()
+            .with_json_content(&body)
+            .unwrap();
+
+        let got = {
+            let mut action = CreateIndex::new(&transport, "/baseball", IndexDefinition::new().with_field("name"));
+            action.make_request().unwrap()
+        };
+
+        assert_eq!(expected, got);
+    }
+
+    #[test]
+    fn make_request_with_name_and_design_document() {
+
+        let transport = MockTransport::new();
+
+        let body = serde_json::builder::ObjectBuilder::new()
+            .insert_object("index", |x| {
+                x.insert_array("fields", |x| x.push_object(|x| x.insert("name", "asc")))
+            })
+            .insert("type", "json")
+            .insert("name", "by_name")
+            .insert("ddoc", "stats")
+            .build();
+
+        let expected = transport
+            .post(vec!["baseball", "_index"])
+            .with_accept_json()
+            .with_json_content(&body)
+            .unwrap();
+
+        let got = {
+            let mut action = CreateIndex::new(&transport, "/baseball", IndexDefinition::new().with_field("name"))
+                .with_name("by_name")
+                .with_design_document("stats");
+            action.make_request().unwrap()
+        };
+
+        assert_eq!(expected, got);
+    }
+
+    #[test]
+    fn handle_response_ok_created() {
+
+        let response = JsonResponseBuilder::new(StatusCode::Ok)
+            .with_json_content_raw(r#"{"result":"created","id":"_design/stats","name":"by_name"}"#)
+            .unwrap();
+
+        let expected = IndexCreation::Created(DesignDocumentName::from("stats"), String::from("by_name"));
+        let got = super::handle_response(response).unwrap();
+        assert_eq!(expected, got);
+    }
+
+    #[test]
+    fn handle_response_ok_exists() {
+
+        let response = JsonResponseBuilder::new(StatusCode::Ok)
+            .with_json_content_raw(r#"{"result":"exists","id":"_design/stats","name":"by_name"}"#)
+            .unwrap();
+
+        let expected = IndexCreation::Exists(DesignDocumentName::from("stats"), String::from("by_name"));
+        let got = super::handle_response(response).unwrap();
+        assert_eq!(expected, got);
+    }
+
+    #[test]
+    fn handle_response_not_found() {
+
+        let response = JsonResponseBuilder::new(StatusCode::NotFound)
+            .with_json_content_raw(r#"{"error":"not_found","reason":"Database does not exist."}"#)
+            .unwrap();
+
+        match super::handle_response(response) {
+            Err(Error::NotFound(ref error_response))
+                if error_response.error() == "not_found" && error_response.reason() == "Database does not exist." => (),
+            x @ _ => unexpected_result!(x),
+        }
+    }
+
+    #[test]
+    fn handle_response_unauthorized() {
+
+        let response = JsonResponseBuilder::new(StatusCode::Unauthorized)
+            .with_json_content_raw(
+                r#"{"error":"unauthorized","reason":"Authentication required."}"#,
+            )
+            .unwrap();
+
+        match super::handle_response(response) {
+            Err(Error::Unauthorized(ref error_response))
+                if error_response.error() == "unauthorized" && error_response.reason() == "Authentication required." =>
+                (),
+            x @ _ => unexpected_result!(x),
+        }
+    }
+}
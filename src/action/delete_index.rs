@@ -0,0 +1,156 @@
+//! Defines an action for deleting a Mango index.
+
+use {DesignDocumentName, Error, IntoDatabasePath, std};
+use transport::{JsonResponse, JsonResponseDecoder, Request, StatusCode, Transport};
+
+/// Deletes a Mango index from the CouchDB server.
+///
+/// Chill deletes the index by sending an HTTP request to the CouchDB server to
+/// `DELETE` the index's path within the database's `_index` path. The index is
+/// identified by its design document and name—e.g., as returned by the
+/// `CreateIndex` and `ListIndexes` actions.
+///
+/// # Errors
+///
+/// The following are _some_ errors that may occur when deleting an index.
+///
+/// <table>
+/// <tr>
+///  <td><code>Error::NotFound</code></td>
+///  <td>The database or index does not exist.</td>
+/// </tr>
+/// <tr>
+///  <td><code>Error::Unauthorized</code></td>
+///  <td>The client lacks permission to delete the index.</td>
+/// </tr>
+/// </table>
+///
+/// # Examples
+///
+/// ```no_run
+/// extern crate chill;
+///
+/// let client = chill::Client::new("http://localhost:5984").unwrap();
+/// client.delete_index("/baseball", "stats", "by_home_runs").run().unwrap();
+/// ```
+///
+pub struct DeleteIndex<'a, T: Transport + 'a, P: IntoDatabasePath> {
+    transport: &'a T,
+    db_path: Option<P>,
+    ddoc_name: DesignDocumentName,
+    index_name: String,
+}
+
+impl<'a, P: IntoDatabasePath, T: Transport + 'a> DeleteIndex<'a, T, P> {
+    #[doc(hidden)]
+    pub fn new<D, N>(transport: &'a T, db_path: P, ddoc_name: D, index_name: N) -> Self
+    where
+        D: Into<DesignDocumentName>,
+        N: Into<String>,
+    {
+        DeleteIndex {
+            transport: transport,
+            db_path: Some(db_path),
+            ddoc_name: ddoc_name.into(),
+            index_name: index_name.into(),
+        }
+    }
+
+    pub fn run(mut self) -> Result<(), Error> {
+        self.transport.send(
+            try!(self.make_request()),
+            JsonResponseDecoder::new(handle_response),
+        )
+    }
+
+    fn make_request(&mut self) -> Result<Request, Error> {
+
+        let db_path = try!(
+            std::mem::replace(&mut self.db_path, None)
+                .unwrap()
+                .into_database_path()
+        );
+
+        let path = db_path.iter().chain(vec![
+            "_index",
+            "_design",
+            self.ddoc_name.as_ref(),
+            "json",
+            self.index_name.as_str(),
+        ]);
+
+        Ok(self.transport.delete(path).with_accept_json())
+    }
+}
+
+fn handle_response(response: JsonResponse) -> Result<(), Error> {
+    match response.status_code() {
+        StatusCode::Ok => Ok(()),
+        StatusCode::NotFound => Err(Error::not_found(&response)),
+        StatusCode::Unauthorized => Err(Error::unauthorized(&response)),
+        _ => Err(Error::server_response(&response)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use Error;
+    use transport::{JsonResponseBuilder, MockTransport, StatusCode, Transport};
+
+    #[test]
+    fn make_request_default() {
+
+        let transport = MockTransport::new();
+        let expected = transport
+            .delete(vec!["baseball", "_index", "_design", "stats", "json", "by_home_runs"])
+            .with_accept_json();
+
+        let got = {
+            let mut action = DeleteIndex::new(&transport, "/baseball", "stats", "by_home_runs");
+            action.make_request().unwrap()
+        };
+
+        assert_eq!(expected, got);
+    }
+
+    #[test]
+    fn handle_response_ok() {
+        let response = JsonResponseBuilder::new(StatusCode::Ok)
+            .with_json_content_raw(r#"{"ok":true}"#)
+            .unwrap();
+        super::handle_response(response).unwrap();
+    }
+
+    #[test]
+    fn handle_response_not_found() {
+
+        let response = JsonResponseBuilder::new(StatusCode::NotFound)
+            .with_json_content_raw(r#"{"error":"not_found","reason":"Index not found"}"#)
+            .unwrap();
+
+        match super::handle_response(response) {
+            Err(Error::NotFound(ref error_response))
+                if error_response.error() == "not_found" && error_response.reason() == "Index not found" => (),
+            x @ _ => unexpected_result!(x),
+        }
+    }
+
+    #[test]
+    fn handle_response_unauthorized() {
+
+        let response = JsonResponseBuilder::new(StatusCode::Unauthorized)
+            .with_json_content_raw(
+                r#"{"error":"unauthorized","reason":"Authentication required."}"#,
+            )
+            .unwrap();
+
+        match super::handle_response(response) {
+            Err(Error::Unauthorized(ref error_response))
+                if error_response.error() == "unauthorized" && error_response.reason() == "Authentication required." =>
+                (),
+            x @ _ => unexpected_result!(x),
+        }
+    }
+}
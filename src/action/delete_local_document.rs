@@ -0,0 +1,173 @@
+//! Defines an action for deleting a local document from the CouchDB server.
+
+use {DocumentId, DocumentPath, Error, IntoDatabasePath, LocalDocumentName, LocalRevision, std};
+use action::query_keys::*;
+use local_document::LocalRevisionJsonable;
+use transport::{JsonResponse, JsonResponseDecoder, Request, StatusCode, Transport};
+
+/// Deletes a local document from the CouchDB server.
+///
+/// Chill deletes the local document by sending an HTTP request to `DELETE` the
+/// document's path—i.e., `/{db}/_local/{name}`—at the given revision. The
+/// server keeps no tombstone for a deleted local document, and the returned
+/// revision is the one the server reports for the deletion—usually `0-0`.
+///
+/// # Errors
+///
+/// The following are _some_ errors that may occur when deleting a local
+/// document.
+///
+/// <table>
+/// <tr>
+///  <td><code>Error::DocumentConflict</code></td>
+///  <td>The revision is not current.</td>
+/// </tr>
+/// <tr>
+///  <td><code>Error::NotFound</code></td>
+///  <td>The local document does not exist.</td>
+/// </tr>
+/// <tr>
+///  <td><code>Error::Unauthorized</code></td>
+///  <td>The client lacks permission to delete the local document.</td>
+/// </tr>
+/// </table>
+///
+/// # Examples
+///
+/// ```no_run
+/// extern crate chill;
+///
+/// let client = chill::Client::new("http://localhost:5984").unwrap();
+///
+/// let doc = client.read_local_document("/baseball", "checkpoint").run().unwrap();
+/// client
+///     .delete_local_document("/baseball", "checkpoint", doc.revision())
+///     .run()
+///     .unwrap();
+/// ```
+///
+pub struct DeleteLocalDocument<'a, T: Transport + 'a, P: IntoDatabasePath> {
+    transport: &'a T,
+    db_path: Option<P>,
+    name: LocalDocumentName,
+    revision: &'a LocalRevision,
+}
+
+impl<'a, T: Transport + 'a, P: IntoDatabasePath> DeleteLocalDocument<'a, T, P> {
+    #[doc(hidden)]
+    pub fn new<N>(transport: &'a T, db_path: P, name: N, revision: &'a LocalRevision) -> Self
+    where
+        N: Into<LocalDocumentName>,
+    {
+        DeleteLocalDocument {
+            transport: transport,
+            db_path: Some(db_path),
+            name: name.into(),
+            revision: revision,
+        }
+    }
+
+    /// Executes the action and waits for the result.
+    pub fn run(mut self) -> Result<LocalRevision, Error> {
+        self.transport.send(
+            try!(self.make_request()),
+            JsonResponseDecoder::new(handle_response),
+        )
+    }
+
+    fn make_request(&mut self) -> Result<Request, Error> {
+        let db_path = try!(
+            std::mem::replace(&mut self.db_path, None)
+                .unwrap()
+                .into_database_path()
+        );
+        let doc_path = DocumentPath::from((db_path, DocumentId::from(self.name.clone())));
+        Ok(
+            self.transport
+                .delete(doc_path.iter())
+                .with_accept_json()
+                .with_query(RevisionQueryKey, self.revision),
+        )
+    }
+}
+
+fn handle_response(response: JsonResponse) -> Result<LocalRevision, Error> {
+    match response.status_code() {
+        StatusCode::Ok => {
+            let content: LocalRevisionJsonable = try!(response.decode_content());
+            Ok(content.revision)
+        }
+        StatusCode::Conflict => Err(Error::document_conflict(&response)),
+        StatusCode::NotFound => Err(Error::not_found(&response)),
+        StatusCode::Unauthorized => Err(Error::unauthorized(&response)),
+        _ => Err(Error::server_response(&response)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use {Error, LocalRevision};
+    use transport::{JsonResponseBuilder, MockTransport, StatusCode, Transport};
+
+    #[test]
+    fn make_request_default() {
+
+        let transport = MockTransport::new();
+        let expected = transport
+            .delete(vec!["foo", "_local", "bar"])
+            .with_accept_json()
+            .with_query_literal("rev", "0-3");
+
+        let got = {
+            let rev = LocalRevision::parse("0-3").unwrap();
+            let mut action = DeleteLocalDocument::new(&transport, "/foo", "bar", &rev);
+            action.make_request().unwrap()
+        };
+
+        assert_eq!(expected, got);
+    }
+
+    #[test]
+    fn handle_response_ok() {
+
+        let response = JsonResponseBuilder::new(StatusCode::Ok)
+            .with_json_content_raw(r#"{"ok":true,"id":"_local/bar","rev":"0-0"}"#)
+            .unwrap();
+
+        let expected = LocalRevision::parse("0-0").unwrap();
+        let got = super::handle_response(response).unwrap();
+        assert_eq!(expected, got);
+    }
+
+    #[test]
+    fn handle_response_conflict() {
+
+        let response = JsonResponseBuilder::new(StatusCode::Conflict)
+            .with_json_content_raw(
+                r#"{"error":"conflict","reason":"Document update conflict."}"#,
+            )
+            .unwrap();
+
+        match super::handle_response(response) {
+            Err(Error::DocumentConflict(ref error_response))
+                if error_response.error() == "conflict" && error_response.reason() == "Document update conflict." => (),
+            x @ _ => unexpected_result!(x),
+        }
+    }
+
+    #[test]
+    fn handle_response_not_found() {
+
+        let response = JsonResponseBuilder::new(StatusCode::NotFound)
+            .with_json_content_raw(r#"{"error":"not_found","reason":"missing"}"#)
+            .unwrap();
+
+        match super::handle_response(response) {
+            Err(Error::NotFound(ref error_response))
+                if error_response.error() == "not_found" && error_response.reason() == "missing" => (),
+            x @ _ => unexpected_result!(x),
+        }
+    }
+}
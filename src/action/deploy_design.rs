@@ -0,0 +1,261 @@
+//! Defines an action for deploying a design document, writing it only if
+//! its content changed.
+
+use {DatabaseName, Deployment, Design, DesignDocumentName, Document, DocumentPath, Error, IntoDesignDocumentPath,
+     Revision, ViewPath, serde_json};
+use action::{CreateDocument, DeleteDocument, ExecuteView, ReadDocument, UpdateDocument};
+use transport::Transport;
+
+/// Action to deploy a design document, writing it only if its content changed.
+///
+/// `DeployDesign` reads the design document currently on the server and
+/// compares its content with the given `Design`. If the two are equal then the
+/// action writes nothing and returns `Deployment::Unchanged`. Otherwise, the
+/// action creates or updates the design document.
+///
+/// # Staging
+///
+/// Changing a design document's views causes CouchDB to rebuild the views'
+/// index, and queries block until the rebuild is done. To avoid this downtime,
+/// the application may set a staging name via the `with_staging` method. The
+/// action then:
+///
+/// 1. Writes the new content to the staging design document,
+/// 2. Queries one of the staging design document's views, which blocks until
+///    CouchDB has built the new index,
+/// 3. Writes the new content to the target design document, which reuses the
+///    already built index, and
+/// 4. Deletes the staging design document.
+///
+/// # Errors
+///
+/// The following are _some_ errors that may occur when deploying a design
+/// document.
+///
+/// <table>
+/// <tr>
+///  <td><code>Error::DocumentConflict</code></td>
+///  <td>The design document was modified by another client during
+///  deployment.</td>
+/// </tr>
+/// <tr>
+///  <td><code>Error::NotFound</code></td>
+///  <td>The database does not exist.</td>
+/// </tr>
+/// <tr>
+///  <td><code>Error::Unauthorized</code></td>
+///  <td>The client lacks permission to write the design document.</td>
+/// </tr>
+/// </table>
+///
+/// # Examples
+///
+/// The following program deploys a design document whose functions are stored
+/// in a directory.
+///
+/// ```no_run
+/// extern crate chill;
+///
+/// let client = chill::Client::new("http://localhost:5984").unwrap();
+/// let design = chill::Design::from_directory("couchdb/baseball").unwrap();
+///
+/// let deployment = client.deploy_design("/baseball/_design/stats", &design)
+///                        .with_staging("stats_staging")
+///                        .run()
+///                        .unwrap();
+///
+/// println!("Design document is at revision {}", deployment.revision());
+/// ```
+///
+pub struct DeployDesign<'a, T: Transport + 'a, P: IntoDesignDocumentPath> {
+    transport: &'a T,
+    ddoc_path: Option<P>,
+    design: &'a Design,
+    staging_name: Option<DesignDocumentName>,
+}
+
+impl<'a, T: Transport + 'a, P: IntoDesignDocumentPath> DeployDesign<'a, T, P> {
+    #[doc(hidden)]
+    pub fn new(transport: &'a T, ddoc_path: P, design: &'a Design) -> Self {
+        DeployDesign {
+            transport: transport,
+            ddoc_path: Some(ddoc_path),
+            design: design,
+            staging_name: None,
+        }
+    }
+
+    /// Modifies the action to stage the design document under the given name
+    /// and build its view index before writing the target design document.
+    ///
+    /// Any existing design document with the staging name is overwritten and
+    /// then deleted after the target design document is written—or after the
+    /// deployment fails.
+    ///
+    pub fn with_staging<N: Into<DesignDocumentName>>(mut self, staging_name: N) -> Self {
+        self.staging_name = Some(staging_name.into());
+        self
+    }
+
+    /// Executes the action and waits for the result.
+    pub fn run(mut self) -> Result<Deployment, Error> {
+
+        let ddoc_path = try!(
+            std::mem::replace(&mut self.ddoc_path, None)
+                .unwrap()
+                .into_design_document_path()
+        );
+        let db_name = ddoc_path.database_name().clone();
+        let doc_path = DocumentPath::from((db_name.clone(), ddoc_path.design_document_name().clone()));
+
+        let existing = try!(read_existing(self.transport, &doc_path));
+
+        if let Some(ref doc) = existing {
+            if try!(is_unchanged(doc, self.design)) {
+                return Ok(Deployment::Unchanged(doc.revision().clone()));
+            }
+        }
+
+        let staging = match self.staging_name {
+            None => None,
+            Some(ref staging_name) => {
+                let staging_path = DocumentPath::from((db_name.clone(), staging_name.clone()));
+                let staging_revision = try!(self.stage(&db_name, staging_name));
+                Some((staging_path, staging_revision))
+            }
+        };
+
+        let written = match existing {
+            None => write(self.transport, &doc_path, None, self.design).map(Deployment::Created),
+            Some(doc) => write(self.transport, &doc_path, Some(doc), self.design).map(Deployment::Updated),
+        };
+
+        // Delete the staging design document only after writing the target
+        // design document, so that the built index stays referenced throughout
+        // the deployment. Delete it even if that write fails, so that the
+        // staging design document isn't left behind on the server.
+
+        match staging {
+            None => written,
+            Some((staging_path, staging_revision)) => {
+                let deleted = DeleteDocument::new(self.transport, staging_path, &staging_revision).run();
+                let deployment = try!(written);
+                try!(deleted);
+                Ok(deployment)
+            }
+        }
+    }
+
+    // Writes the staging design document and builds its index, returning the
+    // staging design document's revision.
+    fn stage(&self, db_name: &DatabaseName, staging_name: &DesignDocumentName) -> Result<Revision, Error> {
+
+        let staging_path = DocumentPath::from((db_name.clone(), staging_name.clone()));
+        let staging_doc = try!(read_existing(self.transport, &staging_path));
+        let staging_revision = try!(write(self.transport, &staging_path, staging_doc, self.design));
+
+        // All views in a design document share one index, so querying any one
+        // view builds the index for all of them.
+
+        if let Some(view_name) = self.design.views.keys().next() {
+            let view_path = ViewPath::from((db_name.clone(), staging_name.clone(), view_name.clone()));
+            let built = ExecuteView::new(self.transport, view_path)
+                .with_reduce(false)
+                .with_limit(0)
+                .run();
+            if let Err(e) = built {
+                let _ = DeleteDocument::new(self.transport, staging_path, &staging_revision).run();
+                return Err(e);
+            }
+        }
+
+        Ok(staging_revision)
+    }
+}
+
+// Returns whether the existing design document has the same content as the
+// design. If the existing content doesn't decode as a `Design`—e.g., because a
+// field has an unexpected type—then the comparison falls back to JSON.
+fn is_unchanged(doc: &Document, design: &Design) -> Result<bool, Error> {
+    match doc.get_content::<Design>() {
+        Ok(content) => Ok(content == *design),
+        Err(Error::JsonDecode { .. }) => {
+            let content: serde_json::Value = try!(doc.get_content());
+            Ok(content == serde_json::to_value(design))
+        }
+        Err(e) => Err(e),
+    }
+}
+
+fn read_existing<T: Transport>(transport: &T, doc_path: &DocumentPath) -> Result<Option<Document>, Error> {
+    match ReadDocument::new(transport, doc_path.clone()).run() {
+        Ok(doc) => Ok(Some(doc)),
+        Err(Error::NotFound(..)) => Ok(None),
+        Err(e) => Err(e),
+    }
+}
+
+fn write<T: Transport>(
+    transport: &T,
+    doc_path: &DocumentPath,
+    existing: Option<Document>,
+    design: &Design,
+) -> Result<Revision, Error> {
+    match existing {
+        None => {
+            let (_doc_id, revision) = try!(
+                CreateDocument::new(transport, doc_path.database_name().clone(), design)
+                    .with_document_id(doc_path.document_id().clone())
+                    .run()
+            );
+            Ok(revision)
+        }
+        Some(mut doc) => {
+            try!(doc.set_content(design));
+            UpdateDocument::new(transport, &doc).run()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use {Design, DesignBuilder, Revision, ViewFunction, serde_json};
+    use document::DocumentBuilder;
+
+    fn make_design() -> Design {
+        DesignBuilder::new()
+            .insert_view("alpha", ViewFunction::new("function(doc) { emit(doc.key, doc.value); }"))
+            .unwrap()
+    }
+
+    #[test]
+    fn is_unchanged_same_design() {
+        let doc = DocumentBuilder::new(
+            "/foo/_design/bar",
+            Revision::parse("1-1234567890abcdef1234567890abcdef").unwrap(),
+        ).with_content(&make_design())
+            .unwrap();
+        assert!(super::is_unchanged(&doc, &make_design()).unwrap());
+    }
+
+    #[test]
+    fn is_unchanged_different_design() {
+        let doc = DocumentBuilder::new(
+            "/foo/_design/bar",
+            Revision::parse("1-1234567890abcdef1234567890abcdef").unwrap(),
+        ).with_content(&DesignBuilder::new().unwrap())
+            .unwrap();
+        assert!(!super::is_unchanged(&doc, &make_design()).unwrap());
+    }
+
+    #[test]
+    fn is_unchanged_undecodable_design() {
+        let doc = DocumentBuilder::new(
+            "/foo/_design/bar",
+            Revision::parse("1-1234567890abcdef1234567890abcdef").unwrap(),
+        ).build_content(|x| x.insert("views", 42))
+            .unwrap();
+        assert!(!super::is_unchanged(&doc, &make_design()).unwrap());
+    }
+}
@@ -0,0 +1,393 @@
+//! Defines an action for executing a list function.
+
+use {DesignDocumentName, Error, FunctionStream, IntoViewPath, ListName, serde, serde_json, std};
+use action::view_options::{GroupLevel, Inclusivity, ViewOptions};
+use transport::{Request, Transport};
+
+/// Executes a list function over a view on the CouchDB server and returns the
+/// result as a stream.
+///
+/// Chill executes the list function by sending an HTTP request to the CouchDB
+/// server to `GET` from the list function's path. The result is a
+/// `FunctionStream` from which the application reads the function's output,
+/// whatever its content type, as the server sends it.
+///
+/// By default, the list function and the view must be in the same design
+/// document. The `with_list_design_document` method allows the list function
+/// to be in a different design document.
+///
+/// The action accepts the same view options as `ExecuteView`—e.g., key ranges
+/// and limits. For more details about list functions, please see the CouchDB
+/// documentation.
+///
+/// # Errors
+///
+/// The following are _some_ errors that may occur when executing a list
+/// function.
+///
+/// <table>
+/// <tr>
+///  <td><code>Error::NotFound</code></td>
+///  <td>The database, design document, list function, or view does not
+///  exist.</td>
+/// </tr>
+/// <tr>
+///  <td><code>Error::Unauthorized</code></td>
+///  <td>The client lacks permission to execute the list function.</td>
+/// </tr>
+/// </table>
+///
+/// # Examples
+///
+/// The following program writes a CSV export of a view to standard output.
+///
+/// ```no_run
+/// extern crate chill;
+///
+/// let client = chill::Client::new("http://localhost:5984").unwrap();
+///
+/// let mut stream = client.execute_list("/baseball/_design/stats/_view/home_runs", "csv")
+///                        .with_descending(true)
+///                        .with_limit(100)
+///                        .run()
+///                        .unwrap();
+///
+/// std::io::copy(&mut stream, &mut std::io::stdout()).unwrap();
+/// ```
+///
+pub struct ExecuteList<'a, T, P, StartKey, EndKey>
+where
+    EndKey: serde::Serialize,
+    P: IntoViewPath,
+    StartKey: serde::Serialize,
+    T: Transport + 'a,
+{
+    transport: &'a T,
+    view_path: Option<P>,
+    list_name: ListName,
+    list_ddoc_name: Option<DesignDocumentName>,
+    options: ViewOptions<StartKey, EndKey>,
+    query: Vec<(String, String)>,
+}
+
+impl<'a, P, T> ExecuteList<'a, T, P, (), ()>
+where
+    P: IntoViewPath,
+    T: Transport + 'a,
+{
+    #[doc(hidden)]
+    pub fn new<L: Into<ListName>>(transport: &'a T, view_path: P, list_name: L) -> Self {
+        ExecuteList {
+            transport: transport,
+            view_path: Some(view_path),
+            list_name: list_name.into(),
+            list_ddoc_name: None,
+            options: ViewOptions::new(),
+            query: Vec::new(),
+        }
+    }
+}
+
+impl<'a, EndKey, P, StartKey, T> ExecuteList<'a, T, P, StartKey, EndKey>
+where
+    EndKey: serde::Serialize,
+    P: IntoViewPath,
+    StartKey: serde::Serialize,
+    T: Transport + 'a,
+{
+    /// Modifies the action to use the list function in the given design
+    /// document instead of the view's design document.
+    pub fn with_list_design_document<N: Into<DesignDocumentName>>(mut self, ddoc_name: N) -> Self {
+        self.list_ddoc_name = Some(ddoc_name.into());
+        self
+    }
+
+    /// Modifies the action to include the given query parameter in the
+    /// request, for use by the list function.
+    pub fn with_query_param<K: Into<String>, V: Into<String>>(mut self, key: K, value: V) -> Self {
+        self.query.push((key.into(), value.into()));
+        self
+    }
+
+    /// Modifies the action to explicitly reduce or not reduce the view.
+    ///
+    /// See `ExecuteView::with_reduce` for details.
+    ///
+    pub fn with_reduce(mut self, reduce: bool) -> Self {
+        self.options.reduce = Some(reduce);
+        self
+    }
+
+    /// Modifies the action to retrieve at most a given number of rows.
+    ///
+    /// See `ExecuteView::with_limit` for details.
+    ///
+    pub fn with_limit(mut self, limit: u64) -> Self {
+        self.options.limit = Some(limit);
+        self
+    }
+
+    /// Modifies the action to retrieve the view rows in descending order.
+    ///
+    /// See `ExecuteView::with_descending` for details.
+    ///
+    pub fn with_descending(mut self, descending: bool) -> Self {
+        self.options.descending = Some(descending);
+        self
+    }
+
+    pub fn with_exact_groups(mut self, yes_or_no: bool) -> Self {
+        self.options.group_level = Some(GroupLevel::Exact(yes_or_no));
+        self
+    }
+
+    pub fn with_group_level(mut self, group_level: u32) -> Self {
+        self.options.group_level = Some(GroupLevel::Number(group_level));
+        self
+    }
+
+    pub fn with_documents(mut self, yes_or_no: bool) -> Self {
+        self.options.include_docs = Some(yes_or_no);
+        self
+    }
+}
+
+impl<'a, P, T> ExecuteList<'a, T, P, (), ()>
+where
+    P: IntoViewPath,
+    T: Transport + 'a,
+{
+    /// Modifies the action to include only records with a key beginning with
+    /// a given prefix.
+    ///
+    /// See `ExecuteView::with_key_prefix` for details.
+    ///
+    pub fn with_key_prefix<K>(self, prefix: K) -> ExecuteList<'a, T, P, serde_json::Value, serde_json::Value>
+    where
+        K: serde::Serialize,
+    {
+        ExecuteList {
+            transport: self.transport,
+            view_path: self.view_path,
+            list_name: self.list_name,
+            list_ddoc_name: self.list_ddoc_name,
+            options: self.options.with_key_prefix(serde_json::to_value(&prefix)),
+            query: self.query,
+        }
+    }
+}
+
+impl<'a, EndKey, P, T> ExecuteList<'a, T, P, (), EndKey>
+where
+    EndKey: serde::Serialize,
+    P: IntoViewPath,
+    T: Transport + 'a,
+{
+    /// Modifies the action to include only records with a key greater than or
+    /// equal to a given key.
+    pub fn with_start_key<StartKey>(self, start_key: StartKey) -> ExecuteList<'a, T, P, StartKey, EndKey>
+    where
+        StartKey: serde::Serialize,
+    {
+        ExecuteList {
+            transport: self.transport,
+            view_path: self.view_path,
+            list_name: self.list_name,
+            list_ddoc_name: self.list_ddoc_name,
+            options: self.options.with_start_key(start_key),
+            query: self.query,
+        }
+    }
+}
+
+impl<'a, P, StartKey, T> ExecuteList<'a, T, P, StartKey, ()>
+where
+    P: IntoViewPath,
+    StartKey: serde::Serialize,
+    T: Transport + 'a,
+{
+    /// Modifies the action to include only records with a key less than or
+    /// equal to a given key.
+    pub fn with_end_key_inclusive<EndKey>(self, end_key: EndKey) -> ExecuteList<'a, T, P, StartKey, EndKey>
+    where
+        EndKey: serde::Serialize,
+    {
+        ExecuteList {
+            transport: self.transport,
+            view_path: self.view_path,
+            list_name: self.list_name,
+            list_ddoc_name: self.list_ddoc_name,
+            options: self.options.with_end_key(end_key, Inclusivity::Inclusive),
+            query: self.query,
+        }
+    }
+
+    /// Modifies the action to include only records with a key less than a given
+    /// key.
+    pub fn with_end_key_exclusive<EndKey>(self, end_key: EndKey) -> ExecuteList<'a, T, P, StartKey, EndKey>
+    where
+        EndKey: serde::Serialize,
+    {
+        ExecuteList {
+            transport: self.transport,
+            view_path: self.view_path,
+            list_name: self.list_name,
+            list_ddoc_name: self.list_ddoc_name,
+            options: self.options.with_end_key(end_key, Inclusivity::Exclusive),
+            query: self.query,
+        }
+    }
+}
+
+impl<'a, EndKey, P, StartKey, T> ExecuteList<'a, T, P, StartKey, EndKey>
+where
+    EndKey: serde::Serialize,
+    P: IntoViewPath,
+    StartKey: serde::Serialize,
+    T: Transport,
+{
+    pub fn run(mut self) -> Result<FunctionStream, Error> {
+        let response = try!(self.transport.send_streaming(try!(self.make_request())));
+        FunctionStream::from_streaming_response(response)
+    }
+
+    fn make_request(&mut self) -> Result<Request, Error> {
+
+        let view_path = try!(
+            std::mem::replace(&mut self.view_path, None)
+                .unwrap()
+                .into_view_path()
+        );
+
+        let view_ddoc_name = view_path.design_document_name();
+
+        let path: Vec<&str> = match self.list_ddoc_name {
+            None => vec![
+                view_path.database_name().as_ref(),
+                "_design",
+                view_ddoc_name.as_ref(),
+                "_list",
+                self.list_name.as_ref(),
+                view_path.attachment_name().as_ref(),
+            ],
+            Some(ref list_ddoc_name) => vec![
+                view_path.database_name().as_ref(),
+                "_design",
+                list_ddoc_name.as_ref(),
+                "_list",
+                self.list_name.as_ref(),
+                view_ddoc_name.as_ref(),
+                view_path.attachment_name().as_ref(),
+            ],
+        };
+
+        let request = try!(self.options.add_to_request(self.transport.get(path)));
+
+        let request = self.query.iter().fold(
+            request,
+            |request, &(ref key, ref value)| request.with_query_literal(key, value),
+        );
+
+        Ok(request)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use transport::{MockTransport, Transport};
+
+    #[test]
+    fn make_request_default() {
+
+        let transport = MockTransport::new();
+        let expected = transport.get(vec!["foo", "_design", "bar", "_list", "csv", "qux"]);
+
+        let got = {
+            let mut action = ExecuteList::new(&transport, "/foo/_design/bar/_view/qux", "csv");
+            action.make_request().unwrap()
+        };
+
+        assert_eq!(expected, got);
+    }
+
+    #[test]
+    fn make_request_with_list_design_document() {
+
+        let transport = MockTransport::new();
+        let expected = transport.get(vec!["foo", "_design", "lists", "_list", "csv", "bar", "qux"]);
+
+        let got = {
+            let mut action = ExecuteList::new(&transport, "/foo/_design/bar/_view/qux", "csv")
+                .with_list_design_document("lists");
+            action.make_request().unwrap()
+        };
+
+        assert_eq!(expected, got);
+    }
+
+    #[test]
+    fn make_request_with_view_options() {
+
+        let transport = MockTransport::new();
+        let expected = transport
+            .get(vec!["foo", "_design", "bar", "_list", "csv", "qux"])
+            .with_query_literal("reduce", "false")
+            .with_query_literal("startkey", "17")
+            .with_query_literal("endkey", "42")
+            .with_query_literal("inclusive_end", "false")
+            .with_query_literal("limit", "10")
+            .with_query_literal("descending", "true")
+            .with_query_literal("include_docs", "true");
+
+        let got = {
+            let mut action = ExecuteList::new(&transport, "/foo/_design/bar/_view/qux", "csv")
+                .with_reduce(false)
+                .with_start_key(&17)
+                .with_end_key_exclusive(&42)
+                .with_limit(10)
+                .with_descending(true)
+                .with_documents(true);
+            action.make_request().unwrap()
+        };
+
+        assert_eq!(expected, got);
+    }
+
+    #[test]
+    fn make_request_with_key_prefix() {
+
+        let transport = MockTransport::new();
+        let expected = transport
+            .get(vec!["foo", "_design", "bar", "_list", "csv", "qux"])
+            .with_query_literal("startkey", r#""abc""#)
+            .with_query_literal("endkey", "\"abc\u{fff0}\"");
+
+        let got = {
+            let mut action = ExecuteList::new(&transport, "/foo/_design/bar/_view/qux", "csv").with_key_prefix("abc");
+            action.make_request().unwrap()
+        };
+
+        assert_eq!(expected, got);
+    }
+
+    #[test]
+    fn make_request_with_query_param() {
+
+        let transport = MockTransport::new();
+        let expected = transport
+            .get(vec!["foo", "_design", "bar", "_list", "csv", "qux"])
+            .with_query_literal("limit", "10")
+            .with_query_literal("separator", ";");
+
+        let got = {
+            let mut action = ExecuteList::new(&transport, "/foo/_design/bar/_view/qux", "csv")
+                .with_limit(10)
+                .with_query_param("separator", ";");
+            action.make_request().unwrap()
+        };
+
+        assert_eq!(expected, got);
+    }
+}
@@ -0,0 +1,182 @@
+//! Defines an action for executing a show function.
+
+use {DocumentId, Error, FunctionResponse, IntoDesignDocumentPath, ShowName, std};
+use transport::{RawResponseDecoder, Request, Transport};
+
+/// Executes a show function on the CouchDB server and returns the result.
+///
+/// Chill executes the show function by sending an HTTP request to the CouchDB
+/// server to `GET` from the show function's path—optionally with a document
+/// id appended. The result is a `FunctionResponse` holding the function's
+/// output, whatever its content type. For more details about show functions,
+/// please see the CouchDB documentation.
+///
+/// # Errors
+///
+/// The following are _some_ errors that may occur when executing a show
+/// function.
+///
+/// <table>
+/// <tr>
+///  <td><code>Error::NotFound</code></td>
+///  <td>The database, design document, or show function does not
+///  exist.</td>
+/// </tr>
+/// <tr>
+///  <td><code>Error::Unauthorized</code></td>
+///  <td>The client lacks permission to execute the show function.</td>
+/// </tr>
+/// </table>
+///
+/// # Examples
+///
+/// The following program renders a document using a show function.
+///
+/// ```no_run
+/// extern crate chill;
+///
+/// let client = chill::Client::new("http://localhost:5984").unwrap();
+///
+/// let response = client.execute_show("/baseball/_design/stats", "player")
+///                      .with_document_id("babe_ruth")
+///                      .with_query_param("format", "html")
+///                      .run()
+///                      .unwrap();
+///
+/// println!("{}", String::from_utf8_lossy(response.content()));
+/// ```
+///
+pub struct ExecuteShow<'a, T: Transport + 'a, P: IntoDesignDocumentPath> {
+    transport: &'a T,
+    ddoc_path: Option<P>,
+    show_name: ShowName,
+    doc_id: Option<DocumentId>,
+    query: Vec<(String, String)>,
+}
+
+impl<'a, T: Transport + 'a, P: IntoDesignDocumentPath> ExecuteShow<'a, T, P> {
+    #[doc(hidden)]
+    pub fn new<S: Into<ShowName>>(transport: &'a T, ddoc_path: P, show_name: S) -> Self {
+        ExecuteShow {
+            transport: transport,
+            ddoc_path: Some(ddoc_path),
+            show_name: show_name.into(),
+            doc_id: None,
+            query: Vec::new(),
+        }
+    }
+
+    /// Modifies the action to pass the given document to the show function.
+    ///
+    /// If the document does not exist then CouchDB passes `null` to the show
+    /// function.
+    ///
+    pub fn with_document_id<D: Into<DocumentId>>(mut self, doc_id: D) -> Self {
+        self.doc_id = Some(doc_id.into());
+        self
+    }
+
+    /// Modifies the action to include the given query parameter in the
+    /// request, for use by the show function.
+    pub fn with_query_param<K: Into<String>, V: Into<String>>(mut self, key: K, value: V) -> Self {
+        self.query.push((key.into(), value.into()));
+        self
+    }
+
+    pub fn run(mut self) -> Result<FunctionResponse, Error> {
+        self.transport.send(
+            try!(self.make_request()),
+            RawResponseDecoder::new(FunctionResponse::from_raw_response),
+        )
+    }
+
+    fn make_request(&mut self) -> Result<Request, Error> {
+
+        let ddoc_path = try!(
+            std::mem::replace(&mut self.ddoc_path, None)
+                .unwrap()
+                .into_design_document_path()
+        );
+
+        let path = ddoc_path.iter().chain(vec!["_show", self.show_name.as_ref()]).chain(
+            self.doc_id
+                .iter()
+                .flat_map(|x| x.prefix().into_iter().chain(Some(x.name_as_str()))),
+        );
+
+        let request = self.query.iter().fold(
+            self.transport.get(path),
+            |request, &(ref key, ref value)| request.with_query_literal(key, value),
+        );
+
+        Ok(request)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use transport::{MockTransport, Transport};
+
+    #[test]
+    fn make_request_default() {
+
+        let transport = MockTransport::new();
+        let expected = transport.get(vec!["foo", "_design", "bar", "_show", "qux"]);
+
+        let got = {
+            let mut action = ExecuteShow::new(&transport, "/foo/_design/bar", "qux");
+            action.make_request().unwrap()
+        };
+
+        assert_eq!(expected, got);
+    }
+
+    #[test]
+    fn make_request_with_document_id() {
+
+        let transport = MockTransport::new();
+        let expected = transport.get(vec!["foo", "_design", "bar", "_show", "qux", "baz"]);
+
+        let got = {
+            let mut action = ExecuteShow::new(&transport, "/foo/_design/bar", "qux").with_document_id("baz");
+            action.make_request().unwrap()
+        };
+
+        assert_eq!(expected, got);
+    }
+
+    #[test]
+    fn make_request_with_design_document_id() {
+
+        let transport = MockTransport::new();
+        let expected = transport.get(vec!["foo", "_design", "bar", "_show", "qux", "_design", "baz"]);
+
+        let got = {
+            let mut action = ExecuteShow::new(&transport, "/foo/_design/bar", "qux").with_document_id("_design/baz");
+            action.make_request().unwrap()
+        };
+
+        assert_eq!(expected, got);
+    }
+
+    #[test]
+    fn make_request_with_query_param() {
+
+        let transport = MockTransport::new();
+        let expected = transport
+            .get(vec!["foo", "_design", "bar", "_show", "qux"])
+            .with_query_literal("format", "html")
+            .with_query_literal("limit", "10");
+
+        let got = {
+            let mut action = ExecuteShow::new(&transport, "/foo/_design/bar", "qux")
+                .with_query_param("format", "html")
+                .with_query_param("limit", "10");
+            action.make_request().unwrap()
+        };
+
+        assert_eq!(expected, got);
+    }
+}
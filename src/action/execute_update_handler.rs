@@ -0,0 +1,329 @@
+//! Defines an action for executing an update handler.
+
+use {DocumentId, Error, FunctionResponse, IntoDesignDocumentPath, Revision, UpdateName, mime, serde, serde_json, std};
+use transport::{RawResponse, RawResponseDecoder, Request, Transport};
+
+enum Content {
+    Json(serde_json::Value),
+    Raw(mime::Mime, Vec<u8>),
+}
+
+/// Executes an update handler on the CouchDB server and returns the result.
+///
+/// Chill executes the update handler by sending an HTTP request to the CouchDB
+/// server to `POST` to the update handler's path or, if a document id is
+/// given, to `PUT` to the update handler's path with the document id appended.
+/// The result is the new revision of the document written by the handler, if
+/// any, along with the handler's response. The revision is `None` if the
+/// handler wrote no document or if the revision isn't a normal revision—e.g.,
+/// the handler wrote a local document, whose revision has the form `0-N`. For
+/// more details about update handlers, please see the CouchDB documentation.
+///
+/// # Errors
+///
+/// The following are _some_ errors that may occur when executing an update
+/// handler.
+///
+/// <table>
+/// <tr>
+///  <td><code>Error::DocumentConflict</code></td>
+///  <td>The document written by the update handler was modified by another
+///  client.</td>
+/// </tr>
+/// <tr>
+///  <td><code>Error::NotFound</code></td>
+///  <td>The database, design document, or update handler does not
+///  exist.</td>
+/// </tr>
+/// <tr>
+///  <td><code>Error::Unauthorized</code></td>
+///  <td>The client lacks permission to execute the update handler.</td>
+/// </tr>
+/// </table>
+///
+/// # Examples
+///
+/// The following program increments a counter using an update handler.
+///
+/// ```no_run
+/// extern crate chill;
+///
+/// let client = chill::Client::new("http://localhost:5984").unwrap();
+///
+/// let (revision, response) = client.execute_update_handler("/baseball/_design/stats", "increment")
+///                                  .with_document_id("home_runs")
+///                                  .with_query_param("by", "1")
+///                                  .run()
+///                                  .unwrap();
+///
+/// println!("Counter is at revision {:?}: {}",
+///          revision,
+///          String::from_utf8_lossy(response.content()));
+/// ```
+///
+pub struct ExecuteUpdateHandler<'a, T: Transport + 'a, P: IntoDesignDocumentPath> {
+    transport: &'a T,
+    ddoc_path: Option<P>,
+    update_name: UpdateName,
+    doc_id: Option<DocumentId>,
+    content: Option<Content>,
+    query: Vec<(String, String)>,
+}
+
+impl<'a, T: Transport + 'a, P: IntoDesignDocumentPath> ExecuteUpdateHandler<'a, T, P> {
+    #[doc(hidden)]
+    pub fn new<U: Into<UpdateName>>(transport: &'a T, ddoc_path: P, update_name: U) -> Self {
+        ExecuteUpdateHandler {
+            transport: transport,
+            ddoc_path: Some(ddoc_path),
+            update_name: update_name.into(),
+            doc_id: None,
+            content: None,
+            query: Vec::new(),
+        }
+    }
+
+    /// Modifies the action to pass the given document to the update handler.
+    ///
+    /// If the document does not exist then CouchDB passes `null` to the update
+    /// handler.
+    ///
+    pub fn with_document_id<D: Into<DocumentId>>(mut self, doc_id: D) -> Self {
+        self.doc_id = Some(doc_id.into());
+        self
+    }
+
+    /// Modifies the action to send the given request body, of the given
+    /// content type, to the update handler.
+    pub fn with_content<C: Into<Vec<u8>>>(mut self, content_type: mime::Mime, content: C) -> Self {
+        self.content = Some(Content::Raw(content_type, content.into()));
+        self
+    }
+
+    /// Modifies the action to send the given value, encoded as JSON, as the
+    /// request body to the update handler.
+    pub fn with_json_content<C: serde::Serialize>(mut self, content: &C) -> Self {
+        self.content = Some(Content::Json(serde_json::to_value(content)));
+        self
+    }
+
+    /// Modifies the action to include the given query parameter in the
+    /// request, for use by the update handler.
+    pub fn with_query_param<K: Into<String>, V: Into<String>>(mut self, key: K, value: V) -> Self {
+        self.query.push((key.into(), value.into()));
+        self
+    }
+
+    pub fn run(mut self) -> Result<(Option<Revision>, FunctionResponse), Error> {
+        self.transport.send(
+            try!(self.make_request()),
+            RawResponseDecoder::new(handle_response),
+        )
+    }
+
+    fn make_request(&mut self) -> Result<Request, Error> {
+
+        let ddoc_path = try!(
+            std::mem::replace(&mut self.ddoc_path, None)
+                .unwrap()
+                .into_design_document_path()
+        );
+
+        let path = ddoc_path.iter().chain(vec!["_update", self.update_name.as_ref()]).chain(
+            self.doc_id
+                .iter()
+                .flat_map(|x| x.prefix().into_iter().chain(Some(x.name_as_str()))),
+        );
+
+        let request = match self.doc_id {
+            None => self.transport.post(path),
+            Some(..) => self.transport.put(path),
+        };
+
+        let request = match std::mem::replace(&mut self.content, None) {
+            None => request,
+            Some(Content::Json(ref content)) => try!(request.with_json_content(content)),
+            Some(Content::Raw(content_type, content)) => request.with_content(content_type, content),
+        };
+
+        let request = self.query.iter().fold(
+            request,
+            |request, &(ref key, ref value)| request.with_query_literal(key, value),
+        );
+
+        Ok(request)
+    }
+}
+
+fn handle_response(response: RawResponse) -> Result<(Option<Revision>, FunctionResponse), Error> {
+
+    // By the time the response arrives, the server has already committed the
+    // write, so an unparsable revision mustn't fail the action.
+
+    let revision = response
+        .headers()
+        .get_raw("X-Couch-Update-NewRev")
+        .and_then(|x| Revision::parse(&String::from_utf8_lossy(x)).ok());
+
+    let response = try!(FunctionResponse::from_raw_response(response));
+    Ok((revision, response))
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use {Error, Revision, serde_json};
+    use transport::{MockTransport, RawResponseBuilder, StatusCode, Transport};
+
+    #[test]
+    fn make_request_default() {
+
+        let transport = MockTransport::new();
+        let expected = transport.post(vec!["foo", "_design", "bar", "_update", "qux"]);
+
+        let got = {
+            let mut action = ExecuteUpdateHandler::new(&transport, "/foo/_design/bar", "qux");
+            action.make_request().unwrap()
+        };
+
+        assert_eq!(expected, got);
+    }
+
+    #[test]
+    fn make_request_with_document_id() {
+
+        let transport = MockTransport::new();
+        let expected = transport.put(vec!["foo", "_design", "bar", "_update", "qux", "baz"]);
+
+        let got = {
+            let mut action = ExecuteUpdateHandler::new(&transport, "/foo/_design/bar", "qux").with_document_id("baz");
+            action.make_request().unwrap()
+        };
+
+        assert_eq!(expected, got);
+    }
+
+    #[test]
+    fn make_request_with_content() {
+
+        let transport = MockTransport::new();
+        let expected = transport
+            .post(vec!["foo", "_design", "bar", "_update", "qux"])
+            .with_content(mime!(Text / Plain), b"hello".to_vec());
+
+        let got = {
+            let mut action = ExecuteUpdateHandler::new(&transport, "/foo/_design/bar", "qux")
+                .with_content(mime!(Text / Plain), "hello");
+            action.make_request().unwrap()
+        };
+
+        assert_eq!(expected, got);
+    }
+
+    #[test]
+    fn make_request_with_json_content_and_query_param() {
+
+        let transport = MockTransport::new();
+
+        let content = serde_json::builder::ObjectBuilder::new()
+            .insert("field_1", 42)
+            .build();
+
+        let expected = transport
+            .put(vec!["foo", "_design", "bar", "_update", "qux", "baz"])
+            .with_json_content(&content)
+            .unwrap()
+            .with_query_literal("by", "1");
+
+        let got = {
+            let mut action = ExecuteUpdateHandler::new(&transport, "/foo/_design/bar", "qux")
+                .with_document_id("baz")
+                .with_json_content(&content)
+                .with_query_param("by", "1");
+            action.make_request().unwrap()
+        };
+
+        assert_eq!(expected, got);
+    }
+
+    #[derive(Clone, Debug, PartialEq)]
+    struct XCouchUpdateNewRev(String);
+
+    impl ::hyper::header::Header for XCouchUpdateNewRev {
+        fn header_name() -> &'static str {
+            "X-Couch-Update-NewRev"
+        }
+        fn parse_header(raw: &[Vec<u8>]) -> ::hyper::Result<Self> {
+            Ok(XCouchUpdateNewRev(String::from_utf8_lossy(&raw[0]).into_owned()))
+        }
+    }
+
+    impl ::hyper::header::HeaderFormat for XCouchUpdateNewRev {
+        fn fmt_header(&self, f: &mut ::std::fmt::Formatter) -> ::std::fmt::Result {
+            f.write_str(&self.0)
+        }
+    }
+
+    #[test]
+    fn handle_response_ok_with_revision() {
+
+        let response = RawResponseBuilder::new(StatusCode::Created)
+            .with_header(XCouchUpdateNewRev(
+                String::from("2-1234567890abcdef1234567890abcdef"),
+            ))
+            .with_content(mime!(Text / Plain), "incremented")
+            .unwrap();
+
+        let (revision, response) = super::handle_response(response).unwrap();
+        assert_eq!(
+            Some(Revision::parse("2-1234567890abcdef1234567890abcdef").unwrap()),
+            revision
+        );
+        assert_eq!(201, response.status_code());
+        assert_eq!(b"incremented", response.content());
+    }
+
+    #[test]
+    fn handle_response_ok_with_local_revision() {
+
+        let response = RawResponseBuilder::new(StatusCode::Created)
+            .with_header(XCouchUpdateNewRev(String::from("0-1")))
+            .with_content(mime!(Text / Plain), "checkpointed")
+            .unwrap();
+
+        let (revision, response) = super::handle_response(response).unwrap();
+        assert_eq!(None, revision);
+        assert_eq!(201, response.status_code());
+        assert_eq!(b"checkpointed", response.content());
+    }
+
+    #[test]
+    fn handle_response_ok_without_revision() {
+
+        let response = RawResponseBuilder::new(StatusCode::Ok)
+            .with_content(mime!(Text / Plain), "nothing to do")
+            .unwrap();
+
+        let (revision, response) = super::handle_response(response).unwrap();
+        assert_eq!(None, revision);
+        assert_eq!(b"nothing to do", response.content());
+    }
+
+    #[test]
+    fn handle_response_conflict() {
+
+        let response = RawResponseBuilder::new(StatusCode::Conflict)
+            .with_content(
+                mime!(Application / Json),
+                r#"{"error":"conflict","reason":"Document update conflict."}"#,
+            )
+            .unwrap();
+
+        match super::handle_response(response) {
+            Err(Error::DocumentConflict(ref error_response))
+                if error_response.error() == "conflict" && error_response.reason() == "Document update conflict." => (),
+            x @ _ => unexpected_result!(x),
+        }
+    }
+}
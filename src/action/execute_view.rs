@@ -1,20 +1,10 @@
 //! Defines an action for executing a view.
 
-use {DatabaseName, Error, IntoViewPath, ViewResponse, serde, std};
-use action::query_keys::*;
+use {DatabaseName, Error, IntoViewPath, TypedViewResponse, ViewResponse, serde, serde_json, std};
+use action::view_options::{GroupLevel, Inclusivity, ViewOptions};
 use transport::{JsonResponse, JsonResponseDecoder, Request, StatusCode, Transport};
 use view::ViewResponseJsonable;
 
-enum Inclusivity {
-    Exclusive,
-    Inclusive,
-}
-
-enum GroupLevel {
-    Exact(bool),
-    Number(u32),
-}
-
 /// Executes a view on the CouchDB server and returns the result.
 ///
 /// Chill executes the view by sending an HTTP request to the CouchDB server to
@@ -111,13 +101,7 @@ where
 {
     transport: &'a T,
     view_path: Option<P>,
-    reduce: Option<bool>,
-    start_key: Option<StartKey>,
-    end_key: Option<(EndKey, Inclusivity)>,
-    limit: Option<u64>,
-    descending: Option<bool>,
-    group_level: Option<GroupLevel>,
-    include_docs: Option<bool>,
+    options: ViewOptions<StartKey, EndKey>,
 }
 
 impl<'a, P, T> ExecuteView<'a, T, P, (), ()>
@@ -130,13 +114,7 @@ where
         ExecuteView {
             transport: transport,
             view_path: Some(view_path),
-            reduce: None,
-            start_key: None,
-            end_key: None,
-            limit: None,
-            descending: None,
-            group_level: None,
-            include_docs: None,
+            options: ViewOptions::new(),
         }
     }
 }
@@ -156,7 +134,7 @@ where
     /// disable reduction of a view that contains a reduction function.
     ///
     pub fn with_reduce(mut self, reduce: bool) -> Self {
-        self.reduce = Some(reduce);
+        self.options.reduce = Some(reduce);
         self
     }
     /// Modifies the action to retrieve at most a given number of documents.
@@ -167,7 +145,7 @@ where
     /// This method has no effect if the view is reduced.
     ///
     pub fn with_limit(mut self, limit: u64) -> Self {
-        self.limit = Some(limit);
+        self.options.limit = Some(limit);
         self
     }
 
@@ -182,26 +160,62 @@ where
     /// This method has no effect if the view is reduced.
     ///
     pub fn with_descending(mut self, descending: bool) -> Self {
-        self.descending = Some(descending);
+        self.options.descending = Some(descending);
         self
     }
 
     pub fn with_exact_groups(mut self, yes_or_no: bool) -> Self {
-        self.group_level = Some(GroupLevel::Exact(yes_or_no));
+        self.options.group_level = Some(GroupLevel::Exact(yes_or_no));
         self
     }
 
     pub fn with_group_level(mut self, group_level: u32) -> Self {
-        self.group_level = Some(GroupLevel::Number(group_level));
+        self.options.group_level = Some(GroupLevel::Number(group_level));
         self
     }
 
     pub fn with_documents(mut self, yes_or_no: bool) -> Self {
-        self.include_docs = Some(yes_or_no);
+        self.options.include_docs = Some(yes_or_no);
         self
     }
 }
 
+impl<'a, P, T> ExecuteView<'a, T, P, (), ()>
+where
+    P: IntoViewPath,
+    T: Transport + 'a,
+{
+    /// Modifies the action to include only records with a key beginning with
+    /// a given prefix.
+    ///
+    /// The `with_key_prefix` method abstracts CouchDB's `startkey` and `endkey`
+    /// query parameters, setting both bounds so that the CouchDB server
+    /// includes all records whose key begins with the prefix. The prefix must
+    /// be an array or a string. For an array prefix—e.g., `["customer-42",
+    /// 2016]`—the server includes all records whose key is an array beginning
+    /// with the prefix's elements. For a string prefix, the server includes
+    /// all records whose key is a string beginning with the prefix.
+    ///
+    /// If the action is also modified to retrieve rows in descending order,
+    /// then Chill swaps the bounds, as CouchDB requires.
+    ///
+    /// # Errors
+    ///
+    /// Executing the action fails if the prefix is neither an array nor a
+    /// string.
+    ///
+    pub fn with_key_prefix<K>(self, prefix: K) -> ExecuteView<'a, T, P, serde_json::Value, serde_json::Value>
+    where
+        K: serde::Serialize,
+    {
+        ExecuteView {
+            transport: self.transport,
+            view_path: self.view_path,
+            options: self.options.with_key_prefix(serde_json::to_value(&prefix)),
+        }
+    }
+}
+
 impl<'a, EndKey, P, T> ExecuteView<'a, T, P, (), EndKey>
 where
     EndKey: serde::Serialize,
@@ -221,13 +235,7 @@ where
         ExecuteView {
             transport: self.transport,
             view_path: self.view_path,
-            reduce: self.reduce,
-            start_key: Some(start_key),
-            end_key: self.end_key,
-            limit: self.limit,
-            descending: self.descending,
-            group_level: self.group_level,
-            include_docs: self.include_docs,
+            options: self.options.with_start_key(start_key),
         }
     }
 }
@@ -251,13 +259,7 @@ where
         ExecuteView {
             transport: self.transport,
             view_path: self.view_path,
-            reduce: self.reduce,
-            start_key: self.start_key,
-            end_key: Some((end_key, Inclusivity::Inclusive)),
-            limit: self.limit,
-            descending: self.descending,
-            group_level: self.group_level,
-            include_docs: self.include_docs,
+            options: self.options.with_end_key(end_key, Inclusivity::Inclusive),
         }
     }
 
@@ -275,13 +277,7 @@ where
         ExecuteView {
             transport: self.transport,
             view_path: self.view_path,
-            reduce: self.reduce,
-            start_key: self.start_key,
-            end_key: Some((end_key, Inclusivity::Exclusive)),
-            limit: self.limit,
-            descending: self.descending,
-            group_level: self.group_level,
-            include_docs: self.include_docs,
+            options: self.options.with_end_key(end_key, Inclusivity::Exclusive),
         }
     }
 }
@@ -301,6 +297,32 @@ where
         )
     }
 
+    /// Executes the action, decodes every row into the given key, value, and
+    /// document content types, and waits for the result.
+    ///
+    /// The `run_typed` method decodes all rows once, when the response
+    /// arrives, so that the application need not decode each row's key and
+    /// value separately. If any row fails to decode then the method returns an
+    /// `Error::ViewRowDecode` error that identifies the offending row's
+    /// document, if available.
+    ///
+    /// The document content type, `D`, is used only if the action is modified
+    /// to include documents—i.e., via the `with_documents` method. Otherwise,
+    /// any decodable type, such as `serde_json::Value`, will do.
+    ///
+    pub fn run_typed<K, V, D>(mut self) -> Result<TypedViewResponse<K, V, D>, Error>
+    where
+        D: serde::Deserialize,
+        K: serde::Deserialize,
+        V: serde::Deserialize,
+    {
+        let (request, db_name) = try!(self.make_request());
+        self.transport.send(
+            request,
+            JsonResponseDecoder::new(move |response| handle_typed_response(response, db_name)),
+        )
+    }
+
     fn make_request(&mut self) -> Result<(Request, DatabaseName), Error> {
 
         let view_path = try!(
@@ -311,45 +333,7 @@ where
         let db_name = view_path.database_name().clone();
 
         let request = self.transport.get(view_path.iter()).with_accept_json();
-
-        let request = match self.reduce {
-            None => request,
-            Some(ref yes_or_no) => request.with_query(ReduceQueryKey, yes_or_no),
-        };
-
-        let request = match self.start_key {
-            None => request,
-            Some(ref key) => try!(request.with_query_fallible(StartKeyQueryKey, key)),
-        };
-
-        let request = match self.end_key {
-            None => request,
-            Some((ref key, Inclusivity::Inclusive)) => try!(request.with_query_fallible(EndKeyQueryKey, key)),
-            Some((ref key, Inclusivity::Exclusive)) => {
-                try!(request.with_query_fallible(EndKeyQueryKey, key)).with_query(InclusiveEndQueryKey, &false)
-            }
-        };
-
-        let request = match self.limit {
-            None => request,
-            Some(ref limit) => request.with_query(LimitQueryKey, limit),
-        };
-
-        let request = match self.descending {
-            None => request,
-            Some(ref yes_or_no) => request.with_query(DescendingQueryKey, yes_or_no),
-        };
-
-        let request = match self.group_level {
-            None => request,
-            Some(GroupLevel::Exact(ref yes_or_no)) => request.with_query(GroupQueryKey, yes_or_no),
-            Some(GroupLevel::Number(ref group_level)) => request.with_query(GroupLevelQueryKey, group_level),
-        };
-
-        let request = match self.include_docs {
-            None => request,
-            Some(ref yes_or_no) => request.with_query(IncludeDocsQueryKey, yes_or_no),
-        };
+        let request = try!(self.options.add_to_request(request));
 
         Ok((request, db_name))
     }
@@ -367,11 +351,31 @@ fn handle_response(response: JsonResponse, db_name: DatabaseName) -> Result<View
     }
 }
 
+fn handle_typed_response<K, V, D>(
+    response: JsonResponse,
+    db_name: DatabaseName,
+) -> Result<TypedViewResponse<K, V, D>, Error>
+where
+    D: serde::Deserialize,
+    K: serde::Deserialize,
+    V: serde::Deserialize,
+{
+    match response.status_code() {
+        StatusCode::Ok => {
+            let body: ViewResponseJsonable = try!(response.decode_content());
+            TypedViewResponse::new_from_decoded(db_name, body)
+        }
+        StatusCode::NotFound => Err(Error::not_found(&response)),
+        StatusCode::Unauthorized => Err(Error::unauthorized(&response)),
+        _ => Err(Error::server_response(&response)),
+    }
+}
+
 #[cfg(test)]
 mod tests {
 
     use super::*;
-    use {DatabaseName, Error};
+    use {DatabaseName, Error, IntoDocumentPath};
     use transport::{JsonResponseBuilder, MockTransport, StatusCode, Transport};
     use view::ViewResponseBuilder;
 
@@ -503,6 +507,88 @@ mod tests {
         assert_eq!(expected, got);
     }
 
+    #[test]
+    fn make_request_with_key_prefix_array() {
+
+        let transport = MockTransport::new();
+
+        let expected = (
+            transport
+                .get(vec!["foo", "_design", "bar", "_view", "qux"])
+                .with_accept_json()
+                .with_query_literal("startkey", r#"["customer-42",2016]"#)
+                .with_query_literal("endkey", r#"["customer-42",2016,{}]"#),
+            DatabaseName::from("foo"),
+        );
+
+        let got = {
+            let mut action = ExecuteView::new(&transport, "/foo/_design/bar/_view/qux")
+                .with_key_prefix(("customer-42", 2016));
+            action.make_request().unwrap()
+        };
+
+        assert_eq!(expected, got);
+    }
+
+    #[test]
+    fn make_request_with_key_prefix_string() {
+
+        let transport = MockTransport::new();
+
+        let expected = (
+            transport
+                .get(vec!["foo", "_design", "bar", "_view", "qux"])
+                .with_accept_json()
+                .with_query_literal("startkey", r#""abc""#)
+                .with_query_literal("endkey", "\"abc\u{fff0}\""),
+            DatabaseName::from("foo"),
+        );
+
+        let got = {
+            let mut action = ExecuteView::new(&transport, "/foo/_design/bar/_view/qux").with_key_prefix("abc");
+            action.make_request().unwrap()
+        };
+
+        assert_eq!(expected, got);
+    }
+
+    #[test]
+    fn make_request_with_key_prefix_and_descending() {
+
+        let transport = MockTransport::new();
+
+        let expected = (
+            transport
+                .get(vec!["foo", "_design", "bar", "_view", "qux"])
+                .with_accept_json()
+                .with_query_literal("startkey", r#"["customer-42",2016,{}]"#)
+                .with_query_literal("endkey", r#"["customer-42",2016]"#)
+                .with_query_literal("descending", "true"),
+            DatabaseName::from("foo"),
+        );
+
+        let got = {
+            let mut action = ExecuteView::new(&transport, "/foo/_design/bar/_view/qux")
+                .with_key_prefix(("customer-42", 2016))
+                .with_descending(true);
+            action.make_request().unwrap()
+        };
+
+        assert_eq!(expected, got);
+    }
+
+    #[test]
+    fn make_request_with_key_prefix_nok_not_array_or_string() {
+
+        let transport = MockTransport::new();
+        let mut action = ExecuteView::new(&transport, "/foo/_design/bar/_view/qux").with_key_prefix(42);
+
+        match action.make_request() {
+            Err(Error::KeyPrefixNotArrayOrString) => (),
+            x @ _ => unexpected_result!(x),
+        }
+    }
+
     #[test]
     fn make_request_with_limit() {
         let transport = MockTransport::new();
@@ -598,6 +684,52 @@ mod tests {
         assert_eq!(expected, got);
     }
 
+    #[test]
+    fn handle_typed_response_ok_unreduced() {
+
+        let response = JsonResponseBuilder::new(StatusCode::Ok)
+            .with_json_content_raw(
+                "{\"total_rows\":20,\"offset\":10,\"rows\":[\
+                                   {\"key\":\"Babe Ruth\",\"value\":714,\"id\":\"babe_ruth\"},\
+                                   {\"key\":\"Hank Aaron\",\"value\":755,\"id\":\"hank_aaron\"}]}",
+            )
+            .unwrap();
+
+        let db_name = DatabaseName::from("baseball");
+        let got = super::handle_typed_response::<String, u32, ()>(response, db_name).unwrap();
+
+        assert_eq!(Some(20), got.total_rows());
+        assert_eq!(Some(10), got.offset());
+
+        let rows = got.rows();
+        assert_eq!(2, rows.len());
+        assert_eq!(Some(&String::from("Babe Ruth")), rows[0].key());
+        assert_eq!(&714, rows[0].value());
+        assert_eq!(Some(&"/baseball/babe_ruth".into_document_path().unwrap()), rows[0].document_path());
+        assert_eq!(Some(&String::from("Hank Aaron")), rows[1].key());
+        assert_eq!(&755, rows[1].value());
+        assert_eq!(Some(&"/baseball/hank_aaron".into_document_path().unwrap()), rows[1].document_path());
+    }
+
+    #[test]
+    fn handle_typed_response_nok_row_decode() {
+
+        let response = JsonResponseBuilder::new(StatusCode::Ok)
+            .with_json_content_raw(
+                "{\"total_rows\":20,\"offset\":10,\"rows\":[\
+                                   {\"key\":\"Babe Ruth\",\"value\":714,\"id\":\"babe_ruth\"},\
+                                   {\"key\":\"Hank Aaron\",\"value\":\"oops\",\"id\":\"hank_aaron\"}]}",
+            )
+            .unwrap();
+
+        let db_name = DatabaseName::from("baseball");
+        match super::handle_typed_response::<String, u32, ()>(response, db_name) {
+            Err(Error::ViewRowDecode { doc_path: Some(ref doc_path), .. })
+                if doc_path.document_id().to_string() == "hank_aaron" => (),
+            x @ _ => unexpected_result!(x),
+        }
+    }
+
     #[test]
     fn handle_response_not_found() {
 
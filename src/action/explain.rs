@@ -0,0 +1,280 @@
+//! Defines an action for explaining how the server would execute a Mango
+//! query.
+
+use {DesignDocumentName, Error, IntoDatabasePath, std};
+use action::find_options::FindOptions;
+use mango::{ExplainResult, Selector, SortOrder};
+use transport::{JsonResponse, JsonResponseDecoder, Request, StatusCode, Transport};
+
+/// Explains how the CouchDB server would execute a Mango query.
+///
+/// Chill explains the query by sending an HTTP request to the CouchDB server
+/// to `POST` to the database's `_explain` path. The result is an
+/// `ExplainResult` describing which index the server would use. The action
+/// accepts the same options as `Find`, and it is useful for checking that a
+/// query uses the intended index.
+///
+/// # Errors
+///
+/// The following are _some_ errors that may occur when explaining a query.
+///
+/// <table>
+/// <tr>
+///  <td><code>Error::NotFound</code></td>
+///  <td>The database does not exist.</td>
+/// </tr>
+/// <tr>
+///  <td><code>Error::Unauthorized</code></td>
+///  <td>The client lacks permission to read from the database.</td>
+/// </tr>
+/// </table>
+///
+/// # Examples
+///
+/// ```no_run
+/// extern crate chill;
+///
+/// use chill::mango::{Selector, SortOrder};
+///
+/// let client = chill::Client::new("http://localhost:5984").unwrap();
+///
+/// let result = client.explain("/baseball", Selector::new().gte("home_runs", 500))
+///                    .with_sort("home_runs", SortOrder::Descending)
+///                    .run()
+///                    .unwrap();
+///
+/// println!("Query would use index {}", result.index().name());
+/// ```
+///
+pub struct Explain<'a, T: Transport + 'a, P: IntoDatabasePath> {
+    transport: &'a T,
+    db_path: Option<P>,
+    options: FindOptions,
+}
+
+impl<'a, T: Transport + 'a, P: IntoDatabasePath> Explain<'a, T, P> {
+    #[doc(hidden)]
+    pub fn new(transport: &'a T, db_path: P, selector: Selector) -> Self {
+        Explain {
+            transport: transport,
+            db_path: Some(db_path),
+            options: FindOptions::new(selector),
+        }
+    }
+
+    /// Modifies the action to return only the given fields of each document.
+    ///
+    /// See `Find::with_fields` for details.
+    ///
+    pub fn with_fields<I, F>(mut self, fields: I) -> Self
+    where
+        I: IntoIterator<Item = F>,
+        F: Into<String>,
+    {
+        self.options.set_fields(fields.into_iter().map(|x| x.into()).collect());
+        self
+    }
+
+    /// Modifies the action to sort the result by the given field.
+    ///
+    /// See `Find::with_sort` for details.
+    ///
+    pub fn with_sort<F: Into<String>>(mut self, field: F, order: SortOrder) -> Self {
+        self.options.sort.push((field.into(), order));
+        self
+    }
+
+    /// Modifies the action to return at most the given number of documents.
+    pub fn with_limit(mut self, limit: u64) -> Self {
+        self.options.limit = Some(limit);
+        self
+    }
+
+    /// Modifies the action to skip the given number of matching documents.
+    pub fn with_skip(mut self, skip: u64) -> Self {
+        self.options.skip = Some(skip);
+        self
+    }
+
+    /// Modifies the action to use an index in the given design document.
+    pub fn with_use_index<D: Into<DesignDocumentName>>(mut self, ddoc_name: D) -> Self {
+        self.options.use_index = Some((ddoc_name.into(), None));
+        self
+    }
+
+    /// Modifies the action to use the index with the given name in the given
+    /// design document.
+    pub fn with_use_index_named<D, N>(mut self, ddoc_name: D, index_name: N) -> Self
+    where
+        D: Into<DesignDocumentName>,
+        N: Into<String>,
+    {
+        self.options.use_index = Some((ddoc_name.into(), Some(index_name.into())));
+        self
+    }
+
+    pub fn run(mut self) -> Result<ExplainResult, Error> {
+        self.transport.send(
+            try!(self.make_request()),
+            JsonResponseDecoder::new(handle_response),
+        )
+    }
+
+    fn make_request(&mut self) -> Result<Request, Error> {
+
+        let db_path = try!(
+            std::mem::replace(&mut self.db_path, None)
+                .unwrap()
+                .into_database_path()
+        );
+
+        let mut path = db_path.iter().collect::<Vec<_>>();
+        path.push("_explain");
+
+        self.transport
+            .post(path)
+            .with_accept_json()
+            .with_json_content(&self.options.to_json())
+    }
+}
+
+fn handle_response(response: JsonResponse) -> Result<ExplainResult, Error> {
+    match response.status_code() {
+        StatusCode::Ok => Ok(try!(response.decode_content())),
+        StatusCode::NotFound => Err(Error::not_found(&response)),
+        StatusCode::Unauthorized => Err(Error::unauthorized(&response)),
+        _ => Err(Error::server_response(&response)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use {DesignDocumentName, Error, serde_json};
+    use mango::{Selector, SortOrder};
+    use transport::{JsonResponseBuilder, MockTransport, StatusCode, Transport};
+
+    #[test]
+    fn make_request_default() {
+
+        let transport = MockTransport::new();
+
+        let body = serde_json::builder::ObjectBuilder::new()
+            .insert_object("selector", |x| x.insert_object("name", |x| x.insert("$eq", "Babe Ruth")))
+            .build();
+
+        let expected = transport
+            .post(vec!["baseball", "_explain"])
+            .with_accept_json()
+            .with_json_content(&body)
+            .unwrap();
+
+        let got = {
+            let mut action = Explain::new(&transport, "/baseball", Selector::new().eq("name", "Babe Ruth"));
+            action.make_request().unwrap()
+        };
+
+        assert_eq!(expected, got);
+    }
+
+    #[test]
+    fn make_request_with_options() {
+
+        let transport = MockTransport::new();
+
+        let body = serde_json::builder::ObjectBuilder::new()
+            .insert_object("selector", |x| x.insert_object("home_runs", |x| x.insert("$gt", 500)))
+            .insert_array("fields", |x| x.push("_id").push("_rev").push("name"))
+            .insert_array("sort", |x| x.push_object(|x| x.insert("home_runs", "desc")))
+            .insert("limit", 10)
+            .insert("skip", 20)
+            .insert("use_index", "_design/stats")
+            .build();
+
+        let expected = transport
+            .post(vec!["baseball", "_explain"])
+            .with_accept_json()
+            .with_json_content(&body)
+            .unwrap();
+
+        let got = {
+            let mut action = Explain::new(&transport, "/baseball", Selector::new().gt("home_runs", 500))
+                .with_fields(vec!["name"])
+                .with_sort("home_runs", SortOrder::Descending)
+                .with_limit(10)
+                .with_skip(20)
+                .with_use_index("stats");
+            action.make_request().unwrap()
+        };
+
+        assert_eq!(expected, got);
+    }
+
+    #[test]
+    fn handle_response_ok() {
+
+        let response = JsonResponseBuilder::new(StatusCode::Ok)
+            .with_json_content_raw(
+                r#"{"dbname":"baseball",
+                "index":{"ddoc":"_design/stats","name":"by_home_runs","type":"json",
+                 "def":{"fields":[{"home_runs":"desc"}]}},
+                "selector":{"home_runs":{"$gt":500}},
+                "opts":{"use_index":[],"bookmark":"nil","limit":10,"skip":0,"sort":{},"fields":"all_fields",
+                 "r":[49],"conflicts":false},
+                "limit":10,"skip":0,"fields":"all_fields",
+                "range":{"start_key":[500],"end_key":[{}]}}"#,
+            )
+            .unwrap();
+
+        let got = super::handle_response(response).unwrap();
+
+        assert_eq!(Some(&DesignDocumentName::from("stats")), got.index().design_document_name());
+        assert_eq!("by_home_runs", got.index().name());
+
+        let expected_selector = serde_json::builder::ObjectBuilder::new()
+            .insert_object("home_runs", |x| x.insert("$gt", 500))
+            .build();
+        assert_eq!(&expected_selector, got.selector());
+
+        assert_eq!(Some(10), got.limit());
+        assert_eq!(Some(0), got.skip());
+        assert_eq!(None, got.fields());
+        assert!(got.range().is_some());
+    }
+
+    #[test]
+    fn handle_response_ok_with_fields() {
+
+        let response = JsonResponseBuilder::new(StatusCode::Ok)
+            .with_json_content_raw(
+                r#"{"index":{"ddoc":null,"name":"_all_docs","type":"special","def":{"fields":[{"_id":"asc"}]}},
+                "selector":{},"fields":["_id","_rev","name"]}"#,
+            )
+            .unwrap();
+
+        let got = super::handle_response(response).unwrap();
+
+        assert_eq!(None, got.index().design_document_name());
+        assert_eq!(
+            Some(&vec![String::from("_id"), String::from("_rev"), String::from("name")]),
+            got.fields()
+        );
+        assert_eq!(None, got.limit());
+        assert_eq!(None, got.range());
+    }
+
+    #[test]
+    fn handle_response_not_found() {
+
+        let response = JsonResponseBuilder::new(StatusCode::NotFound)
+            .with_json_content_raw(r#"{"error":"not_found","reason":"Database does not exist."}"#)
+            .unwrap();
+
+        match super::handle_response(response) {
+            Err(Error::NotFound(ref error_response))
+                if error_response.error() == "not_found" && error_response.reason() == "Database does not exist." => (),
+            x @ _ => unexpected_result!(x),
+        }
+    }
+}
@@ -0,0 +1,586 @@
+//! Defines an action for executing a Mango query.
+
+use {DatabaseName, DatabasePath, DesignDocumentName, Document, Error, IntoDatabasePath, serde, std};
+use action::find_options::FindOptions;
+use mango::{FindResponse, FindResponseJsonable, Selector, SortOrder};
+use transport::{JsonResponse, JsonResponseDecoder, Request, StatusCode, Transport};
+
+/// Executes a Mango query on the CouchDB server and returns the result.
+///
+/// Chill executes the query by sending an HTTP request to the CouchDB server
+/// to `POST` to the database's `_find` path. The result is a `FindResponse`
+/// containing the documents that match the query's selector. Mango queries
+/// require CouchDB 2.0 or later. For more details about Mango queries, please
+/// see the CouchDB documentation.
+///
+/// # Errors
+///
+/// The following are _some_ errors that may occur when executing a Mango
+/// query.
+///
+/// <table>
+/// <tr>
+///  <td><code>Error::NotFound</code></td>
+///  <td>The database does not exist.</td>
+/// </tr>
+/// <tr>
+///  <td><code>Error::Unauthorized</code></td>
+///  <td>The client lacks permission to read from the database.</td>
+/// </tr>
+/// </table>
+///
+/// # Examples
+///
+/// The following program finds the ten players with the most home runs among
+/// players with at least 500 home runs.
+///
+/// ```no_run
+/// extern crate chill;
+///
+/// use chill::mango::{Selector, SortOrder};
+///
+/// let client = chill::Client::new("http://localhost:5984").unwrap();
+///
+/// let response = client.find("/baseball", Selector::new().gte("home_runs", 500))
+///                      .with_fields(vec!["name", "home_runs"])
+///                      .with_sort("home_runs", SortOrder::Descending)
+///                      .with_limit(10)
+///                      .run()
+///                      .unwrap();
+///
+/// for doc in response.documents() {
+///     println!("{}", doc.path());
+/// }
+/// ```
+///
+pub struct Find<'a, T: Transport + 'a, P: IntoDatabasePath> {
+    transport: &'a T,
+    db_path: Option<P>,
+    options: FindOptions,
+}
+
+impl<'a, T: Transport + 'a, P: IntoDatabasePath> Find<'a, T, P> {
+    #[doc(hidden)]
+    pub fn new(transport: &'a T, db_path: P, selector: Selector) -> Self {
+        Find {
+            transport: transport,
+            db_path: Some(db_path),
+            options: FindOptions::new(selector),
+        }
+    }
+
+    /// Modifies the action to return only the given fields of each document.
+    ///
+    /// The `with_fields` method abstracts the query's `fields` parameter. By
+    /// default, CouchDB returns all fields. Chill always adds the `_id` and
+    /// `_rev` fields, which every `Document` requires.
+    ///
+    pub fn with_fields<I, F>(mut self, fields: I) -> Self
+    where
+        I: IntoIterator<Item = F>,
+        F: Into<String>,
+    {
+        self.options.set_fields(fields.into_iter().map(|x| x.into()).collect());
+        self
+    }
+
+    /// Modifies the action to sort the result by the given field.
+    ///
+    /// The `with_sort` method abstracts the query's `sort` parameter. Each call
+    /// adds a field to sort by, with earlier fields taking precedence. CouchDB
+    /// requires an index covering all sort fields.
+    ///
+    pub fn with_sort<F: Into<String>>(mut self, field: F, order: SortOrder) -> Self {
+        self.options.sort.push((field.into(), order));
+        self
+    }
+
+    /// Modifies the action to return at most the given number of documents.
+    ///
+    /// The `with_limit` method abstracts the query's `limit` parameter. By
+    /// default, CouchDB returns at most 25 documents.
+    ///
+    pub fn with_limit(mut self, limit: u64) -> Self {
+        self.options.limit = Some(limit);
+        self
+    }
+
+    /// Modifies the action to skip the given number of matching documents.
+    pub fn with_skip(mut self, skip: u64) -> Self {
+        self.options.skip = Some(skip);
+        self
+    }
+
+    /// Modifies the action to use an index in the given design document.
+    pub fn with_use_index<D: Into<DesignDocumentName>>(mut self, ddoc_name: D) -> Self {
+        self.options.use_index = Some((ddoc_name.into(), None));
+        self
+    }
+
+    /// Modifies the action to use the index with the given name in the given
+    /// design document.
+    pub fn with_use_index_named<D, N>(mut self, ddoc_name: D, index_name: N) -> Self
+    where
+        D: Into<DesignDocumentName>,
+        N: Into<String>,
+    {
+        self.options.use_index = Some((ddoc_name.into(), Some(index_name.into())));
+        self
+    }
+
+    /// Modifies the action to resume from the given bookmark.
+    ///
+    /// The bookmark comes from the `FindResponse` of an earlier query with the
+    /// same selector. Resuming from a bookmark retrieves the next page of
+    /// results.
+    ///
+    pub fn with_bookmark<B: Into<String>>(mut self, bookmark: B) -> Self {
+        self.options.bookmark = Some(bookmark.into());
+        self
+    }
+
+    /// Modifies the action to include execution statistics in the result.
+    pub fn with_execution_stats(mut self, yes_or_no: bool) -> Self {
+        self.options.execution_stats = Some(yes_or_no);
+        self
+    }
+
+    pub fn run(mut self) -> Result<FindResponse, Error> {
+        let (request, db_name) = try!(self.make_request());
+        self.transport.send(
+            request,
+            JsonResponseDecoder::new(move |response| handle_response(response, db_name)),
+        )
+    }
+
+    /// Executes the action as a sequence of queries, one per page of results,
+    /// and returns an iterator over the matching documents.
+    ///
+    /// The iterator sends the first query when first advanced. Thereafter,
+    /// whenever it exhausts a page, it sends the same query again with the
+    /// bookmark from the previous page, and it stops when the server returns
+    /// an empty page. Only one page of documents is held in memory at a time.
+    ///
+    /// The page size is the query's limit, if set via the `with_limit`
+    /// method, else CouchDB's default.
+    ///
+    /// If a query fails then the iterator yields the error and then stops.
+    ///
+    pub fn run_iter(mut self) -> Result<FindIter<'a, T>, Error> {
+
+        let db_path = try!(
+            std::mem::replace(&mut self.db_path, None)
+                .unwrap()
+                .into_database_path()
+        );
+
+        Ok(FindIter {
+            transport: self.transport,
+            db_path: db_path,
+            options: self.options,
+            page: Vec::new().into_iter(),
+            done: false,
+        })
+    }
+
+    /// Executes the action as a sequence of queries, like the `run_iter`
+    /// method, and returns an iterator over the content of the matching
+    /// documents, decoded into the given type.
+    pub fn run_iter_typed<C: serde::Deserialize>(self) -> Result<TypedFindIter<'a, T, C>, Error> {
+        Ok(TypedFindIter {
+            inner: try!(self.run_iter()),
+            _content: std::marker::PhantomData,
+        })
+    }
+
+    fn make_request(&mut self) -> Result<(Request, DatabaseName), Error> {
+
+        let db_path = try!(
+            std::mem::replace(&mut self.db_path, None)
+                .unwrap()
+                .into_database_path()
+        );
+
+        let request = try!(make_find_request(self.transport, &db_path, &self.options));
+        Ok((request, db_path.database_name().clone()))
+    }
+}
+
+/// Iterator over the documents matching a Mango query, page by page.
+///
+/// A `FindIter` is created by the `Find::run_iter` method. It yields each
+/// matching document, following the bookmark from each page of results to
+/// retrieve the next page.
+///
+pub struct FindIter<'a, T: Transport + 'a> {
+    transport: &'a T,
+    db_path: DatabasePath,
+    options: FindOptions,
+    page: std::vec::IntoIter<Document>,
+    done: bool,
+}
+
+impl<'a, T: Transport + 'a> FindIter<'a, T> {
+    fn make_request(&self) -> Result<Request, Error> {
+        make_find_request(self.transport, &self.db_path, &self.options)
+    }
+
+    // An empty page or a page without a bookmark is the last page. The bookmark
+    // already accounts for any skipped documents, so subsequent pages must not
+    // skip them again.
+    fn accept_page(&mut self, response: FindResponse) {
+
+        if response.documents().is_empty() {
+            self.done = true;
+            return;
+        }
+
+        match response.bookmark() {
+            None => self.done = true,
+            Some(bookmark) => {
+                self.options.bookmark = Some(String::from(bookmark));
+                self.options.skip = None;
+            }
+        }
+
+        self.page = response.into_documents().into_iter();
+    }
+}
+
+impl<'a, T: Transport + 'a> Iterator for FindIter<'a, T> {
+    type Item = Result<Document, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(doc) = self.page.next() {
+                return Some(Ok(doc));
+            }
+
+            if self.done {
+                return None;
+            }
+
+            let db_name = self.db_path.database_name().clone();
+            let result = self.make_request().and_then(|request| {
+                self.transport.send(
+                    request,
+                    JsonResponseDecoder::new(move |response| handle_response(response, db_name)),
+                )
+            });
+
+            match result {
+                Ok(response) => self.accept_page(response),
+                Err(e) => {
+                    self.done = true;
+                    return Some(Err(e));
+                }
+            }
+        }
+    }
+}
+
+/// Iterator over the content of the documents matching a Mango query, page by
+/// page.
+///
+/// A `TypedFindIter` is created by the `Find::run_iter_typed` method. It is
+/// like a `FindIter` except that it decodes each document's content into the
+/// application's type.
+///
+pub struct TypedFindIter<'a, T: Transport + 'a, C: serde::Deserialize> {
+    inner: FindIter<'a, T>,
+    _content: std::marker::PhantomData<C>,
+}
+
+impl<'a, T: Transport + 'a, C: serde::Deserialize> Iterator for TypedFindIter<'a, T, C> {
+    type Item = Result<C, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next().map(|x| x.and_then(|doc| doc.get_content()))
+    }
+}
+
+fn make_find_request<T>(transport: &T, db_path: &DatabasePath, options: &FindOptions) -> Result<Request, Error>
+where
+    T: Transport,
+{
+
+    let mut path = db_path.iter().collect::<Vec<_>>();
+    path.push("_find");
+
+    transport
+        .post(path)
+        .with_accept_json()
+        .with_json_content(&options.to_json())
+}
+
+fn handle_response(response: JsonResponse, db_name: DatabaseName) -> Result<FindResponse, Error> {
+    match response.status_code() {
+        StatusCode::Ok => {
+            let body: FindResponseJsonable = try!(response.decode_content());
+            Ok(FindResponse::new_from_decoded(db_name, body))
+        }
+        StatusCode::NotFound => Err(Error::not_found(&response)),
+        StatusCode::Unauthorized => Err(Error::unauthorized(&response)),
+        _ => Err(Error::server_response(&response)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use {DatabaseName, Error, serde_json};
+    use mango::{FindResponse, FindResponseJsonable, Selector, SortOrder};
+    use transport::{JsonResponseBuilder, MockTransport, StatusCode, Transport};
+
+    #[test]
+    fn make_request_default() {
+
+        let transport = MockTransport::new();
+
+        let body = serde_json::builder::ObjectBuilder::new()
+            .insert_object("selector", |x| x.insert_object("name", |x| x.insert("$eq", "Babe Ruth")))
+            .build();
+
+        let expected = (
+            transport
+                .post(vec!["baseball", "_find"])
+                .with_accept_json()
+                .with_json_content(&body)
+                .unwrap(),
+            DatabaseName::from("baseball"),
+        );
+
+        let got = {
+            let mut action = Find::new(&transport, "/baseball", Selector::new().eq("name", "Babe Ruth"));
+            action.make_request().unwrap()
+        };
+
+        assert_eq!(expected, got);
+    }
+
+    #[test]
+    fn make_request_with_all_options() {
+
+        let transport = MockTransport::new();
+
+        let body = serde_json::builder::ObjectBuilder::new()
+            .insert_object("selector", |x| x.insert_object("home_runs", |x| x.insert("$gt", 500)))
+            .insert_array("fields", |x| x.push("_id").push("_rev").push("name"))
+            .insert_array("sort", |x| {
+                x.push_object(|x| x.insert("home_runs", "desc"))
+                    .push_object(|x| x.insert("name", "asc"))
+            })
+            .insert("limit", 10)
+            .insert("skip", 20)
+            .insert_array("use_index", |x| x.push("_design/stats").push("by_home_runs"))
+            .insert("bookmark", "abc")
+            .insert("execution_stats", true)
+            .build();
+
+        let expected = (
+            transport
+                .post(vec!["baseball", "_find"])
+                .with_accept_json()
+                .with_json_content(&body)
+                .unwrap(),
+            DatabaseName::from("baseball"),
+        );
+
+        let got = {
+            let mut action = Find::new(&transport, "/baseball", Selector::new().gt("home_runs", 500))
+                .with_fields(vec!["name"])
+                .with_sort("home_runs", SortOrder::Descending)
+                .with_sort("name", SortOrder::Ascending)
+                .with_limit(10)
+                .with_skip(20)
+                .with_use_index_named("stats", "by_home_runs")
+                .with_bookmark("abc")
+                .with_execution_stats(true);
+            action.make_request().unwrap()
+        };
+
+        assert_eq!(expected, got);
+    }
+
+    #[test]
+    fn make_request_with_use_index() {
+
+        let transport = MockTransport::new();
+
+        let body = serde_json::builder::ObjectBuilder::new()
+            .insert_object("selector", |x| x)
+            .insert("use_index", "_design/stats")
+            .build();
+
+        let expected = (
+            transport
+                .post(vec!["baseball", "_find"])
+                .with_accept_json()
+                .with_json_content(&body)
+                .unwrap(),
+            DatabaseName::from("baseball"),
+        );
+
+        let got = {
+            let mut action = Find::new(&transport, "/baseball", Selector::new()).with_use_index("stats");
+            action.make_request().unwrap()
+        };
+
+        assert_eq!(expected, got);
+    }
+
+    #[test]
+    fn handle_response_ok() {
+
+        let response = JsonResponseBuilder::new(StatusCode::Ok)
+            .with_json_content_raw(
+                r#"{"docs":[{"_id":"babe_ruth","_rev":"1-1234567890abcdef1234567890abcdef","name":"Babe Ruth"}],
+                "bookmark":"abc"}"#,
+            )
+            .unwrap();
+
+        let got = super::handle_response(response, DatabaseName::from("baseball")).unwrap();
+        assert_eq!(1, got.documents().len());
+        assert_eq!("/baseball/babe_ruth", got.documents()[0].path().to_string());
+        assert_eq!(Some("abc"), got.bookmark());
+    }
+
+    #[test]
+    fn handle_response_bad_request() {
+
+        let response = JsonResponseBuilder::new(StatusCode::BadRequest)
+            .with_json_content_raw(r#"{"error":"invalid_operator","reason":"Invalid operator: $foo"}"#)
+            .unwrap();
+
+        match super::handle_response(response, DatabaseName::from("baseball")) {
+            Err(Error::ServerResponse { status_code: StatusCode::BadRequest, .. }) => (),
+            x @ _ => unexpected_result!(x),
+        }
+    }
+
+    #[test]
+    fn handle_response_not_found() {
+
+        let response = JsonResponseBuilder::new(StatusCode::NotFound)
+            .with_json_content_raw(r#"{"error":"not_found","reason":"Database does not exist."}"#)
+            .unwrap();
+
+        match super::handle_response(response, DatabaseName::from("baseball")) {
+            Err(Error::NotFound(ref error_response))
+                if error_response.error() == "not_found" && error_response.reason() == "Database does not exist." => (),
+            x @ _ => unexpected_result!(x),
+        }
+    }
+
+    #[test]
+    fn handle_response_unauthorized() {
+
+        let response = JsonResponseBuilder::new(StatusCode::Unauthorized)
+            .with_json_content_raw(
+                r#"{"error":"unauthorized","reason":"Authentication required."}"#,
+            )
+            .unwrap();
+
+        match super::handle_response(response, DatabaseName::from("baseball")) {
+            Err(Error::Unauthorized(ref error_response))
+                if error_response.error() == "unauthorized" && error_response.reason() == "Authentication required." =>
+                (),
+            x @ _ => unexpected_result!(x),
+        }
+    }
+
+    fn find_response(source: &str) -> FindResponse {
+        let decoded: FindResponseJsonable = serde_json::from_str(source).unwrap();
+        FindResponse::new_from_decoded(DatabaseName::from("baseball"), decoded)
+    }
+
+    #[test]
+    fn find_iter_follows_bookmark() {
+
+        let transport = MockTransport::new();
+
+        let body = serde_json::builder::ObjectBuilder::new()
+            .insert_object("selector", |x| x)
+            .insert("limit", 1)
+            .insert("bookmark", "abc")
+            .build();
+
+        let expected = transport
+            .post(vec!["baseball", "_find"])
+            .with_accept_json()
+            .with_json_content(&body)
+            .unwrap();
+
+        let mut iter = Find::new(&transport, "/baseball", Selector::new())
+            .with_limit(1)
+            .run_iter()
+            .unwrap();
+
+        iter.accept_page(find_response(
+            r#"{"docs":[{"_id":"babe_ruth","_rev":"1-1234567890abcdef1234567890abcdef"}],"bookmark":"abc"}"#,
+        ));
+
+        assert_eq!(expected, iter.make_request().unwrap());
+
+        let doc = iter.next().unwrap().unwrap();
+        assert_eq!("/baseball/babe_ruth", doc.path().to_string());
+        assert!(!iter.done);
+    }
+
+    #[test]
+    fn find_iter_clears_skip_after_first_page() {
+
+        let transport = MockTransport::new();
+
+        let body = serde_json::builder::ObjectBuilder::new()
+            .insert_object("selector", |x| x)
+            .insert("limit", 1)
+            .insert("bookmark", "abc")
+            .build();
+
+        let expected = transport
+            .post(vec!["baseball", "_find"])
+            .with_accept_json()
+            .with_json_content(&body)
+            .unwrap();
+
+        let mut iter = Find::new(&transport, "/baseball", Selector::new())
+            .with_limit(1)
+            .with_skip(20)
+            .run_iter()
+            .unwrap();
+
+        iter.accept_page(find_response(
+            r#"{"docs":[{"_id":"babe_ruth","_rev":"1-1234567890abcdef1234567890abcdef"}],"bookmark":"abc"}"#,
+        ));
+
+        assert_eq!(expected, iter.make_request().unwrap());
+    }
+
+    #[test]
+    fn find_iter_stops_on_empty_page() {
+
+        let transport = MockTransport::new();
+
+        let mut iter = Find::new(&transport, "/baseball", Selector::new()).run_iter().unwrap();
+        iter.accept_page(find_response(r#"{"docs":[],"bookmark":"nil"}"#));
+
+        assert!(iter.done);
+        assert!(iter.next().is_none());
+    }
+
+    #[test]
+    fn find_iter_stops_without_bookmark() {
+
+        let transport = MockTransport::new();
+
+        let mut iter = Find::new(&transport, "/baseball", Selector::new()).run_iter().unwrap();
+        iter.accept_page(find_response(
+            r#"{"docs":[{"_id":"babe_ruth","_rev":"1-1234567890abcdef1234567890abcdef"}]}"#,
+        ));
+
+        assert!(iter.next().unwrap().is_ok());
+        assert!(iter.next().is_none());
+    }
+}
@@ -0,0 +1,94 @@
+//! Defines the query options shared by all actions that run a Mango query.
+
+use {DesignDocumentName, serde_json};
+use mango::{Selector, SortOrder};
+
+pub struct FindOptions {
+    pub selector: Selector,
+    pub fields: Option<Vec<String>>,
+    pub sort: Vec<(String, SortOrder)>,
+    pub limit: Option<u64>,
+    pub skip: Option<u64>,
+    pub use_index: Option<(DesignDocumentName, Option<String>)>,
+    pub bookmark: Option<String>,
+    pub execution_stats: Option<bool>,
+}
+
+impl FindOptions {
+    pub fn new(selector: Selector) -> Self {
+        FindOptions {
+            selector: selector,
+            fields: None,
+            sort: Vec::new(),
+            limit: None,
+            skip: None,
+            use_index: None,
+            bookmark: None,
+            execution_stats: None,
+        }
+    }
+
+    // Chill always requests the _id and _rev fields because every Document
+    // requires them.
+    pub fn set_fields(&mut self, fields: Vec<String>) {
+        let mut fields = fields;
+        for required in &["_rev", "_id"] {
+            if !fields.iter().any(|x| x == required) {
+                fields.insert(0, String::from(*required));
+            }
+        }
+        self.fields = Some(fields);
+    }
+
+    pub fn to_json(&self) -> serde_json::Value {
+
+        let body = serde_json::builder::ObjectBuilder::new().insert("selector", &self.selector);
+
+        let body = match self.fields {
+            None => body,
+            Some(ref fields) => body.insert("fields", fields),
+        };
+
+        let body = if self.sort.is_empty() {
+            body
+        } else {
+            body.insert_array("sort", |x| {
+                self.sort.iter().fold(x, |x, &(ref field, ref order)| {
+                    x.push_object(|x| x.insert(field.clone(), order.as_str()))
+                })
+            })
+        };
+
+        let body = match self.limit {
+            None => body,
+            Some(limit) => body.insert("limit", limit),
+        };
+
+        let body = match self.skip {
+            None => body,
+            Some(skip) => body.insert("skip", skip),
+        };
+
+        let body = match self.use_index {
+            None => body,
+            Some((ref ddoc_name, None)) => body.insert("use_index", format!("_design/{}", ddoc_name)),
+            Some((ref ddoc_name, Some(ref index_name))) => {
+                body.insert_array("use_index", |x| {
+                    x.push(format!("_design/{}", ddoc_name)).push(index_name)
+                })
+            }
+        };
+
+        let body = match self.bookmark {
+            None => body,
+            Some(ref bookmark) => body.insert("bookmark", bookmark),
+        };
+
+        let body = match self.execution_stats {
+            None => body,
+            Some(yes_or_no) => body.insert("execution_stats", yes_or_no),
+        };
+
+        body.build()
+    }
+}
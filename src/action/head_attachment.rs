@@ -0,0 +1,166 @@
+//! Defines an action for reading an attachment's meta-information without its
+//! content.
+
+use {AttachmentInfo, Error, IntoAttachmentPath, std};
+use transport::{RawResponse, RawResponseDecoder, Request, StatusCode, Transport};
+
+/// Reads an attachment's meta-information from the CouchDB server, without
+/// downloading the attachment's content.
+///
+/// Chill reads the meta-information by sending an HTTP request to `HEAD` the
+/// attachment's path. The result is an `AttachmentInfo` containing the
+/// attachment's content length, content type, and digest, or `None` if the
+/// attachment—or its document—does not exist.
+///
+/// # Errors
+///
+/// The following are _some_ errors that may occur when reading an
+/// attachment's meta-information.
+///
+/// <table>
+/// <tr>
+///  <td><code>Error::ServerResponse</code></td>
+///  <td>The server responded with an error status—e.g., because the client
+///  lacks permission to read the document. The server sends no error details
+///  in response to a <code>HEAD</code> request.</td>
+/// </tr>
+/// </table>
+///
+/// # Examples
+///
+/// ```no_run
+/// extern crate chill;
+///
+/// let client = chill::Client::new("http://localhost:5984").unwrap();
+///
+/// let info = client.head_attachment("/baseball/babe_ruth/photo.png")
+///                  .run()
+///                  .unwrap()
+///                  .expect("Attachment does not exist");
+///
+/// println!("Attachment is {} bytes", info.content_length());
+/// ```
+///
+pub struct HeadAttachment<'a, T: Transport + 'a, P: IntoAttachmentPath> {
+    transport: &'a T,
+    att_path: Option<P>,
+}
+
+impl<'a, T: Transport + 'a, P: IntoAttachmentPath> HeadAttachment<'a, T, P> {
+    #[doc(hidden)]
+    pub fn new(transport: &'a T, att_path: P) -> Self {
+        HeadAttachment {
+            transport: transport,
+            att_path: Some(att_path),
+        }
+    }
+
+    /// Executes the action and waits for the result.
+    pub fn run(mut self) -> Result<Option<AttachmentInfo>, Error> {
+        self.transport.send(
+            try!(self.make_request()),
+            RawResponseDecoder::new(handle_response),
+        )
+    }
+
+    fn make_request(&mut self) -> Result<Request, Error> {
+        let att_path = try!(
+            std::mem::replace(&mut self.att_path, None)
+                .unwrap()
+                .into_attachment_path()
+        );
+        Ok(self.transport.head(att_path.iter()))
+    }
+}
+
+fn handle_response(response: RawResponse) -> Result<Option<AttachmentInfo>, Error> {
+    match response.status_code() {
+        StatusCode::Ok => {
+            let headers = response.headers();
+
+            let content_length = match headers.content_length() {
+                None => return Err(Error::UnexpectedResponse("The server returned no Content-Length header")),
+                Some(x) => x,
+            };
+
+            // The server sends the attachment's MD5 digest, base64-encoded, in
+            // the Content-MD5 header and, quoted, in the ETag header. We
+            // prefix the digest the same way as in an attachment stub.
+            let digest = headers
+                .get_raw("Content-MD5")
+                .or_else(|| headers.get_raw("ETag"))
+                .map(|x| format!("md5-{}", String::from_utf8_lossy(x).trim_matches('"')));
+
+            Ok(Some(
+                AttachmentInfo::new(headers.content_type(), content_length, digest),
+            ))
+        }
+        StatusCode::NotFound => Ok(None),
+        status_code @ _ => Err(Error::ServerResponse {
+            status_code: status_code,
+            error_response: None,
+        }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use {AttachmentInfo, Error, hyper};
+    use transport::{MockTransport, RawResponseBuilder, StatusCode, Transport};
+
+    #[test]
+    fn make_request_default() {
+
+        let transport = MockTransport::new();
+        let expected = transport.head(vec!["foo", "bar", "qux"]);
+
+        let got = {
+            let mut action = HeadAttachment::new(&transport, "/foo/bar/qux");
+            action.make_request().unwrap()
+        };
+
+        assert_eq!(expected, got);
+    }
+
+    #[test]
+    fn handle_response_ok() {
+
+        let response = RawResponseBuilder::new(StatusCode::Ok)
+            .with_header(hyper::header::ContentType(mime!(Image / Png)))
+            .with_header(hyper::header::ContentLength(17))
+            .with_header(hyper::header::ETag(hyper::header::EntityTag::strong(
+                String::from("iMaiC8wqiFlD2NjLTemvCQ=="),
+            )))
+            .unwrap();
+
+        let expected = AttachmentInfo::new(
+            Some(mime!(Image / Png)),
+            17,
+            Some(String::from("md5-iMaiC8wqiFlD2NjLTemvCQ==")),
+        );
+
+        let got = super::handle_response(response).unwrap();
+        assert_eq!(Some(expected), got);
+    }
+
+    #[test]
+    fn handle_response_not_found() {
+        let response = RawResponseBuilder::new(StatusCode::NotFound).unwrap();
+        let got = super::handle_response(response).unwrap();
+        assert_eq!(None, got);
+    }
+
+    #[test]
+    fn handle_response_unauthorized() {
+        let response = RawResponseBuilder::new(StatusCode::Unauthorized).unwrap();
+        match super::handle_response(response) {
+            Err(Error::ServerResponse {
+                    status_code: StatusCode::Unauthorized,
+                    error_response: None,
+                }) => (),
+            x @ _ => unexpected_result!(x),
+        }
+    }
+}
@@ -0,0 +1,155 @@
+//! Defines an action for checking whether a document exists and, if so, what
+//! its current revision is.
+
+use {Error, IntoDocumentPath, Revision, std};
+use transport::{RawResponse, RawResponseDecoder, Request, StatusCode, Transport};
+
+/// Checks whether a document exists on the CouchDB server and, if so, returns
+/// the document's current revision.
+///
+/// Chill checks the document by sending an HTTP request to `HEAD` the
+/// document's path. The server responds with the document's revision in the
+/// `ETag` header but without the document's content, which makes this action
+/// cheaper than `ReadDocument` when the application needs only the revision.
+///
+/// The result is `None` if the document does not exist—including if the
+/// document has been deleted.
+///
+/// # Errors
+///
+/// The following are _some_ errors that may occur when checking a document.
+///
+/// <table>
+/// <tr>
+///  <td><code>Error::ServerResponse</code></td>
+///  <td>The server responded with an error status—e.g., because the client
+///  lacks permission to read the document. The server sends no error details
+///  in response to a <code>HEAD</code> request.</td>
+/// </tr>
+/// </table>
+///
+/// # Examples
+///
+/// ```no_run
+/// extern crate chill;
+///
+/// let client = chill::Client::new("http://localhost:5984").unwrap();
+///
+/// match client.head_document("/baseball/babe_ruth").run().unwrap() {
+///     None => println!("Document does not exist"),
+///     Some(rev) => println!("Document exists at revision {}", rev),
+/// }
+/// ```
+///
+pub struct HeadDocument<'a, T: Transport + 'a, P: IntoDocumentPath> {
+    transport: &'a T,
+    doc_path: Option<P>,
+}
+
+impl<'a, T: Transport + 'a, P: IntoDocumentPath> HeadDocument<'a, T, P> {
+    #[doc(hidden)]
+    pub fn new(transport: &'a T, doc_path: P) -> Self {
+        HeadDocument {
+            transport: transport,
+            doc_path: Some(doc_path),
+        }
+    }
+
+    /// Executes the action and waits for the result.
+    pub fn run(mut self) -> Result<Option<Revision>, Error> {
+        self.transport.send(
+            try!(self.make_request()),
+            RawResponseDecoder::new(handle_response),
+        )
+    }
+
+    fn make_request(&mut self) -> Result<Request, Error> {
+        let doc_path = try!(
+            std::mem::replace(&mut self.doc_path, None)
+                .unwrap()
+                .into_document_path()
+        );
+        Ok(self.transport.head(doc_path.iter()))
+    }
+}
+
+fn handle_response(response: RawResponse) -> Result<Option<Revision>, Error> {
+    match response.status_code() {
+        StatusCode::Ok => {
+            let etag = match response.headers().get_raw("ETag") {
+                None => return Err(Error::UnexpectedResponse("The server returned no ETag header")),
+                Some(x) => String::from_utf8_lossy(x).into_owned(),
+            };
+            Ok(Some(try!(Revision::parse(etag.trim_matches('"')))))
+        }
+        StatusCode::NotFound => Ok(None),
+        status_code @ _ => Err(Error::ServerResponse {
+            status_code: status_code,
+            error_response: None,
+        }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use {Error, Revision, hyper};
+    use transport::{MockTransport, RawResponseBuilder, StatusCode, Transport};
+
+    #[test]
+    fn make_request_default() {
+
+        let transport = MockTransport::new();
+        let expected = transport.head(vec!["foo", "bar"]);
+
+        let got = {
+            let mut action = HeadDocument::new(&transport, "/foo/bar");
+            action.make_request().unwrap()
+        };
+
+        assert_eq!(expected, got);
+    }
+
+    #[test]
+    fn handle_response_ok() {
+
+        let response = RawResponseBuilder::new(StatusCode::Ok)
+            .with_header(hyper::header::ETag(hyper::header::EntityTag::strong(
+                String::from("1-967a00dff5e02add41819138abb3284d"),
+            )))
+            .unwrap();
+
+        let expected = Revision::parse("1-967a00dff5e02add41819138abb3284d").unwrap();
+        let got = super::handle_response(response).unwrap();
+        assert_eq!(Some(expected), got);
+    }
+
+    #[test]
+    fn handle_response_ok_without_etag() {
+        let response = RawResponseBuilder::new(StatusCode::Ok).unwrap();
+        match super::handle_response(response) {
+            Err(Error::UnexpectedResponse(..)) => (),
+            x @ _ => unexpected_result!(x),
+        }
+    }
+
+    #[test]
+    fn handle_response_not_found() {
+        let response = RawResponseBuilder::new(StatusCode::NotFound).unwrap();
+        let got = super::handle_response(response).unwrap();
+        assert_eq!(None, got);
+    }
+
+    #[test]
+    fn handle_response_unauthorized() {
+        let response = RawResponseBuilder::new(StatusCode::Unauthorized).unwrap();
+        match super::handle_response(response) {
+            Err(Error::ServerResponse {
+                    status_code: StatusCode::Unauthorized,
+                    error_response: None,
+                }) => (),
+            x @ _ => unexpected_result!(x),
+        }
+    }
+}
@@ -0,0 +1,178 @@
+//! Defines an action for listing the Mango indexes in a database.
+
+use {Error, IntoDatabasePath, std};
+use mango::{Index, IndexListJsonable};
+use transport::{JsonResponse, JsonResponseDecoder, Request, StatusCode, Transport};
+
+/// Lists the Mango indexes in a database on the CouchDB server.
+///
+/// Chill lists the indexes by sending an HTTP request to the CouchDB server to
+/// `GET` from the database's `_index` path. The result includes the special
+/// index over the `_id` field, which every database has.
+///
+/// # Errors
+///
+/// The following are _some_ errors that may occur when listing indexes.
+///
+/// <table>
+/// <tr>
+///  <td><code>Error::NotFound</code></td>
+///  <td>The database does not exist.</td>
+/// </tr>
+/// <tr>
+///  <td><code>Error::Unauthorized</code></td>
+///  <td>The client lacks permission to read from the database.</td>
+/// </tr>
+/// </table>
+///
+/// # Examples
+///
+/// ```no_run
+/// extern crate chill;
+///
+/// let client = chill::Client::new("http://localhost:5984").unwrap();
+///
+/// for index in client.list_indexes("/baseball").run().unwrap() {
+///     println!("{} ({})", index.name(), index.index_type());
+/// }
+/// ```
+///
+pub struct ListIndexes<'a, T: Transport + 'a, P: IntoDatabasePath> {
+    transport: &'a T,
+    db_path: Option<P>,
+}
+
+impl<'a, P: IntoDatabasePath, T: Transport + 'a> ListIndexes<'a, T, P> {
+    #[doc(hidden)]
+    pub fn new(transport: &'a T, db_path: P) -> Self {
+        ListIndexes {
+            transport: transport,
+            db_path: Some(db_path),
+        }
+    }
+
+    pub fn run(mut self) -> Result<Vec<Index>, Error> {
+        self.transport.send(
+            try!(self.make_request()),
+            JsonResponseDecoder::new(handle_response),
+        )
+    }
+
+    fn make_request(&mut self) -> Result<Request, Error> {
+
+        let db_path = try!(
+            std::mem::replace(&mut self.db_path, None)
+                .unwrap()
+                .into_database_path()
+        );
+
+        let mut path = db_path.iter().collect::<Vec<_>>();
+        path.push("_index");
+
+        Ok(self.transport.get(path).with_accept_json())
+    }
+}
+
+fn handle_response(response: JsonResponse) -> Result<Vec<Index>, Error> {
+    match response.status_code() {
+        StatusCode::Ok => {
+            let body: IndexListJsonable = try!(response.decode_content());
+            Ok(body.into_indexes())
+        }
+        StatusCode::NotFound => Err(Error::not_found(&response)),
+        StatusCode::Unauthorized => Err(Error::unauthorized(&response)),
+        _ => Err(Error::server_response(&response)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use {DesignDocumentName, Error};
+    use mango::SortOrder;
+    use transport::{JsonResponseBuilder, MockTransport, StatusCode, Transport};
+
+    #[test]
+    fn make_request_default() {
+
+        let transport = MockTransport::new();
+        let expected = transport.get(vec!["baseball", "_index"]).with_accept_json();
+
+        let got = {
+            let mut action = ListIndexes::new(&transport, "/baseball");
+            action.make_request().unwrap()
+        };
+
+        assert_eq!(expected, got);
+    }
+
+    #[test]
+    fn handle_response_ok() {
+
+        let response = JsonResponseBuilder::new(StatusCode::Ok)
+            .with_json_content_raw(
+                r#"{"total_rows":2,"indexes":[
+                {"ddoc":null,"name":"_all_docs","type":"special","def":{"fields":[{"_id":"asc"}]}},
+                {"ddoc":"_design/stats","name":"by_home_runs","type":"json",
+                 "def":{"fields":[{"home_runs":"desc"},"name"],
+                 "partial_filter_selector":{"retired":{"$exists":false}}}}]}"#,
+            )
+            .unwrap();
+
+        let got = super::handle_response(response).unwrap();
+        assert_eq!(2, got.len());
+
+        assert_eq!(None, got[0].design_document_name());
+        assert_eq!("_all_docs", got[0].name());
+        assert_eq!("special", got[0].index_type());
+        assert_eq!(
+            &vec![(String::from("_id"), SortOrder::Ascending)],
+            got[0].definition().fields()
+        );
+        assert_eq!(None, got[0].definition().partial_filter_selector());
+
+        assert_eq!(Some(&DesignDocumentName::from("stats")), got[1].design_document_name());
+        assert_eq!("by_home_runs", got[1].name());
+        assert_eq!("json", got[1].index_type());
+        assert_eq!(
+            &vec![
+                (String::from("home_runs"), SortOrder::Descending),
+                (String::from("name"), SortOrder::Ascending),
+            ],
+            got[1].definition().fields()
+        );
+        assert!(got[1].definition().partial_filter_selector().is_some());
+    }
+
+    #[test]
+    fn handle_response_not_found() {
+
+        let response = JsonResponseBuilder::new(StatusCode::NotFound)
+            .with_json_content_raw(r#"{"error":"not_found","reason":"Database does not exist."}"#)
+            .unwrap();
+
+        match super::handle_response(response) {
+            Err(Error::NotFound(ref error_response))
+                if error_response.error() == "not_found" && error_response.reason() == "Database does not exist." => (),
+            x @ _ => unexpected_result!(x),
+        }
+    }
+
+    #[test]
+    fn handle_response_unauthorized() {
+
+        let response = JsonResponseBuilder::new(StatusCode::Unauthorized)
+            .with_json_content_raw(
+                r#"{"error":"unauthorized","reason":"Authentication required."}"#,
+            )
+            .unwrap();
+
+        match super::handle_response(response) {
+            Err(Error::Unauthorized(ref error_response))
+                if error_response.error() == "unauthorized" && error_response.reason() == "Authentication required." =>
+                (),
+            x @ _ => unexpected_result!(x),
+        }
+    }
+}
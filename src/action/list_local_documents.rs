@@ -0,0 +1,369 @@
+//! Defines an action for listing all local documents in a database.
+
+use {DocumentId, Error, IntoDatabasePath, LocalDocumentName, LocalRevision, serde, serde_json, std};
+use local_document::LocalRevisionJsonable;
+use transport::{JsonResponse, JsonResponseDecoder, Request, StatusCode, Transport};
+
+/// Lists all local documents in a database, along with their revisions.
+///
+/// Chill lists the local documents by sending an HTTP request to `GET` from
+/// the database's `_local_docs` path. The result pairs each local document's
+/// name—i.e., its id without the `_local/` prefix—with the document's current
+/// revision, in the order the server returns them.
+///
+/// The `_local_docs` endpoint requires CouchDB 2.2 or later.
+///
+/// # Errors
+///
+/// The following are _some_ errors that may occur when listing local
+/// documents.
+///
+/// <table>
+/// <tr>
+///  <td><code>Error::NotFound</code></td>
+///  <td>The database does not exist.</td>
+/// </tr>
+/// <tr>
+///  <td><code>Error::Unauthorized</code></td>
+///  <td>The client lacks permission to read the database.</td>
+/// </tr>
+/// </table>
+///
+/// # Examples
+///
+/// ```no_run
+/// extern crate chill;
+///
+/// let client = chill::Client::new("http://localhost:5984").unwrap();
+///
+/// for (name, rev) in client.list_local_documents("/baseball").run().unwrap() {
+///     println!("Local document {} is at revision {}", name, rev);
+/// }
+/// ```
+///
+pub struct ListLocalDocuments<'a, T: Transport + 'a, P: IntoDatabasePath> {
+    transport: &'a T,
+    db_path: Option<P>,
+}
+
+impl<'a, T: Transport + 'a, P: IntoDatabasePath> ListLocalDocuments<'a, T, P> {
+    #[doc(hidden)]
+    pub fn new(transport: &'a T, db_path: P) -> Self {
+        ListLocalDocuments {
+            transport: transport,
+            db_path: Some(db_path),
+        }
+    }
+
+    /// Executes the action and waits for the result.
+    pub fn run(mut self) -> Result<Vec<(LocalDocumentName, LocalRevision)>, Error> {
+        self.transport.send(
+            try!(self.make_request()),
+            JsonResponseDecoder::new(handle_response),
+        )
+    }
+
+    fn make_request(&mut self) -> Result<Request, Error> {
+        let db_path = try!(
+            std::mem::replace(&mut self.db_path, None)
+                .unwrap()
+                .into_database_path()
+        );
+        Ok(
+            self.transport
+                .get(db_path.iter().chain(vec!["_local_docs"]))
+                .with_accept_json(),
+        )
+    }
+}
+
+fn handle_response(response: JsonResponse) -> Result<Vec<(LocalDocumentName, LocalRevision)>, Error> {
+    match response.status_code() {
+        StatusCode::Ok => {
+            let content: LocalDocumentsJsonable = try!(response.decode_content());
+            let mut local_docs = Vec::with_capacity(content.rows.len());
+            for row in content.rows {
+                match row.doc_id {
+                    DocumentId::Local(name) => local_docs.push((name, row.revision)),
+                    _ => return Err(Error::UnexpectedResponse("Listed document is not a local document")),
+                }
+            }
+            Ok(local_docs)
+        }
+        StatusCode::NotFound => Err(Error::not_found(&response)),
+        StatusCode::Unauthorized => Err(Error::unauthorized(&response)),
+        _ => Err(Error::server_response(&response)),
+    }
+}
+
+#[derive(Debug, PartialEq)]
+struct LocalDocumentsJsonable {
+    rows: Vec<LocalDocumentsRowJsonable>,
+}
+
+impl serde::Deserialize for LocalDocumentsJsonable {
+    fn deserialize<D>(deserializer: &mut D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer,
+    {
+        enum Field {
+            Ignored,
+            Rows,
+        }
+
+        impl serde::Deserialize for Field {
+            fn deserialize<D>(deserializer: &mut D) -> Result<Self, D::Error>
+            where
+                D: serde::Deserializer,
+            {
+                struct Visitor;
+
+                impl serde::de::Visitor for Visitor {
+                    type Value = Field;
+
+                    fn visit_str<E>(&mut self, value: &str) -> Result<Self::Value, E>
+                    where
+                        E: serde::de::Error,
+                    {
+                        match value {
+                            "rows" => Ok(Field::Rows),
+                            _ => Ok(Field::Ignored),
+                        }
+                    }
+                }
+
+                deserializer.deserialize(Visitor)
+            }
+        }
+
+        struct Visitor;
+
+        impl serde::de::Visitor for Visitor {
+            type Value = LocalDocumentsJsonable;
+
+            fn visit_map<V>(&mut self, mut visitor: V) -> Result<Self::Value, V::Error>
+            where
+                V: serde::de::MapVisitor,
+            {
+                let mut rows = None;
+                loop {
+                    match try!(visitor.visit_key()) {
+                        Some(Field::Ignored) => {
+                            try!(visitor.visit_value::<serde_json::Value>());
+                        }
+                        Some(Field::Rows) => {
+                            rows = Some(try!(visitor.visit_value()));
+                        }
+                        None => {
+                            break;
+                        }
+                    }
+                }
+
+                try!(visitor.end());
+
+                Ok(LocalDocumentsJsonable {
+                    rows: match rows {
+                        Some(x) => x,
+                        None => try!(visitor.missing_field("rows")),
+                    },
+                })
+            }
+        }
+
+        static FIELDS: &'static [&'static str] = &["rows"];
+        deserializer.deserialize_struct("LocalDocumentsJsonable", FIELDS, Visitor)
+    }
+}
+
+#[derive(Debug, PartialEq)]
+struct LocalDocumentsRowJsonable {
+    doc_id: DocumentId,
+    revision: LocalRevision,
+}
+
+impl serde::Deserialize for LocalDocumentsRowJsonable {
+    fn deserialize<D>(deserializer: &mut D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer,
+    {
+        enum Field {
+            Id,
+            Ignored,
+            Value,
+        }
+
+        impl serde::Deserialize for Field {
+            fn deserialize<D>(deserializer: &mut D) -> Result<Self, D::Error>
+            where
+                D: serde::Deserializer,
+            {
+                struct Visitor;
+
+                impl serde::de::Visitor for Visitor {
+                    type Value = Field;
+
+                    fn visit_str<E>(&mut self, value: &str) -> Result<Self::Value, E>
+                    where
+                        E: serde::de::Error,
+                    {
+                        match value {
+                            "id" => Ok(Field::Id),
+                            "value" => Ok(Field::Value),
+                            _ => Ok(Field::Ignored),
+                        }
+                    }
+                }
+
+                deserializer.deserialize(Visitor)
+            }
+        }
+
+        struct Visitor;
+
+        impl serde::de::Visitor for Visitor {
+            type Value = LocalDocumentsRowJsonable;
+
+            fn visit_map<V>(&mut self, mut visitor: V) -> Result<Self::Value, V::Error>
+            where
+                V: serde::de::MapVisitor,
+            {
+                let mut id = None;
+                let mut value = None;
+                loop {
+                    match try!(visitor.visit_key()) {
+                        Some(Field::Id) => {
+                            id = Some(try!(visitor.visit_value()));
+                        }
+                        Some(Field::Ignored) => {
+                            try!(visitor.visit_value::<serde_json::Value>());
+                        }
+                        Some(Field::Value) => {
+                            value = Some(try!(visitor.visit_value::<LocalRevisionJsonable>()));
+                        }
+                        None => {
+                            break;
+                        }
+                    }
+                }
+
+                try!(visitor.end());
+
+                Ok(LocalDocumentsRowJsonable {
+                    doc_id: match id {
+                        Some(x) => x,
+                        None => try!(visitor.missing_field("id")),
+                    },
+                    revision: match value {
+                        Some(x) => x.revision,
+                        None => try!(visitor.missing_field("value")),
+                    },
+                })
+            }
+        }
+
+        static FIELDS: &'static [&'static str] = &["id", "value"];
+        deserializer.deserialize_struct("LocalDocumentsRowJsonable", FIELDS, Visitor)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use {DocumentId, Error, LocalDocumentName, LocalRevision, serde_json};
+    use transport::{JsonResponseBuilder, MockTransport, StatusCode, Transport};
+
+    #[test]
+    fn make_request_default() {
+
+        let transport = MockTransport::new();
+        let expected = transport.get(vec!["foo", "_local_docs"]).with_accept_json();
+
+        let got = {
+            let mut action = ListLocalDocuments::new(&transport, "/foo");
+            action.make_request().unwrap()
+        };
+
+        assert_eq!(expected, got);
+    }
+
+    #[test]
+    fn local_documents_row_jsonable_deserialize_ok() {
+
+        let expected = LocalDocumentsRowJsonable {
+            doc_id: DocumentId::from("_local/bar"),
+            revision: LocalRevision::parse("0-3").unwrap(),
+        };
+
+        let source = serde_json::builder::ObjectBuilder::new()
+            .insert("id", "_local/bar")
+            .insert("key", "_local/bar")
+            .insert_object("value", |x| x.insert("rev", "0-3"))
+            .build();
+
+        let got = serde_json::from_value(source).unwrap();
+        assert_eq!(expected, got);
+    }
+
+    #[test]
+    fn local_documents_row_jsonable_deserialize_nok_missing_value() {
+        let source = serde_json::builder::ObjectBuilder::new()
+            .insert("id", "_local/bar")
+            .insert("key", "_local/bar")
+            .build();
+        let got = serde_json::from_value::<LocalDocumentsRowJsonable>(source);
+        expect_json_error_missing_field!(got, "value");
+    }
+
+    #[test]
+    fn handle_response_ok() {
+
+        let response = JsonResponseBuilder::new(StatusCode::Ok)
+            .with_json_content_raw(
+                r#"{"total_rows":null,"offset":null,"rows":[
+                    {"id":"_local/bar","key":"_local/bar","value":{"rev":"0-3"}},
+                    {"id":"_local/qux","key":"_local/qux","value":{"rev":"0-1"}}
+                ]}"#,
+            )
+            .unwrap();
+
+        let expected = vec![
+            (LocalDocumentName::from("bar"), LocalRevision::parse("0-3").unwrap()),
+            (LocalDocumentName::from("qux"), LocalRevision::parse("0-1").unwrap()),
+        ];
+        let got = super::handle_response(response).unwrap();
+        assert_eq!(expected, got);
+    }
+
+    #[test]
+    fn handle_response_nok_not_local() {
+
+        let response = JsonResponseBuilder::new(StatusCode::Ok)
+            .with_json_content_raw(
+                r#"{"total_rows":null,"offset":null,"rows":[
+                    {"id":"bar","key":"bar","value":{"rev":"0-3"}}
+                ]}"#,
+            )
+            .unwrap();
+
+        match super::handle_response(response) {
+            Err(Error::UnexpectedResponse(..)) => (),
+            x @ _ => unexpected_result!(x),
+        }
+    }
+
+    #[test]
+    fn handle_response_not_found() {
+
+        let response = JsonResponseBuilder::new(StatusCode::NotFound)
+            .with_json_content_raw(r#"{"error":"not_found","reason":"Database does not exist."}"#)
+            .unwrap();
+
+        match super::handle_response(response) {
+            Err(Error::NotFound(ref error_response))
+                if error_response.error() == "not_found" && error_response.reason() == "Database does not exist." => (),
+            x @ _ => unexpected_result!(x),
+        }
+    }
+}
@@ -1,20 +1,63 @@
+pub mod copy_document;
 pub mod create_database;
 pub mod create_document;
+pub mod create_index;
 pub mod delete_document;
+pub mod delete_index;
+pub mod delete_local_document;
+pub mod deploy_design;
+pub mod execute_list;
+pub mod execute_show;
+pub mod execute_update_handler;
 pub mod execute_view;
+pub mod explain;
+pub mod find;
+mod find_options;
+pub mod head_attachment;
+pub mod head_document;
+pub mod list_indexes;
+pub mod list_local_documents;
+pub mod modify_document;
 pub mod read_document;
+pub mod read_local_document;
+pub mod read_server_info;
+pub mod read_uuids;
+pub mod resolve_conflicts;
 pub mod update_document;
+mod view_options;
+pub mod write_local_document;
 
+pub use self::copy_document::CopyDocument;
 pub use self::create_database::CreateDatabase;
 pub use self::create_document::CreateDocument;
+pub use self::create_index::CreateIndex;
 pub use self::delete_document::DeleteDocument;
+pub use self::delete_index::DeleteIndex;
+pub use self::delete_local_document::DeleteLocalDocument;
+pub use self::deploy_design::DeployDesign;
+pub use self::execute_list::ExecuteList;
+pub use self::execute_show::ExecuteShow;
+pub use self::execute_update_handler::ExecuteUpdateHandler;
 pub use self::execute_view::ExecuteView;
-pub use self::read_document::ReadDocument;
+pub use self::explain::Explain;
+pub use self::find::{Find, FindIter, TypedFindIter};
+pub use self::head_attachment::HeadAttachment;
+pub use self::head_document::HeadDocument;
+pub use self::list_indexes::ListIndexes;
+pub use self::list_local_documents::ListLocalDocuments;
+pub use self::modify_document::{Modification, ModifyDocument};
+pub use self::read_document::{OpenRevision, OpenRevs, ReadDocument, ReadOpenRevisions};
+pub use self::read_local_document::ReadLocalDocument;
+pub use self::read_server_info::ReadServerInfo;
+pub use self::read_uuids::ReadUuids;
+pub use self::resolve_conflicts::ResolveConflicts;
 pub use self::update_document::UpdateDocument;
+pub use self::write_local_document::WriteLocalDocument;
 
 pub mod query_keys {
 
-    use {Error, Revision, serde, transport};
+    use {Error, LocalRevision, Revision, serde, transport};
+    use action::read_document::OpenRevs;
 
     macro_rules! define_query_key {
         ($key_name:ident, $key_str:expr) => {
@@ -58,9 +101,18 @@ pub mod query_keys {
     define_query_key!(AttachmentsQueryKey, "attachments");
     define_query_value_bool!(AttachmentsQueryKey);
 
+    define_query_key!(ConflictsQueryKey, "conflicts");
+    define_query_value_bool!(ConflictsQueryKey);
+
+    define_query_key!(CountQueryKey, "count");
+    define_query_value_simple!(CountQueryKey, u64);
+
     define_query_key!(DescendingQueryKey, "descending");
     define_query_value_bool!(DescendingQueryKey);
 
+    define_query_key!(DeletedConflictsQueryKey, "deleted_conflicts");
+    define_query_value_bool!(DeletedConflictsQueryKey);
+
     define_query_key!(EndKeyQueryKey, "endkey");
     impl<T> transport::AsQueryValueFallible<EndKeyQueryKey> for T
     where
@@ -85,12 +137,35 @@ pub mod query_keys {
     define_query_key!(InclusiveEndQueryKey, "inclusive_end");
     define_query_value_bool!(InclusiveEndQueryKey);
 
+    define_query_key!(LatestQueryKey, "latest");
+    define_query_value_bool!(LatestQueryKey);
+
     define_query_key!(LimitQueryKey, "limit");
     define_query_value_simple!(LimitQueryKey, u64);
 
+    define_query_key!(OpenRevsQueryKey, "open_revs");
+    impl transport::AsQueryValueFallible<OpenRevsQueryKey> for OpenRevs {
+        type Value = String;
+        fn as_query_value_fallible(&self) -> Result<Self::Value, Error> {
+            use serde_json;
+            match self {
+                &OpenRevs::All => Ok(String::from("all")),
+                &OpenRevs::List(ref revisions) => {
+                    serde_json::to_string(revisions).map_err(|e| Error::JsonEncode { cause: e })
+                }
+            }
+        }
+    }
+
     define_query_key!(ReduceQueryKey, "reduce");
     define_query_value_bool!(ReduceQueryKey);
 
+    define_query_key!(RevisionsInfoQueryKey, "revs_info");
+    define_query_value_bool!(RevisionsInfoQueryKey);
+
+    define_query_key!(RevisionsQueryKey, "revs");
+    define_query_value_bool!(RevisionsQueryKey);
+
     define_query_key!(RevisionQueryKey, "rev");
     impl transport::AsQueryValue<RevisionQueryKey> for Revision {
         type Value = String;
@@ -98,6 +173,7 @@ pub mod query_keys {
             self.to_string()
         }
     }
+    define_query_value_simple!(RevisionQueryKey, LocalRevision);
 
     define_query_key!(StartKeyQueryKey, "startkey");
     impl<T> transport::AsQueryValueFallible<StartKeyQueryKey> for T
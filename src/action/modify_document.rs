@@ -0,0 +1,429 @@
+//! Defines an action for modifying a document via a read-modify-write loop
+//! that retries on conflict.
+
+use {Document, DocumentPath, Error, IntoDocumentPath, Revision, serde_json, std};
+use action::{CreateDocument, DeleteDocument, ReadDocument, UpdateDocument};
+use transport::Transport;
+
+const DEFAULT_MAX_RETRIES: u32 = 5;
+const DEFAULT_BACKOFF_MILLISECONDS: u64 = 20;
+
+/// Specifies how the `ModifyDocument` action should write a document.
+///
+/// A `Modification` is the result of the closure passed to
+/// `Client::modify_document`.
+///
+#[derive(Clone, Debug, PartialEq)]
+pub enum Modification {
+    /// Updates the document with the changes the closure made to it.
+    ///
+    /// The document must exist.
+    ///
+    Update,
+
+    /// Creates the document with the given content.
+    ///
+    /// The document must not exist—i.e., the closure must have received
+    /// `None`.
+    ///
+    Create(serde_json::Value),
+
+    /// Deletes the document.
+    ///
+    /// The document must exist.
+    ///
+    Delete,
+
+    /// Leaves the document as is.
+    ///
+    /// The document must exist.
+    ///
+    Unchanged,
+}
+
+/// Modifies a document by reading it, passing it to a closure, and writing
+/// the result, retrying if another client modifies the document in the
+/// meantime.
+///
+/// Chill modifies the document in a loop. First, it reads the document's
+/// current revision, as with the `ReadDocument` action. Second, it passes the
+/// document to the closure, which may change the document and returns a
+/// `Modification` specifying what to write. Third, it writes the document, as
+/// with the `UpdateDocument`, `CreateDocument`, or `DeleteDocument` action. If
+/// the write fails with a document conflict, then Chill waits and starts over
+/// with a fresh read. The wait starts at 20 milliseconds and doubles with each
+/// retry.
+///
+/// The closure receives `None` if the document doesn't exist—including if the
+/// document has been deleted. In that case, the closure may return
+/// `Modification::Create` to create the document.
+///
+/// The result is the document's revision after the write. If the closure
+/// returns `Modification::Unchanged`, then the action writes nothing and the
+/// result is the document's current revision.
+///
+/// # Errors
+///
+/// The following are _some_ errors that may occur when modifying a document.
+///
+/// <table>
+/// <tr>
+///  <td><code>Error::DocumentConflict</code></td>
+///  <td>The write conflicted on every attempt, including all retries.</td>
+/// </tr>
+/// <tr>
+///  <td><code>Error::NotFound</code></td>
+///  <td>The database does not exist, or the document does not exist and the
+///  closure returned a modification other than
+///  <code>Modification::Create</code>.</td>
+/// </tr>
+/// <tr>
+///  <td><code>Error::Unauthorized</code></td>
+///  <td>The client lacks permission to read or write the document.</td>
+/// </tr>
+/// </table>
+///
+/// Any error the closure returns aborts the action and becomes the action's
+/// result. Likewise, if the closure returns `Modification::Create` for a
+/// document that exists, then the action writes nothing and returns an error.
+///
+/// # Examples
+///
+/// ```no_run
+/// extern crate chill;
+/// extern crate serde_json;
+///
+/// use chill::action::Modification;
+///
+/// let client = chill::Client::new("http://localhost:5984").unwrap();
+///
+/// let rev = client
+///     .modify_document("/baseball/babe_ruth", |doc: Option<&mut chill::Document>| {
+///         match doc {
+///             None => {
+///                 let content = serde_json::builder::ObjectBuilder::new()
+///                     .insert("home_runs", 1)
+///                     .build();
+///                 Ok(Modification::Create(content))
+///             }
+///             Some(doc) => {
+///                 let mut content: serde_json::Value = try!(doc.get_content());
+///                 let home_runs = content.find("home_runs").and_then(|x| x.as_u64()).unwrap_or(0);
+///                 content.as_object_mut().unwrap().insert(
+///                     String::from("home_runs"),
+///                     serde_json::Value::U64(home_runs + 1),
+///                 );
+///                 doc.set_content(&content).unwrap();
+///                 Ok(Modification::Update)
+///             }
+///         }
+///     })
+///     .run()
+///     .unwrap();
+///
+/// println!("Document modified at revision {}", rev);
+/// ```
+///
+pub struct ModifyDocument<'a, T, P, F>
+where
+    F: FnMut(Option<&mut Document>) -> Result<Modification, Error>,
+    P: IntoDocumentPath,
+    T: Transport + 'a,
+{
+    transport: &'a T,
+    doc_path: Option<P>,
+    modifier: F,
+    max_retries: u32,
+    backoff: std::time::Duration,
+}
+
+impl<'a, T, P, F> ModifyDocument<'a, T, P, F>
+where
+    F: FnMut(Option<&mut Document>) -> Result<Modification, Error>,
+    P: IntoDocumentPath,
+    T: Transport + 'a,
+{
+    #[doc(hidden)]
+    pub fn new(transport: &'a T, doc_path: P, modifier: F) -> Self {
+        ModifyDocument {
+            transport: transport,
+            doc_path: Some(doc_path),
+            modifier: modifier,
+            max_retries: DEFAULT_MAX_RETRIES,
+            backoff: std::time::Duration::from_millis(DEFAULT_BACKOFF_MILLISECONDS),
+        }
+    }
+
+    /// Modifies the action to retry at most the given number of times after a
+    /// document conflict.
+    ///
+    /// By default, the action retries at most five times. With zero retries,
+    /// the action returns the first document conflict.
+    ///
+    pub fn with_max_retries(mut self, max_retries: u32) -> Self {
+        self.max_retries = max_retries;
+        self
+    }
+
+    /// Modifies the action to wait the given duration before its first retry.
+    ///
+    /// The wait doubles with each subsequent retry, up to 65,536 times the
+    /// given duration or the largest `Duration`, whichever is smaller.
+    ///
+    pub fn with_backoff(mut self, backoff: std::time::Duration) -> Self {
+        self.backoff = backoff;
+        self
+    }
+
+    /// Executes the action and waits for the result.
+    pub fn run(mut self) -> Result<Revision, Error> {
+
+        let doc_path = try!(
+            std::mem::replace(&mut self.doc_path, None)
+                .unwrap()
+                .into_document_path()
+        );
+
+        let max_retries = self.max_retries;
+        let backoff = self.backoff;
+        retry_on_conflict(max_retries, backoff, || self.run_once(&doc_path))
+    }
+
+    fn run_once(&mut self, doc_path: &DocumentPath) -> Result<Revision, Error> {
+
+        let read_result = ReadDocument::new(self.transport, doc_path.clone()).run();
+
+        match try!(plan_write(read_result, &mut self.modifier)) {
+            Write::Create(content) => {
+                CreateDocument::new(self.transport, doc_path.database_name().clone(), &content)
+                    .with_document_id(doc_path.document_id().clone())
+                    .run()
+                    .map(|(_doc_id, revision)| revision)
+            }
+            Write::Update(doc) => UpdateDocument::new(self.transport, &doc).run(),
+            Write::Delete(revision) => DeleteDocument::new(self.transport, doc_path.clone(), &revision).run(),
+            Write::Unchanged(revision) => Ok(revision),
+        }
+    }
+}
+
+// Specifies what one attempt of the action writes to the server.
+#[derive(Debug, PartialEq)]
+enum Write {
+    Create(serde_json::Value),
+    Update(Document),
+    Delete(Revision),
+    Unchanged(Revision),
+}
+
+// Passes the result of reading the document to the closure and decides what
+// to write, based on the closure's modification.
+fn plan_write<F>(read_result: Result<Document, Error>, modifier: &mut F) -> Result<Write, Error>
+where
+    F: FnMut(Option<&mut Document>) -> Result<Modification, Error>,
+{
+    match read_result {
+        Ok(mut doc) => {
+            match try!(modifier(Some(&mut doc))) {
+                Modification::Update => Ok(Write::Update(doc)),
+                Modification::Create(..) => Err(Error::DocumentExists),
+                Modification::Delete => Ok(Write::Delete(doc.revision().clone())),
+                Modification::Unchanged => Ok(Write::Unchanged(doc.revision().clone())),
+            }
+        }
+        Err(Error::NotFound(error_response)) => {
+            match try!(modifier(None)) {
+                Modification::Create(content) => Ok(Write::Create(content)),
+                _ => Err(Error::NotFound(error_response)),
+            }
+        }
+        Err(e) => Err(e),
+    }
+}
+
+// Calls the attempt function until it returns something other than a document
+// conflict or until there are no retries left.
+fn retry_on_conflict<F>(max_retries: u32, backoff: std::time::Duration, mut attempt: F) -> Result<Revision, Error>
+where
+    F: FnMut() -> Result<Revision, Error>,
+{
+    let mut retries = 0;
+    loop {
+        match attempt() {
+            Err(Error::DocumentConflict(..)) if retries < max_retries => {
+                std::thread::sleep(backoff_delay(backoff, retries));
+                retries += 1;
+            }
+            x @ _ => return x,
+        }
+    }
+}
+
+// Returns how long to wait before the given retry, counting from zero. The
+// delay saturates at the largest duration instead of overflowing.
+fn backoff_delay(backoff: std::time::Duration, retry: u32) -> std::time::Duration {
+    backoff
+        .checked_mul(1 << std::cmp::min(retry, 16))
+        .unwrap_or_else(|| std::time::Duration::new(std::u64::MAX, 999_999_999))
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::{Write, plan_write, retry_on_conflict};
+    use {Document, Error, ErrorResponse, Revision, serde_json, std};
+    use action::Modification;
+    use document::DocumentBuilder;
+
+    fn make_document() -> Document {
+        DocumentBuilder::new(
+            "/foo/bar",
+            Revision::parse("1-1234567890abcdef1234567890abcdef").unwrap(),
+        ).build_content(|x| x.insert("field_1", 42))
+            .unwrap()
+    }
+
+    fn make_conflict() -> Error {
+        Error::DocumentConflict(ErrorResponse::new("conflict", "Document update conflict."))
+    }
+
+    fn make_not_found() -> Error {
+        Error::NotFound(ErrorResponse::new("not_found", "missing"))
+    }
+
+    #[test]
+    fn plan_write_update() {
+        let mut modifier = |doc: Option<&mut Document>| {
+            let doc = doc.unwrap();
+            doc.set_content(&serde_json::builder::ObjectBuilder::new().insert("field_1", 17).build())
+                .unwrap();
+            Ok(Modification::Update)
+        };
+        let mut expected = make_document();
+        expected
+            .set_content(&serde_json::builder::ObjectBuilder::new().insert("field_1", 17).build())
+            .unwrap();
+        let got = plan_write(Ok(make_document()), &mut modifier).unwrap();
+        assert_eq!(Write::Update(expected), got);
+    }
+
+    #[test]
+    fn plan_write_delete() {
+        let mut modifier = |_: Option<&mut Document>| Ok(Modification::Delete);
+        let expected = Write::Delete(Revision::parse("1-1234567890abcdef1234567890abcdef").unwrap());
+        let got = plan_write(Ok(make_document()), &mut modifier).unwrap();
+        assert_eq!(expected, got);
+    }
+
+    #[test]
+    fn plan_write_unchanged() {
+        let mut modifier = |_: Option<&mut Document>| Ok(Modification::Unchanged);
+        let expected = Write::Unchanged(Revision::parse("1-1234567890abcdef1234567890abcdef").unwrap());
+        let got = plan_write(Ok(make_document()), &mut modifier).unwrap();
+        assert_eq!(expected, got);
+    }
+
+    #[test]
+    fn plan_write_create_when_missing() {
+        let content = serde_json::builder::ObjectBuilder::new().insert("field_1", 42).build();
+        let mut modifier = |doc: Option<&mut Document>| {
+            assert!(doc.is_none());
+            Ok(Modification::Create(content.clone()))
+        };
+        let got = plan_write(Err(make_not_found()), &mut modifier).unwrap();
+        assert_eq!(Write::Create(content.clone()), got);
+    }
+
+    #[test]
+    fn plan_write_nok_create_when_existing() {
+        let mut modifier = |_: Option<&mut Document>| Ok(Modification::Create(serde_json::Value::Null));
+        match plan_write(Ok(make_document()), &mut modifier) {
+            Err(Error::DocumentExists) => (),
+            x @ _ => unexpected_result!(x),
+        }
+    }
+
+    #[test]
+    fn plan_write_nok_update_when_missing() {
+        let mut modifier = |_: Option<&mut Document>| Ok(Modification::Update);
+        match plan_write(Err(make_not_found()), &mut modifier) {
+            Err(Error::NotFound(ref error_response)) if error_response.error() == "not_found" => (),
+            x @ _ => unexpected_result!(x),
+        }
+    }
+
+    #[test]
+    fn plan_write_nok_modifier_error() {
+        let mut modifier = |_: Option<&mut Document>| Err(Error::DocumentIsDeleted);
+        match plan_write(Ok(make_document()), &mut modifier) {
+            Err(Error::DocumentIsDeleted) => (),
+            x @ _ => unexpected_result!(x),
+        }
+    }
+
+    #[test]
+    fn retry_on_conflict_succeeds_after_conflicts() {
+        let mut attempts = 0;
+        let got = retry_on_conflict(5, std::time::Duration::from_millis(0), || {
+            attempts += 1;
+            if attempts < 3 {
+                Err(make_conflict())
+            } else {
+                Ok(Revision::parse("2-1234567890abcdef1234567890abcdef").unwrap())
+            }
+        });
+        assert_eq!(Revision::parse("2-1234567890abcdef1234567890abcdef").unwrap(), got.unwrap());
+        assert_eq!(3, attempts);
+    }
+
+    #[test]
+    fn retry_on_conflict_nok_retries_exhausted() {
+        let mut attempts = 0;
+        let got = retry_on_conflict(2, std::time::Duration::from_millis(0), || {
+            attempts += 1;
+            Err(make_conflict())
+        });
+        match got {
+            Err(Error::DocumentConflict(..)) => (),
+            x @ _ => unexpected_result!(x),
+        }
+        assert_eq!(3, attempts);
+    }
+
+    #[test]
+    fn retry_on_conflict_nok_other_error_is_not_retried() {
+        let mut attempts = 0;
+        let got = retry_on_conflict(5, std::time::Duration::from_millis(0), || {
+            attempts += 1;
+            Err(make_not_found())
+        });
+        match got {
+            Err(Error::NotFound(..)) => (),
+            x @ _ => unexpected_result!(x),
+        }
+        assert_eq!(1, attempts);
+    }
+
+    #[test]
+    fn backoff_delay_doubles() {
+        let backoff = std::time::Duration::from_millis(20);
+        assert_eq!(std::time::Duration::from_millis(20), super::backoff_delay(backoff, 0));
+        assert_eq!(std::time::Duration::from_millis(40), super::backoff_delay(backoff, 1));
+        assert_eq!(std::time::Duration::from_millis(80), super::backoff_delay(backoff, 2));
+    }
+
+    #[test]
+    fn backoff_delay_is_bounded() {
+        let backoff = std::time::Duration::from_millis(1);
+        let expected = std::time::Duration::from_millis(1 << 16);
+        assert_eq!(expected, super::backoff_delay(backoff, 16));
+        assert_eq!(expected, super::backoff_delay(backoff, 1000));
+    }
+
+    #[test]
+    fn backoff_delay_saturates() {
+        let backoff = std::time::Duration::from_secs(std::u64::MAX / 2);
+        let expected = std::time::Duration::new(std::u64::MAX, 999_999_999);
+        assert_eq!(expected, super::backoff_delay(backoff, 2));
+    }
+}
@@ -1,6 +1,6 @@
 //! Defines an action for reading a document from the CouchDB server.
 
-use {DatabaseName, Document, Error, IntoDocumentPath, Revision, std};
+use {DatabaseName, Document, Error, IntoDocumentPath, Revision, TypedDocument, serde, serde_json, std};
 use action::query_keys::*;
 use document::JsonDecodableDocument;
 use transport::{JsonResponse, JsonResponseDecoder, Request, StatusCode, Transport};
@@ -61,6 +61,10 @@ pub struct ReadDocument<'a, T: Transport + 'a, P: IntoDocumentPath> {
     doc_path: Option<P>,
     revision: Option<&'a Revision>,
     attachment_content: Option<AttachmentContent>,
+    conflicts: bool,
+    deleted_conflicts: bool,
+    revisions: bool,
+    revisions_info: bool,
 }
 
 impl<'a, T: Transport + 'a, P: IntoDocumentPath> ReadDocument<'a, T, P> {
@@ -71,6 +75,10 @@ impl<'a, T: Transport + 'a, P: IntoDocumentPath> ReadDocument<'a, T, P> {
             doc_path: Some(doc_path),
             revision: None,
             attachment_content: None,
+            conflicts: false,
+            deleted_conflicts: false,
+            revisions: false,
+            revisions_info: false,
         }
     }
 
@@ -95,6 +103,74 @@ impl<'a, T: Transport + 'a, P: IntoDocumentPath> ReadDocument<'a, T, P> {
         self
     }
 
+    /// Modifies the action to retrieve (or not retrieve) the revisions of the
+    /// document's conflicts.
+    ///
+    /// The `with_conflicts` method abstracts the `conflicts` query parameter of
+    /// the HTTP request `GET /db/docid`. The application may access the
+    /// conflicts via the `Document::conflicts` method.
+    ///
+    pub fn with_conflicts(mut self, yes_or_no: bool) -> Self {
+        self.conflicts = yes_or_no;
+        self
+    }
+
+    /// Modifies the action to retrieve (or not retrieve) the revisions of the
+    /// document's deleted conflicts.
+    ///
+    /// The `with_deleted_conflicts` method abstracts the `deleted_conflicts`
+    /// query parameter of the HTTP request `GET /db/docid`. The application
+    /// may access the deleted conflicts via the `Document::deleted_conflicts`
+    /// method.
+    ///
+    pub fn with_deleted_conflicts(mut self, yes_or_no: bool) -> Self {
+        self.deleted_conflicts = yes_or_no;
+        self
+    }
+
+    /// Modifies the action to retrieve (or not retrieve) the document's
+    /// revision history.
+    ///
+    /// The `with_revisions` method abstracts the `revs` query parameter of the
+    /// HTTP request `GET /db/docid`. The application may access the history
+    /// via the `Document::revisions` method.
+    ///
+    pub fn with_revisions(mut self, yes_or_no: bool) -> Self {
+        self.revisions = yes_or_no;
+        self
+    }
+
+    /// Modifies the action to retrieve (or not retrieve) the availability of
+    /// each revision in the document's history.
+    ///
+    /// The `with_revisions_info` method abstracts the `revs_info` query
+    /// parameter of the HTTP request `GET /db/docid`. The application may
+    /// access the information via the `Document::revisions_info` method.
+    ///
+    pub fn with_revisions_info(mut self, yes_or_no: bool) -> Self {
+        self.revisions_info = yes_or_no;
+        self
+    }
+
+    /// Modifies the action to read the given leaf revisions of the document
+    /// instead of only the winning revision.
+    ///
+    /// The `with_open_revisions` method abstracts the `open_revs` query
+    /// parameter of the HTTP request `GET /db/docid`. It returns a
+    /// `ReadOpenRevisions` action, whose result contains one item for each
+    /// requested revision. Any revision or conflicts set on this action are
+    /// discarded, whereas the attachment-content setting is kept.
+    ///
+    pub fn with_open_revisions(self, open_revs: OpenRevs) -> ReadOpenRevisions<'a, T, P> {
+        ReadOpenRevisions {
+            transport: self.transport,
+            doc_path: self.doc_path,
+            open_revs: open_revs,
+            attachment_content: self.attachment_content,
+            latest: false,
+        }
+    }
+
     /// Executes the action and waits for the result.
     pub fn run(mut self) -> Result<Document, Error> {
         let (request, db_name) = try!(self.make_request());
@@ -104,6 +180,19 @@ impl<'a, T: Transport + 'a, P: IntoDocumentPath> ReadDocument<'a, T, P> {
         )
     }
 
+    /// Executes the action, decodes the document content into the given type,
+    /// and waits for the result.
+    ///
+    /// The `run_typed` method decodes the content once, when the response
+    /// arrives, so that the application can then access and modify the content
+    /// directly and write it back via the `UpdateDocument` action. If the
+    /// content fails to decode then the method returns an `Error::JsonDecode`
+    /// error.
+    ///
+    pub fn run_typed<C: serde::Deserialize>(self) -> Result<TypedDocument<C>, Error> {
+        self.run().and_then(Document::into_typed)
+    }
+
     fn make_request(&mut self) -> Result<(Request, DatabaseName), Error> {
         let doc_path = try!(
             std::mem::replace(&mut self.doc_path, None)
@@ -119,6 +208,30 @@ impl<'a, T: Transport + 'a, P: IntoDocumentPath> ReadDocument<'a, T, P> {
             Some(AttachmentContent::All) => request.with_query(AttachmentsQueryKey, &true),
         };
 
+        let request = if self.conflicts {
+            request.with_query(ConflictsQueryKey, &true)
+        } else {
+            request
+        };
+
+        let request = if self.deleted_conflicts {
+            request.with_query(DeletedConflictsQueryKey, &true)
+        } else {
+            request
+        };
+
+        let request = if self.revisions {
+            request.with_query(RevisionsQueryKey, &true)
+        } else {
+            request
+        };
+
+        let request = if self.revisions_info {
+            request.with_query(RevisionsInfoQueryKey, &true)
+        } else {
+            request
+        };
+
         let request = match self.revision {
             None => request,
             Some(rev) => request.with_query(RevisionQueryKey, rev),
@@ -156,11 +269,261 @@ pub enum AttachmentContent {
     All,
 }
 
+/// Specifies the leaf revisions of a document to read via the
+/// `ReadOpenRevisions` action.
+///
+/// `OpenRevs` abstracts the `open_revs` query parameter of the HTTP request
+/// `GET /db/doc_id`.
+///
+#[derive(Clone, Debug, PartialEq)]
+pub enum OpenRevs {
+    /// Specifies all leaf revisions—i.e., the winning revision plus all
+    /// conflicts, including deleted ones.
+    All,
+
+    /// Specifies the given revisions.
+    List(Vec<Revision>),
+}
+
+/// Contains the result for one revision read via the `ReadOpenRevisions`
+/// action.
+#[derive(Clone, Debug, PartialEq)]
+pub enum OpenRevision {
+    /// The server has the revision, whose document is given. The document may
+    /// be deleted—see the `Document::is_deleted` method.
+    Found(Document),
+
+    /// The server doesn't have the given revision.
+    Missing(Revision),
+}
+
+/// Reads one or more leaf revisions of a document from the CouchDB server.
+///
+/// Chill reads the revisions by sending an HTTP request to `GET` from the
+/// document's path with the `open_revs` query parameter set. The result
+/// contains one `OpenRevision` for each revision the server returns. This is
+/// useful for resolving conflicts, which requires the content of every
+/// conflicting revision.
+///
+/// The application constructs a `ReadOpenRevisions` action via the
+/// `ReadDocument::with_open_revisions` method.
+///
+/// # Errors
+///
+/// The following are _some_ errors that may occur when reading open
+/// revisions.
+///
+/// <table>
+/// <tr>
+///  <td><code>Error::NotFound</code></td>
+///  <td>The database or document does not exist.</td>
+/// </tr>
+/// <tr>
+///  <td><code>Error::Unauthorized</code></td>
+///  <td>The client lacks permission to read the document.</td>
+/// </tr>
+/// </table>
+///
+/// # Examples
+///
+/// ```no_run
+/// extern crate chill;
+///
+/// use chill::action::{OpenRevision, OpenRevs};
+///
+/// let client = chill::Client::new("http://localhost:5984").unwrap();
+///
+/// let leaves = client.read_document("/baseball/babe_ruth")
+///                    .with_open_revisions(OpenRevs::All)
+///                    .run()
+///                    .unwrap();
+///
+/// for leaf in leaves {
+///     match leaf {
+///         OpenRevision::Found(doc) => println!("Found revision {}", doc.revision()),
+///         OpenRevision::Missing(rev) => println!("Missing revision {}", rev),
+///     }
+/// }
+/// ```
+///
+pub struct ReadOpenRevisions<'a, T: Transport + 'a, P: IntoDocumentPath> {
+    transport: &'a T,
+    doc_path: Option<P>,
+    open_revs: OpenRevs,
+    attachment_content: Option<AttachmentContent>,
+    latest: bool,
+}
+
+impl<'a, T: Transport + 'a, P: IntoDocumentPath> ReadOpenRevisions<'a, T, P> {
+    /// Modifies the action to read (or not read) the latest leaf revision
+    /// descending from each requested revision instead of the requested
+    /// revision itself.
+    ///
+    /// The `with_latest` method abstracts the `latest` query parameter of the
+    /// HTTP request `GET /db/docid`.
+    ///
+    pub fn with_latest(mut self, yes_or_no: bool) -> Self {
+        self.latest = yes_or_no;
+        self
+    }
+
+    /// Executes the action and waits for the result.
+    pub fn run(mut self) -> Result<Vec<OpenRevision>, Error> {
+        let (request, db_name) = try!(self.make_request());
+        self.transport.send(
+            request,
+            JsonResponseDecoder::new(move |response| handle_open_revisions_response(response, db_name)),
+        )
+    }
+
+    fn make_request(&mut self) -> Result<(Request, DatabaseName), Error> {
+        let doc_path = try!(
+            std::mem::replace(&mut self.doc_path, None)
+                .unwrap()
+                .into_document_path()
+        );
+        let db_name = doc_path.database_name().clone();
+        let request = try!(
+            self.transport
+                .get(doc_path.iter())
+                .with_accept_json()
+                .with_query_fallible(OpenRevsQueryKey, &self.open_revs)
+        );
+
+        let request = match self.attachment_content {
+            None => request,
+            Some(AttachmentContent::None) => request.with_query(AttachmentsQueryKey, &false),
+            Some(AttachmentContent::All) => request.with_query(AttachmentsQueryKey, &true),
+        };
+
+        let request = if self.latest {
+            request.with_query(LatestQueryKey, &true)
+        } else {
+            request
+        };
+
+        Ok((request, db_name))
+    }
+}
+
+fn handle_open_revisions_response(response: JsonResponse, db_name: DatabaseName) -> Result<Vec<OpenRevision>, Error> {
+    match response.status_code() {
+        StatusCode::Ok => {
+            let decoded: Vec<OpenRevisionJsonable> = try!(response.decode_content());
+            Ok(
+                decoded
+                    .into_iter()
+                    .map(|x| match x {
+                        OpenRevisionJsonable::Ok(doc) => {
+                            OpenRevision::Found(Document::new_from_decoded(db_name.clone(), doc))
+                        }
+                        OpenRevisionJsonable::Missing(rev) => OpenRevision::Missing(rev),
+                    })
+                    .collect(),
+            )
+        }
+        StatusCode::NotFound => Err(Error::not_found(&response)),
+        StatusCode::Unauthorized => Err(Error::unauthorized(&response)),
+        _ => Err(Error::server_response(&response)),
+    }
+}
+
+// OpenRevisionJsonable is an intermediate type for decoding one item of the
+// server's response to an open_revs request--i.e., either {"ok": doc} or
+// {"missing": rev}.
+#[derive(Debug, PartialEq)]
+enum OpenRevisionJsonable {
+    Ok(JsonDecodableDocument),
+    Missing(Revision),
+}
+
+impl serde::Deserialize for OpenRevisionJsonable {
+    fn deserialize<D>(deserializer: &mut D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer,
+    {
+        enum Field {
+            Ignored,
+            Missing,
+            Ok,
+        }
+
+        impl serde::Deserialize for Field {
+            fn deserialize<D>(deserializer: &mut D) -> Result<Field, D::Error>
+            where
+                D: serde::Deserializer,
+            {
+                struct Visitor;
+
+                impl serde::de::Visitor for Visitor {
+                    type Value = Field;
+
+                    fn visit_str<E>(&mut self, value: &str) -> Result<Field, E>
+                    where
+                        E: serde::de::Error,
+                    {
+                        match value {
+                            "missing" => Ok(Field::Missing),
+                            "ok" => Ok(Field::Ok),
+                            _ => Ok(Field::Ignored),
+                        }
+                    }
+                }
+
+                deserializer.deserialize(Visitor)
+            }
+        }
+
+        struct Visitor;
+
+        impl serde::de::Visitor for Visitor {
+            type Value = OpenRevisionJsonable;
+
+            fn visit_map<V>(&mut self, mut visitor: V) -> Result<Self::Value, V::Error>
+            where
+                V: serde::de::MapVisitor,
+            {
+                let mut result = None;
+
+                loop {
+                    match try!(visitor.visit_key()) {
+                        Some(Field::Ignored) => {
+                            try!(visitor.visit_value::<serde_json::Value>());
+                        }
+                        Some(Field::Missing) => {
+                            result = Some(OpenRevisionJsonable::Missing(try!(visitor.visit_value())));
+                        }
+                        Some(Field::Ok) => {
+                            result = Some(OpenRevisionJsonable::Ok(try!(visitor.visit_value())));
+                        }
+                        None => {
+                            break;
+                        }
+                    }
+                }
+
+                try!(visitor.end());
+
+                match result {
+                    Some(x) => Ok(x),
+                    None => {
+                        use serde::de::Error;
+                        Err(V::Error::invalid_value("Expected an \"ok\" or \"missing\" field"))
+                    }
+                }
+            }
+        }
+
+        static FIELDS: &'static [&'static str] = &["missing", "ok"];
+        deserializer.deserialize_struct("OpenRevisionJsonable", FIELDS, Visitor)
+    }
+}
+
 #[cfg(test)]
 mod tests {
 
     use super::*;
-    use {DatabaseName, Error, Revision};
+    use {DatabaseName, Error, Revision, serde_json};
     use document::DocumentBuilder;
     use transport::{JsonResponseBuilder, MockTransport, StatusCode, Transport};
 
@@ -245,6 +608,205 @@ mod tests {
         assert_eq!(expected, got);
     }
 
+    #[test]
+    fn make_request_with_conflicts_and_deleted_conflicts() {
+
+        let transport = MockTransport::new();
+
+        let expected = (
+            transport
+                .get(vec!["foo", "bar"])
+                .with_accept_json()
+                .with_query_literal("conflicts", "true")
+                .with_query_literal("deleted_conflicts", "true"),
+            DatabaseName::from("foo"),
+        );
+
+        let got = {
+            let mut action = ReadDocument::new(&transport, "/foo/bar")
+                .with_conflicts(true)
+                .with_deleted_conflicts(true);
+            action.make_request().unwrap()
+        };
+
+        assert_eq!(expected, got);
+    }
+
+    #[test]
+    fn make_request_with_revisions_and_revisions_info() {
+
+        let transport = MockTransport::new();
+
+        let expected = (
+            transport
+                .get(vec!["foo", "bar"])
+                .with_accept_json()
+                .with_query_literal("revs", "true")
+                .with_query_literal("revs_info", "true"),
+            DatabaseName::from("foo"),
+        );
+
+        let got = {
+            let mut action = ReadDocument::new(&transport, "/foo/bar")
+                .with_revisions(true)
+                .with_revisions_info(true);
+            action.make_request().unwrap()
+        };
+
+        assert_eq!(expected, got);
+    }
+
+    #[test]
+    fn handle_response_ok_with_revisions() {
+
+        let response = JsonResponseBuilder::new(StatusCode::Ok)
+            .with_json_content_raw(
+                r#"{"_id": "bar", "_rev": "2-1234567890abcdef1234567890abcdef",
+                "_revisions": {"start": 2, "ids": ["1234567890abcdef1234567890abcdef",
+                "abcdef1234567890abcdef1234567890"]}}"#,
+            )
+            .unwrap();
+
+        let expected = vec![
+            Revision::parse("2-1234567890abcdef1234567890abcdef").unwrap(),
+            Revision::parse("1-abcdef1234567890abcdef1234567890").unwrap(),
+        ];
+
+        let got = super::handle_response(response, DatabaseName::from("foo")).unwrap();
+        assert_eq!(&expected[..], got.revisions());
+        assert!(got.revisions_info().is_empty());
+    }
+
+    #[test]
+    fn handle_response_ok_with_conflicts() {
+
+        let response = JsonResponseBuilder::new(StatusCode::Ok)
+            .with_json_content_raw(
+                r#"{"_id": "bar", "_rev": "2-1234567890abcdef1234567890abcdef",
+                "_conflicts": ["2-abcdef1234567890abcdef1234567890"], "field": 42}"#,
+            )
+            .unwrap();
+
+        let got = super::handle_response(response, DatabaseName::from("foo")).unwrap();
+        assert_eq!(
+            &[Revision::parse("2-abcdef1234567890abcdef1234567890").unwrap()],
+            got.conflicts()
+        );
+        assert!(got.deleted_conflicts().is_empty());
+        assert_eq!(
+            serde_json::builder::ObjectBuilder::new().insert("field", 42).build(),
+            got.get_content::<serde_json::Value>().unwrap()
+        );
+    }
+
+    #[test]
+    fn make_request_with_open_revisions_all() {
+
+        let transport = MockTransport::new();
+
+        let expected = (
+            transport
+                .get(vec!["foo", "bar"])
+                .with_accept_json()
+                .with_query_literal("open_revs", "all"),
+            DatabaseName::from("foo"),
+        );
+
+        let got = {
+            let mut action = ReadDocument::new(&transport, "/foo/bar").with_open_revisions(OpenRevs::All);
+            action.make_request().unwrap()
+        };
+
+        assert_eq!(expected, got);
+    }
+
+    #[test]
+    fn make_request_with_open_revisions_list_and_latest() {
+
+        let transport = MockTransport::new();
+
+        let expected = (
+            transport
+                .get(vec!["foo", "bar"])
+                .with_accept_json()
+                .with_query_literal(
+                    "open_revs",
+                    r#"["1-1234567890abcdef1234567890abcdef","2-abcdef1234567890abcdef1234567890"]"#,
+                )
+                .with_query_literal("attachments", "true")
+                .with_query_literal("latest", "true"),
+            DatabaseName::from("foo"),
+        );
+
+        let revs = vec![
+            Revision::parse("1-1234567890abcdef1234567890abcdef").unwrap(),
+            Revision::parse("2-abcdef1234567890abcdef1234567890").unwrap(),
+        ];
+
+        let got = {
+            let mut action = ReadDocument::new(&transport, "/foo/bar")
+                .with_attachment_content(AttachmentContent::All)
+                .with_open_revisions(OpenRevs::List(revs))
+                .with_latest(true);
+            action.make_request().unwrap()
+        };
+
+        assert_eq!(expected, got);
+    }
+
+    #[test]
+    fn handle_open_revisions_response_ok() {
+
+        let response = JsonResponseBuilder::new(StatusCode::Ok)
+            .with_json_content_raw(
+                r#"[{"ok":{"_id":"bar","_rev":"1-1234567890abcdef1234567890abcdef","field":42}},
+                {"missing":"2-abcdef1234567890abcdef1234567890"}]"#,
+            )
+            .unwrap();
+
+        let expected = vec![
+            OpenRevision::Found(
+                DocumentBuilder::new(
+                    "/foo/bar",
+                    Revision::parse("1-1234567890abcdef1234567890abcdef").unwrap(),
+                ).build_content(|x| x.insert("field", 42))
+                    .unwrap(),
+            ),
+            OpenRevision::Missing(
+                Revision::parse("2-abcdef1234567890abcdef1234567890").unwrap(),
+            ),
+        ];
+
+        let got = super::handle_open_revisions_response(response, DatabaseName::from("foo")).unwrap();
+        assert_eq!(expected, got);
+    }
+
+    #[test]
+    fn handle_open_revisions_response_nok_bad_item() {
+
+        let response = JsonResponseBuilder::new(StatusCode::Ok)
+            .with_json_content_raw(r#"[{}]"#)
+            .unwrap();
+
+        match super::handle_open_revisions_response(response, DatabaseName::from("foo")) {
+            Err(Error::JsonDecode { .. }) => (),
+            x @ _ => unexpected_result!(x),
+        }
+    }
+
+    #[test]
+    fn handle_open_revisions_response_not_found() {
+
+        let response = JsonResponseBuilder::new(StatusCode::NotFound)
+            .with_json_content_raw(r#"{"error":"not_found","reason":"missing"}"#)
+            .unwrap();
+
+        match super::handle_open_revisions_response(response, DatabaseName::from("foo")) {
+            Err(Error::NotFound(..)) => (),
+            x @ _ => unexpected_result!(x),
+        }
+    }
+
     #[test]
     fn handle_response_ok() {
 
@@ -0,0 +1,154 @@
+//! Defines an action for reading a local document from the CouchDB server.
+
+use {DocumentId, DocumentPath, Error, IntoDatabasePath, LocalDocument, LocalDocumentName, std};
+use transport::{JsonResponse, JsonResponseDecoder, Request, StatusCode, Transport};
+
+/// Reads a local document from the CouchDB server and returns the result.
+///
+/// Chill reads the local document by sending an HTTP request to `GET` from the
+/// document's path—i.e., `/{db}/_local/{name}`. Unlike `ReadDocument`, this
+/// action decodes the document's revision as a `LocalRevision`, which has the
+/// form `0-N` that the server uses for local documents.
+///
+/// # Errors
+///
+/// The following are _some_ errors that may occur when reading a local
+/// document.
+///
+/// <table>
+/// <tr>
+///  <td><code>Error::NotFound</code></td>
+///  <td>The local document does not exist.</td>
+/// </tr>
+/// <tr>
+///  <td><code>Error::Unauthorized</code></td>
+///  <td>The client lacks permission to read the local document.</td>
+/// </tr>
+/// </table>
+///
+/// # Examples
+///
+/// ```no_run
+/// extern crate chill;
+/// extern crate serde_json;
+///
+/// let client = chill::Client::new("http://localhost:5984").unwrap();
+///
+/// let doc = client.read_local_document("/baseball", "checkpoint").run().unwrap();
+/// let content = doc.get_content::<serde_json::Value>().unwrap();
+/// println!("Checkpoint at revision {}: {}", doc.revision(), content);
+/// ```
+///
+pub struct ReadLocalDocument<'a, T: Transport + 'a, P: IntoDatabasePath> {
+    transport: &'a T,
+    db_path: Option<P>,
+    name: LocalDocumentName,
+}
+
+impl<'a, T: Transport + 'a, P: IntoDatabasePath> ReadLocalDocument<'a, T, P> {
+    #[doc(hidden)]
+    pub fn new<N>(transport: &'a T, db_path: P, name: N) -> Self
+    where
+        N: Into<LocalDocumentName>,
+    {
+        ReadLocalDocument {
+            transport: transport,
+            db_path: Some(db_path),
+            name: name.into(),
+        }
+    }
+
+    /// Executes the action and waits for the result.
+    pub fn run(mut self) -> Result<LocalDocument, Error> {
+        self.transport.send(
+            try!(self.make_request()),
+            JsonResponseDecoder::new(handle_response),
+        )
+    }
+
+    fn make_request(&mut self) -> Result<Request, Error> {
+        let db_path = try!(
+            std::mem::replace(&mut self.db_path, None)
+                .unwrap()
+                .into_database_path()
+        );
+        let doc_path = DocumentPath::from((db_path, DocumentId::from(self.name.clone())));
+        Ok(self.transport.get(doc_path.iter()).with_accept_json())
+    }
+}
+
+fn handle_response(response: JsonResponse) -> Result<LocalDocument, Error> {
+    match response.status_code() {
+        StatusCode::Ok => response.decode_content(),
+        StatusCode::NotFound => Err(Error::not_found(&response)),
+        StatusCode::Unauthorized => Err(Error::unauthorized(&response)),
+        _ => Err(Error::server_response(&response)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use {Error, LocalRevision};
+    use transport::{JsonResponseBuilder, MockTransport, StatusCode, Transport};
+
+    #[test]
+    fn make_request_default() {
+
+        let transport = MockTransport::new();
+        let expected = transport
+            .get(vec!["foo", "_local", "bar"])
+            .with_accept_json();
+
+        let got = {
+            let mut action = ReadLocalDocument::new(&transport, "/foo", "bar");
+            action.make_request().unwrap()
+        };
+
+        assert_eq!(expected, got);
+    }
+
+    #[test]
+    fn handle_response_ok() {
+
+        let response = JsonResponseBuilder::new(StatusCode::Ok)
+            .with_json_content_raw(r#"{"_id":"_local/bar","_rev":"0-3","seq":42}"#)
+            .unwrap();
+
+        let got = super::handle_response(response).unwrap();
+        assert_eq!("bar", got.name().as_ref());
+        assert_eq!(&LocalRevision::parse("0-3").unwrap(), got.revision());
+    }
+
+    #[test]
+    fn handle_response_not_found() {
+
+        let response = JsonResponseBuilder::new(StatusCode::NotFound)
+            .with_json_content_raw(r#"{"error":"not_found","reason":"missing"}"#)
+            .unwrap();
+
+        match super::handle_response(response) {
+            Err(Error::NotFound(ref error_response))
+                if error_response.error() == "not_found" && error_response.reason() == "missing" => (),
+            x @ _ => unexpected_result!(x),
+        }
+    }
+
+    #[test]
+    fn handle_response_unauthorized() {
+
+        let response = JsonResponseBuilder::new(StatusCode::Unauthorized)
+            .with_json_content_raw(
+                r#"{"error":"unauthorized","reason":"Authentication required."}"#,
+            )
+            .unwrap();
+
+        match super::handle_response(response) {
+            Err(Error::Unauthorized(ref error_response))
+                if error_response.error() == "unauthorized" && error_response.reason() == "Authentication required." =>
+                (),
+            x @ _ => unexpected_result!(x),
+        }
+    }
+}
@@ -0,0 +1,105 @@
+//! Defines an action for reading information about the CouchDB server.
+
+use {Error, ServerInfo};
+use transport::{JsonResponse, JsonResponseDecoder, Request, StatusCode, Transport};
+
+/// Reads information about the CouchDB server, such as its version.
+///
+/// Chill reads the information by sending an HTTP request to the CouchDB
+/// server to `GET` from the server's root path. The result is a `ServerInfo`
+/// containing the server's welcome message, version, vendor, and other
+/// details.
+///
+/// # Examples
+///
+/// ```no_run
+/// extern crate chill;
+///
+/// let client = chill::Client::new("http://localhost:5984").unwrap();
+/// let info = client.server_info().run().unwrap();
+///
+/// if info.version() >= chill::ServerVersion::new(2, 0, 0) {
+///     println!("Server supports Mango queries");
+/// }
+/// ```
+///
+pub struct ReadServerInfo<'a, T: Transport + 'a> {
+    transport: &'a T,
+}
+
+impl<'a, T: Transport + 'a> ReadServerInfo<'a, T> {
+    #[doc(hidden)]
+    pub fn new(transport: &'a T) -> Self {
+        ReadServerInfo { transport: transport }
+    }
+
+    pub fn run(self) -> Result<ServerInfo, Error> {
+        self.transport.send(self.make_request(), JsonResponseDecoder::new(handle_response))
+    }
+
+    fn make_request(&self) -> Request {
+        self.transport.get(Vec::<&str>::new()).with_accept_json()
+    }
+}
+
+fn handle_response(response: JsonResponse) -> Result<ServerInfo, Error> {
+    match response.status_code() {
+        StatusCode::Ok => Ok(try!(response.decode_content())),
+        StatusCode::Unauthorized => Err(Error::unauthorized(&response)),
+        _ => Err(Error::server_response(&response)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use {Error, ServerVersion};
+    use transport::{JsonResponseBuilder, MockTransport, StatusCode, Transport};
+
+    #[test]
+    fn make_request_default() {
+
+        let transport = MockTransport::new();
+        let expected = transport.get(Vec::<&str>::new()).with_accept_json();
+
+        let got = {
+            let action = ReadServerInfo::new(&transport);
+            action.make_request()
+        };
+
+        assert_eq!(expected, got);
+    }
+
+    #[test]
+    fn handle_response_ok() {
+
+        let response = JsonResponseBuilder::new(StatusCode::Ok)
+            .with_json_content_raw(
+                r#"{"couchdb":"Welcome","version":"2.1.0","features":["scheduler"],
+                "vendor":{"name":"The Apache Software Foundation"}}"#,
+            )
+            .unwrap();
+
+        let got = super::handle_response(response).unwrap();
+        assert_eq!("Welcome", got.couchdb());
+        assert_eq!(ServerVersion::new(2, 1, 0), got.version());
+    }
+
+    #[test]
+    fn handle_response_unauthorized() {
+
+        let response = JsonResponseBuilder::new(StatusCode::Unauthorized)
+            .with_json_content_raw(
+                r#"{"error":"unauthorized","reason":"Authentication required."}"#,
+            )
+            .unwrap();
+
+        match super::handle_response(response) {
+            Err(Error::Unauthorized(ref error_response))
+                if error_response.error() == "unauthorized" && error_response.reason() == "Authentication required." =>
+                (),
+            x @ _ => unexpected_result!(x),
+        }
+    }
+}
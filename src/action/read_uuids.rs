@@ -0,0 +1,120 @@
+//! Defines an action for reading server-generated UUIDs.
+
+use Error;
+use action::query_keys::*;
+use transport::{JsonResponse, JsonResponseDecoder, Request, StatusCode, Transport};
+use uuids::UuidsJsonable;
+
+/// Reads one or more UUIDs generated by the CouchDB server.
+///
+/// Chill reads the UUIDs by sending an HTTP request to the CouchDB server to
+/// `GET` from the server's `_uuids` path. The result is a list of UUIDs, each
+/// as a string in the format of the server's configured UUID algorithm. The
+/// UUIDs are suitable for use as document ids.
+///
+/// # Examples
+///
+/// ```no_run
+/// extern crate chill;
+///
+/// let client = chill::Client::new("http://localhost:5984").unwrap();
+///
+/// for uuid in client.uuids(10).run().unwrap() {
+///     println!("{}", uuid);
+/// }
+/// ```
+///
+pub struct ReadUuids<'a, T: Transport + 'a> {
+    transport: &'a T,
+    count: u64,
+}
+
+impl<'a, T: Transport + 'a> ReadUuids<'a, T> {
+    #[doc(hidden)]
+    pub fn new(transport: &'a T, count: u64) -> Self {
+        ReadUuids {
+            transport: transport,
+            count: count,
+        }
+    }
+
+    pub fn run(self) -> Result<Vec<String>, Error> {
+        self.transport.send(self.make_request(), JsonResponseDecoder::new(handle_response))
+    }
+
+    fn make_request(&self) -> Request {
+        self.transport
+            .get(vec!["_uuids"])
+            .with_accept_json()
+            .with_query(CountQueryKey, &self.count)
+    }
+}
+
+fn handle_response(response: JsonResponse) -> Result<Vec<String>, Error> {
+    match response.status_code() {
+        StatusCode::Ok => {
+            let body: UuidsJsonable = try!(response.decode_content());
+            Ok(body.into_uuids())
+        }
+        StatusCode::Unauthorized => Err(Error::unauthorized(&response)),
+        _ => Err(Error::server_response(&response)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use Error;
+    use transport::{JsonResponseBuilder, MockTransport, StatusCode, Transport};
+
+    #[test]
+    fn make_request_default() {
+
+        let transport = MockTransport::new();
+        let expected = transport
+            .get(vec!["_uuids"])
+            .with_accept_json()
+            .with_query_literal("count", "3");
+
+        let got = {
+            let action = ReadUuids::new(&transport, 3);
+            action.make_request()
+        };
+
+        assert_eq!(expected, got);
+    }
+
+    #[test]
+    fn handle_response_ok() {
+
+        let response = JsonResponseBuilder::new(StatusCode::Ok)
+            .with_json_content_raw(
+                r#"{"uuids":["75480ca477454894678e22eec6002413","75480ca477454894678e22eec600250b"]}"#,
+            )
+            .unwrap();
+
+        let expected = vec![
+            String::from("75480ca477454894678e22eec6002413"),
+            String::from("75480ca477454894678e22eec600250b"),
+        ];
+
+        let got = super::handle_response(response).unwrap();
+        assert_eq!(expected, got);
+    }
+
+    #[test]
+    fn handle_response_bad_request() {
+
+        let response = JsonResponseBuilder::new(StatusCode::BadRequest)
+            .with_json_content_raw(
+                r#"{"error":"bad_request","reason":"count parameter too large"}"#,
+            )
+            .unwrap();
+
+        match super::handle_response(response) {
+            Err(Error::ServerResponse { status_code: StatusCode::BadRequest, .. }) => (),
+            x @ _ => unexpected_result!(x),
+        }
+    }
+}
@@ -0,0 +1,485 @@
+//! Defines an action for resolving a document's conflicts.
+
+use {ConflictResolver, Document, DocumentPath, Error, ErrorResponse, IntoDocumentPath, Resolution, Revision, serde,
+     serde_json, std};
+use action::{OpenRevision, OpenRevs, ReadDocument};
+use conflict::winning_leaf;
+use transport::{JsonResponse, JsonResponseDecoder, Request, StatusCode, Transport};
+
+/// Resolves a document's conflicts by writing a winner and deleting all losing
+/// leaf revisions.
+///
+/// Chill resolves the conflicts in two steps. First, it reads all leaf
+/// revisions of the document, as with the `ReadOpenRevisions` action, and
+/// passes the undeleted leaves to the `ConflictResolver`. Second, it sends an
+/// HTTP request to `POST` to the database's `_bulk_docs` path, containing
+/// both the resolved content, if any, and a deletion for each losing leaf.
+/// The write and the deletions are sent in one request, but the request is
+/// not atomic: the server applies each write separately, so some writes may
+/// succeed while others fail—e.g., because another client updated a leaf in
+/// the meantime. If any write fails, then the action returns an error for the
+/// first failed write, and the application may run the action again to
+/// resolve the remaining conflicts.
+///
+/// The result is the revision of the document's winning leaf. If the
+/// document has no conflicts, then the action sends no writes and the result
+/// is the document's current revision.
+///
+/// # Errors
+///
+/// The following are _some_ errors that may occur when resolving conflicts.
+///
+/// <table>
+/// <tr>
+///  <td><code>Error::DocumentConflict</code></td>
+///  <td>A leaf was updated after Chill read it. The application may run the
+///  action again.</td>
+/// </tr>
+/// <tr>
+///  <td><code>Error::NotFound</code></td>
+///  <td>The database or document does not exist, or the document is
+///  deleted.</td>
+/// </tr>
+/// <tr>
+///  <td><code>Error::Unauthorized</code></td>
+///  <td>The client lacks permission to update the document.</td>
+/// </tr>
+/// </table>
+///
+/// The action also returns an error, without writing anything, if the
+/// resolver returns `Resolution::Keep` with a revision that isn't one of the
+/// leaves given to it.
+///
+/// # Examples
+///
+/// ```no_run
+/// extern crate chill;
+///
+/// let client = chill::Client::new("http://localhost:5984").unwrap();
+///
+/// let rev = client.resolve_conflicts("/baseball/babe_ruth", chill::HighestSequenceWins::new())
+///                 .run()
+///                 .unwrap();
+///
+/// println!("Document resolved at revision {}", rev);
+/// ```
+///
+pub struct ResolveConflicts<'a, T, P, R>
+where
+    T: Transport + 'a,
+    P: IntoDocumentPath,
+    R: ConflictResolver,
+{
+    transport: &'a T,
+    doc_path: Option<P>,
+    resolver: R,
+}
+
+impl<'a, T, P, R> ResolveConflicts<'a, T, P, R>
+where
+    T: Transport + 'a,
+    P: IntoDocumentPath,
+    R: ConflictResolver,
+{
+    #[doc(hidden)]
+    pub fn new(transport: &'a T, doc_path: P, resolver: R) -> Self {
+        ResolveConflicts {
+            transport: transport,
+            doc_path: Some(doc_path),
+            resolver: resolver,
+        }
+    }
+
+    /// Executes the action and waits for the result.
+    pub fn run(mut self) -> Result<Revision, Error> {
+
+        let doc_path = try!(
+            std::mem::replace(&mut self.doc_path, None)
+                .unwrap()
+                .into_document_path()
+        );
+
+        let mut leaves = try!(
+            ReadDocument::new(self.transport, doc_path.clone())
+                .with_open_revisions(OpenRevs::All)
+                .run()
+        ).into_iter()
+            .filter_map(|x| match x {
+                OpenRevision::Found(ref doc) if doc.is_deleted() => None,
+                OpenRevision::Found(doc) => Some(doc),
+                OpenRevision::Missing(..) => None,
+            })
+            .collect::<Vec<_>>();
+
+        if leaves.len() < 2 {
+            return match leaves.pop() {
+                Some(doc) => Ok(doc.revision().clone()),
+                None => Err(Error::NotFound(ErrorResponse::new("not_found", "deleted"))),
+            };
+        }
+
+        let resolution = try!(self.resolver.resolve(&leaves));
+        let (request, kept_revision) = try!(make_request(self.transport, &doc_path, &leaves, resolution));
+        self.transport.send(
+            request,
+            JsonResponseDecoder::new(move |response| handle_response(response, kept_revision)),
+        )
+    }
+}
+
+// Returns the _bulk_docs request plus, if the resolution keeps an existing
+// leaf, that leaf's revision.
+fn make_request<T: Transport>(
+    transport: &T,
+    doc_path: &DocumentPath,
+    leaves: &[Document],
+    resolution: Resolution,
+) -> Result<(Request, Option<Revision>), Error> {
+
+    let doc_id = serde_json::to_value(doc_path.document_id());
+
+    let make_tombstone = |rev: &Revision| {
+        serde_json::builder::ObjectBuilder::new()
+            .insert("_id", doc_id.clone())
+            .insert("_rev", rev)
+            .insert("_deleted", true)
+            .build()
+    };
+
+    let (docs, kept_revision) = match resolution {
+        Resolution::Keep(rev) => {
+            if !leaves.iter().any(|x| x.revision() == &rev) {
+                return Err(Error::ResolvedRevisionNotLeaf);
+            }
+            let docs = leaves
+                .iter()
+                .filter(|x| x.revision() != &rev)
+                .map(|x| make_tombstone(x.revision()))
+                .collect::<Vec<_>>();
+            (docs, Some(rev))
+        }
+        Resolution::Merge(content) => {
+            let base = winning_leaf(leaves).unwrap();
+            let mut merged = base.clone();
+            try!(merged.set_content(&content));
+            let mut merged = serde_json::to_value(&merged);
+            if let serde_json::Value::Object(ref mut fields) = merged {
+                fields.insert(String::from("_id"), doc_id.clone());
+                fields.insert(String::from("_rev"), serde_json::to_value(base.revision()));
+            }
+            let docs = std::iter::once(merged)
+                .chain(
+                    leaves
+                        .iter()
+                        .filter(|x| x.revision() != base.revision())
+                        .map(|x| make_tombstone(x.revision())),
+                )
+                .collect::<Vec<_>>();
+            (docs, None)
+        }
+    };
+
+    let body = serde_json::builder::ObjectBuilder::new()
+        .insert("docs", docs)
+        .build();
+
+    let request = try!(
+        transport
+            .post(vec![doc_path.database_name().as_ref(), "_bulk_docs"])
+            .with_accept_json()
+            .with_json_content(&body)
+    );
+
+    Ok((request, kept_revision))
+}
+
+fn handle_response(response: JsonResponse, kept_revision: Option<Revision>) -> Result<Revision, Error> {
+    match response.status_code() {
+        StatusCode::Created => {
+            let items: Vec<BulkDocsItem> = try!(response.decode_content());
+            for item in &items {
+                if let Some(ref error) = item.error {
+                    let error_response = ErrorResponse::new(error.clone(), item.reason.clone().unwrap_or_default());
+                    return Err(match error.as_str() {
+                        "conflict" => Error::DocumentConflict(error_response),
+                        _ => Error::ServerResponse {
+                            status_code: response.status_code(),
+                            error_response: Some(error_response),
+                        },
+                    });
+                }
+            }
+            match kept_revision {
+                Some(rev) => Ok(rev),
+                None => {
+                    items
+                        .into_iter()
+                        .next()
+                        .and_then(|x| x.rev)
+                        .ok_or(Error::UnexpectedResponse("The server returned no revision"))
+                }
+            }
+        }
+        StatusCode::NotFound => Err(Error::not_found(&response)),
+        StatusCode::Unauthorized => Err(Error::unauthorized(&response)),
+        _ => Err(Error::server_response(&response)),
+    }
+}
+
+// BulkDocsItem is an intermediate type for decoding the result for one
+// document in the server's response to a _bulk_docs request.
+#[derive(Debug, PartialEq)]
+struct BulkDocsItem {
+    rev: Option<Revision>,
+    error: Option<String>,
+    reason: Option<String>,
+}
+
+impl serde::Deserialize for BulkDocsItem {
+    fn deserialize<D>(deserializer: &mut D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer,
+    {
+        enum Field {
+            Error,
+            Ignored,
+            Reason,
+            Rev,
+        }
+
+        impl serde::Deserialize for Field {
+            fn deserialize<D>(deserializer: &mut D) -> Result<Field, D::Error>
+            where
+                D: serde::Deserializer,
+            {
+                struct Visitor;
+
+                impl serde::de::Visitor for Visitor {
+                    type Value = Field;
+
+                    fn visit_str<E>(&mut self, value: &str) -> Result<Field, E>
+                    where
+                        E: serde::de::Error,
+                    {
+                        match value {
+                            "error" => Ok(Field::Error),
+                            "reason" => Ok(Field::Reason),
+                            "rev" => Ok(Field::Rev),
+                            _ => Ok(Field::Ignored),
+                        }
+                    }
+                }
+
+                deserializer.deserialize(Visitor)
+            }
+        }
+
+        struct Visitor;
+
+        impl serde::de::Visitor for Visitor {
+            type Value = BulkDocsItem;
+
+            fn visit_map<V>(&mut self, mut visitor: V) -> Result<Self::Value, V::Error>
+            where
+                V: serde::de::MapVisitor,
+            {
+                let mut error = None;
+                let mut reason = None;
+                let mut rev = None;
+
+                loop {
+                    match try!(visitor.visit_key()) {
+                        Some(Field::Error) => {
+                            error = Some(try!(visitor.visit_value()));
+                        }
+                        Some(Field::Ignored) => {
+                            try!(visitor.visit_value::<serde_json::Value>());
+                        }
+                        Some(Field::Reason) => {
+                            reason = Some(try!(visitor.visit_value()));
+                        }
+                        Some(Field::Rev) => {
+                            rev = Some(try!(visitor.visit_value()));
+                        }
+                        None => {
+                            break;
+                        }
+                    }
+                }
+
+                try!(visitor.end());
+
+                Ok(BulkDocsItem {
+                    rev: rev,
+                    error: error,
+                    reason: reason,
+                })
+            }
+        }
+
+        static FIELDS: &'static [&'static str] = &["error", "reason", "rev"];
+        deserializer.deserialize_struct("BulkDocsItem", FIELDS, Visitor)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use {Document, DocumentPath, Error, IntoDocumentPath, Resolution, Revision, serde_json};
+    use document::DocumentBuilder;
+    use transport::{JsonResponseBuilder, MockTransport, StatusCode, Transport};
+
+    fn make_leaves() -> Vec<Document> {
+        vec![
+            DocumentBuilder::new(
+                "/foo/bar",
+                Revision::parse("2-abcdef1234567890abcdef1234567890").unwrap(),
+            ).build_content(|x| x.insert("field", 1))
+                .unwrap(),
+            DocumentBuilder::new(
+                "/foo/bar",
+                Revision::parse("2-1234567890abcdef1234567890abcdef").unwrap(),
+            ).build_content(|x| x.insert("field", 2))
+                .unwrap(),
+        ]
+    }
+
+    fn make_doc_path() -> DocumentPath {
+        "/foo/bar".into_document_path().unwrap()
+    }
+
+    #[test]
+    fn make_request_keep() {
+
+        let transport = MockTransport::new();
+
+        let body = serde_json::builder::ObjectBuilder::new()
+            .insert_array("docs", |x| {
+                x.push_object(|x| {
+                    x.insert("_id", "bar")
+                        .insert("_rev", "2-1234567890abcdef1234567890abcdef")
+                        .insert("_deleted", true)
+                })
+            })
+            .build();
+
+        let expected = (
+            transport
+                .post(vec!["foo", "_bulk_docs"])
+                .with_accept_json()
+                .with_json_content(&body)
+                .unwrap(),
+            Some(Revision::parse("2-abcdef1234567890abcdef1234567890").unwrap()),
+        );
+
+        let resolution = Resolution::Keep(Revision::parse("2-abcdef1234567890abcdef1234567890").unwrap());
+        let got = super::make_request(&transport, &make_doc_path(), &make_leaves(), resolution).unwrap();
+        assert_eq!(expected, got);
+    }
+
+    #[test]
+    fn make_request_merge() {
+
+        let transport = MockTransport::new();
+
+        let body = serde_json::builder::ObjectBuilder::new()
+            .insert_array("docs", |x| {
+                x.push_object(|x| {
+                    x.insert("_id", "bar")
+                        .insert("_rev", "2-abcdef1234567890abcdef1234567890")
+                        .insert("field", 3)
+                }).push_object(|x| {
+                        x.insert("_id", "bar")
+                            .insert("_rev", "2-1234567890abcdef1234567890abcdef")
+                            .insert("_deleted", true)
+                    })
+            })
+            .build();
+
+        let expected = (
+            transport
+                .post(vec!["foo", "_bulk_docs"])
+                .with_accept_json()
+                .with_json_content(&body)
+                .unwrap(),
+            None,
+        );
+
+        let resolution = Resolution::Merge(
+            serde_json::builder::ObjectBuilder::new()
+                .insert("field", 3)
+                .build(),
+        );
+        let got = super::make_request(&transport, &make_doc_path(), &make_leaves(), resolution).unwrap();
+        assert_eq!(expected, got);
+    }
+
+    #[test]
+    fn make_request_nok_keep_nonleaf() {
+        let transport = MockTransport::new();
+        let resolution = Resolution::Keep(Revision::parse("1-00000000000000000000000000000000").unwrap());
+        match super::make_request(&transport, &make_doc_path(), &make_leaves(), resolution) {
+            Err(Error::ResolvedRevisionNotLeaf) => (),
+            x @ _ => unexpected_result!(x),
+        }
+    }
+
+    #[test]
+    fn handle_response_created_keep() {
+
+        let response = JsonResponseBuilder::new(StatusCode::Created)
+            .with_json_content_raw(r#"[{"ok":true,"id":"bar","rev":"3-1234567890abcdef1234567890abcdef"}]"#)
+            .unwrap();
+
+        let kept = Revision::parse("2-abcdef1234567890abcdef1234567890").unwrap();
+        let got = super::handle_response(response, Some(kept.clone())).unwrap();
+        assert_eq!(kept, got);
+    }
+
+    #[test]
+    fn handle_response_created_merge() {
+
+        let response = JsonResponseBuilder::new(StatusCode::Created)
+            .with_json_content_raw(
+                r#"[{"ok":true,"id":"bar","rev":"3-abcdef1234567890abcdef1234567890"},
+                {"ok":true,"id":"bar","rev":"3-1234567890abcdef1234567890abcdef"}]"#,
+            )
+            .unwrap();
+
+        let expected = Revision::parse("3-abcdef1234567890abcdef1234567890").unwrap();
+        let got = super::handle_response(response, None).unwrap();
+        assert_eq!(expected, got);
+    }
+
+    #[test]
+    fn handle_response_created_with_conflict() {
+
+        let response = JsonResponseBuilder::new(StatusCode::Created)
+            .with_json_content_raw(r#"[{"id":"bar","error":"conflict","reason":"Document update conflict."}]"#)
+            .unwrap();
+
+        match super::handle_response(response, None) {
+            Err(Error::DocumentConflict(ref error_response))
+                if error_response.error() == "conflict" && error_response.reason() == "Document update conflict." => (),
+            x @ _ => unexpected_result!(x),
+        }
+    }
+
+    #[test]
+    fn handle_response_unauthorized() {
+
+        let response = JsonResponseBuilder::new(StatusCode::Unauthorized)
+            .with_json_content_raw(
+                r#"{"error":"unauthorized","reason":"Authentication required."}"#,
+            )
+            .unwrap();
+
+        match super::handle_response(response, None) {
+            Err(Error::Unauthorized(ref error_response))
+                if error_response.error() == "unauthorized" && error_response.reason() == "Authentication required." =>
+                (),
+            x @ _ => unexpected_result!(x),
+        }
+    }
+}
@@ -1,22 +1,24 @@
-use {Document, Error, Revision};
+use {Document, Error, Revision, UpdatableDocument};
 use action::query_keys::*;
 use document::WriteDocumentResponse;
 use transport::{JsonResponse, JsonResponseDecoder, Request, StatusCode, Transport};
 
-pub struct UpdateDocument<'a, T>
+pub struct UpdateDocument<'a, T, D = Document>
 where
+    D: UpdatableDocument + 'a,
     T: Transport + 'a,
 {
     transport: &'a T,
-    doc: &'a Document,
+    doc: &'a D,
 }
 
-impl<'a, T> UpdateDocument<'a, T>
+impl<'a, T, D> UpdateDocument<'a, T, D>
 where
+    D: UpdatableDocument + 'a,
     T: Transport + 'a,
 {
     #[doc(hidden)]
-    pub fn new(transport: &'a T, doc: &'a Document) -> Self {
+    pub fn new(transport: &'a T, doc: &'a D) -> Self {
         UpdateDocument {
             transport: transport,
             doc: doc,
@@ -56,7 +58,7 @@ fn handle_response(response: JsonResponse) -> Result<Revision, Error> {
 mod tests {
 
     use super::*;
-    use {Error, Revision, serde_json};
+    use {Error, Revision, serde_json, std};
     use document::DocumentBuilder;
     use transport::{JsonResponseBuilder, MockTransport, StatusCode, Transport};
 
@@ -91,6 +93,63 @@ mod tests {
         assert_eq!(expected, got);
     }
 
+    #[test]
+    fn make_request_typed() {
+
+        let transport = MockTransport::new();
+
+        let mut doc = DocumentBuilder::new(
+            "/foo/bar",
+            Revision::parse("1-1234567890abcdef1234567890abcdef").unwrap(),
+        ).build_content(|x| x.insert("field_1", 42))
+            .unwrap()
+            .into_typed::<std::collections::BTreeMap<String, i64>>()
+            .unwrap();
+
+        doc.content_mut().insert(String::from("field_2"), 17);
+
+        let request_content = serde_json::builder::ObjectBuilder::new()
+            .insert("field_1", 42)
+            .insert("field_2", 17)
+            .build();
+
+        let expected = transport
+            .put(vec!["foo", "bar"])
+            .with_accept_json()
+            .with_query_literal("rev", "1-1234567890abcdef1234567890abcdef")
+            .with_json_content(&request_content)
+            .unwrap();
+
+        let got = {
+            let mut action = UpdateDocument::new(&transport, &doc);
+            action.make_request().unwrap()
+        };
+
+        assert_eq!(expected, got);
+    }
+
+    #[test]
+    fn make_request_nok_content_not_object() {
+
+        let transport = MockTransport::new();
+
+        let mut doc = DocumentBuilder::new(
+            "/foo/bar",
+            Revision::parse("1-1234567890abcdef1234567890abcdef").unwrap(),
+        ).build_content(|x| x.insert("field_1", 42))
+            .unwrap()
+            .into_typed::<serde_json::Value>()
+            .unwrap();
+
+        doc.set_content(serde_json::Value::I64(42));
+
+        let mut action = UpdateDocument::new(&transport, &doc);
+        match action.make_request() {
+            Err(Error::JsonEncode { .. }) => (),
+            x @ _ => unexpected_result!(x),
+        }
+    }
+
     #[test]
     fn handle_response_created() {
 
@@ -0,0 +1,152 @@
+//! Defines the query options shared by all actions that read a view.
+
+use {Error, collation, serde, serde_json};
+use action::query_keys::*;
+use transport::Request;
+
+pub enum Inclusivity {
+    Exclusive,
+    Inclusive,
+}
+
+pub enum GroupLevel {
+    Exact(bool),
+    Number(u32),
+}
+
+// The StartKey and EndKey type parameters are () if the key is not set. This
+// lets the key-setting methods of each action be available only while the key
+// is unset.
+pub struct ViewOptions<StartKey, EndKey> {
+    pub reduce: Option<bool>,
+    pub start_key: Option<StartKey>,
+    pub end_key: Option<(EndKey, Inclusivity)>,
+    pub limit: Option<u64>,
+    pub descending: Option<bool>,
+    pub group_level: Option<GroupLevel>,
+    pub include_docs: Option<bool>,
+    pub key_prefix: Option<serde_json::Value>,
+}
+
+impl ViewOptions<(), ()> {
+    pub fn new() -> Self {
+        ViewOptions {
+            reduce: None,
+            start_key: None,
+            end_key: None,
+            limit: None,
+            descending: None,
+            group_level: None,
+            include_docs: None,
+            key_prefix: None,
+        }
+    }
+
+    pub fn with_key_prefix(self, prefix: serde_json::Value) -> ViewOptions<serde_json::Value, serde_json::Value> {
+        ViewOptions {
+            reduce: self.reduce,
+            start_key: None,
+            end_key: None,
+            limit: self.limit,
+            descending: self.descending,
+            group_level: self.group_level,
+            include_docs: self.include_docs,
+            key_prefix: Some(prefix),
+        }
+    }
+}
+
+impl<StartKey, EndKey> ViewOptions<StartKey, EndKey> {
+    pub fn with_start_key<K>(self, start_key: K) -> ViewOptions<K, EndKey> {
+        ViewOptions {
+            reduce: self.reduce,
+            start_key: Some(start_key),
+            end_key: self.end_key,
+            limit: self.limit,
+            descending: self.descending,
+            group_level: self.group_level,
+            include_docs: self.include_docs,
+            key_prefix: self.key_prefix,
+        }
+    }
+
+    pub fn with_end_key<K>(self, end_key: K, inclusivity: Inclusivity) -> ViewOptions<StartKey, K> {
+        ViewOptions {
+            reduce: self.reduce,
+            start_key: self.start_key,
+            end_key: Some((end_key, inclusivity)),
+            limit: self.limit,
+            descending: self.descending,
+            group_level: self.group_level,
+            include_docs: self.include_docs,
+            key_prefix: self.key_prefix,
+        }
+    }
+}
+
+impl<StartKey, EndKey> ViewOptions<StartKey, EndKey>
+where
+    EndKey: serde::Serialize,
+    StartKey: serde::Serialize,
+{
+    pub fn add_to_request(&self, request: Request) -> Result<Request, Error> {
+
+        let request = match self.reduce {
+            None => request,
+            Some(ref yes_or_no) => request.with_query(ReduceQueryKey, yes_or_no),
+        };
+
+        let request = match self.key_prefix {
+            None => request,
+            Some(ref prefix) => {
+                let upper_bound = try!(collation::upper_bound_for_prefix(prefix).ok_or(
+                    Error::KeyPrefixNotArrayOrString,
+                ));
+                let (start_key, end_key) = match self.descending {
+                    Some(true) => (&upper_bound, prefix),
+                    _ => (prefix, &upper_bound),
+                };
+                try!(
+                    try!(request.with_query_fallible(StartKeyQueryKey, start_key))
+                        .with_query_fallible(EndKeyQueryKey, end_key)
+                )
+            }
+        };
+
+        let request = match self.start_key {
+            None => request,
+            Some(ref key) => try!(request.with_query_fallible(StartKeyQueryKey, key)),
+        };
+
+        let request = match self.end_key {
+            None => request,
+            Some((ref key, Inclusivity::Inclusive)) => try!(request.with_query_fallible(EndKeyQueryKey, key)),
+            Some((ref key, Inclusivity::Exclusive)) => {
+                try!(request.with_query_fallible(EndKeyQueryKey, key)).with_query(InclusiveEndQueryKey, &false)
+            }
+        };
+
+        let request = match self.limit {
+            None => request,
+            Some(ref limit) => request.with_query(LimitQueryKey, limit),
+        };
+
+        let request = match self.descending {
+            None => request,
+            Some(ref yes_or_no) => request.with_query(DescendingQueryKey, yes_or_no),
+        };
+
+        let request = match self.group_level {
+            None => request,
+            Some(GroupLevel::Exact(ref yes_or_no)) => request.with_query(GroupQueryKey, yes_or_no),
+            Some(GroupLevel::Number(ref group_level)) => request.with_query(GroupLevelQueryKey, group_level),
+        };
+
+        let request = match self.include_docs {
+            None => request,
+            Some(ref yes_or_no) => request.with_query(IncludeDocsQueryKey, yes_or_no),
+        };
+
+        Ok(request)
+    }
+}
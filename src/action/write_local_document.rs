@@ -0,0 +1,244 @@
+//! Defines an action for creating or updating a local document on the CouchDB
+//! server.
+
+use {DocumentId, DocumentPath, Error, IntoDatabasePath, LocalDocumentName, LocalRevision, serde, std};
+use action::query_keys::*;
+use local_document::LocalRevisionJsonable;
+use transport::{JsonResponse, JsonResponseDecoder, Request, StatusCode, Transport};
+
+/// Creates or updates a local document on the CouchDB server and returns the
+/// document's new revision.
+///
+/// Chill writes the local document by sending an HTTP request to `PUT` the
+/// given content to the document's path—i.e., `/{db}/_local/{name}`.
+///
+/// By default, the action creates the local document, which must not already
+/// exist. To update an existing local document, use the `with_revision` method
+/// to specify the document's current revision.
+///
+/// # Errors
+///
+/// The following are _some_ errors that may occur when writing a local
+/// document.
+///
+/// <table>
+/// <tr>
+///  <td><code>Error::DocumentConflict</code></td>
+///  <td>The local document already exists and the action specifies no
+///  revision, or the revision is not current.</td>
+/// </tr>
+/// <tr>
+///  <td><code>Error::Unauthorized</code></td>
+///  <td>The client lacks permission to write the local document.</td>
+/// </tr>
+/// </table>
+///
+/// # Examples
+///
+/// ```no_run
+/// extern crate chill;
+/// extern crate serde_json;
+///
+/// let client = chill::Client::new("http://localhost:5984").unwrap();
+///
+/// let content = serde_json::builder::ObjectBuilder::new()
+///     .insert("seq", 42)
+///     .build();
+///
+/// let rev1 = client
+///     .write_local_document("/baseball", "checkpoint", &content)
+///     .run()
+///     .unwrap();
+///
+/// let rev2 = client
+///     .write_local_document("/baseball", "checkpoint", &content)
+///     .with_revision(&rev1)
+///     .run()
+///     .unwrap();
+///
+/// assert!(rev1 < rev2);
+/// ```
+///
+pub struct WriteLocalDocument<'a, T, P, C>
+where
+    C: serde::Serialize + 'a,
+    P: IntoDatabasePath,
+    T: Transport + 'a,
+{
+    transport: &'a T,
+    db_path: Option<P>,
+    name: LocalDocumentName,
+    content: &'a C,
+    revision: Option<&'a LocalRevision>,
+}
+
+impl<'a, T, P, C> WriteLocalDocument<'a, T, P, C>
+where
+    C: serde::Serialize + 'a,
+    P: IntoDatabasePath,
+    T: Transport + 'a,
+{
+    #[doc(hidden)]
+    pub fn new<N>(transport: &'a T, db_path: P, name: N, content: &'a C) -> Self
+    where
+        N: Into<LocalDocumentName>,
+    {
+        WriteLocalDocument {
+            transport: transport,
+            db_path: Some(db_path),
+            name: name.into(),
+            content: content,
+            revision: None,
+        }
+    }
+
+    /// Modifies the action to update an existing local document, which must be
+    /// at the given revision.
+    pub fn with_revision(mut self, revision: &'a LocalRevision) -> Self {
+        self.revision = Some(revision);
+        self
+    }
+
+    /// Executes the action and waits for the result.
+    pub fn run(mut self) -> Result<LocalRevision, Error> {
+        self.transport.send(
+            try!(self.make_request()),
+            JsonResponseDecoder::new(handle_response),
+        )
+    }
+
+    fn make_request(&mut self) -> Result<Request, Error> {
+
+        let db_path = try!(
+            std::mem::replace(&mut self.db_path, None)
+                .unwrap()
+                .into_database_path()
+        );
+        let doc_path = DocumentPath::from((db_path, DocumentId::from(self.name.clone())));
+
+        let request = try!(
+            self.transport
+                .put(doc_path.iter())
+                .with_accept_json()
+                .with_json_content(self.content)
+        );
+
+        let request = match self.revision {
+            None => request,
+            Some(revision) => request.with_query(RevisionQueryKey, revision),
+        };
+
+        Ok(request)
+    }
+}
+
+fn handle_response(response: JsonResponse) -> Result<LocalRevision, Error> {
+    match response.status_code() {
+        StatusCode::Created => {
+            let content: LocalRevisionJsonable = try!(response.decode_content());
+            Ok(content.revision)
+        }
+        StatusCode::Conflict => Err(Error::document_conflict(&response)),
+        StatusCode::Unauthorized => Err(Error::unauthorized(&response)),
+        _ => Err(Error::server_response(&response)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use {Error, LocalRevision, serde_json};
+    use transport::{JsonResponseBuilder, MockTransport, StatusCode, Transport};
+
+    #[test]
+    fn make_request_default() {
+
+        let content = serde_json::builder::ObjectBuilder::new()
+            .insert("seq", 42)
+            .build();
+
+        let transport = MockTransport::new();
+        let expected = transport
+            .put(vec!["foo", "_local", "bar"])
+            .with_accept_json()
+            .with_json_content(&content)
+            .unwrap();
+
+        let got = {
+            let mut action = WriteLocalDocument::new(&transport, "/foo", "bar", &content);
+            action.make_request().unwrap()
+        };
+
+        assert_eq!(expected, got);
+    }
+
+    #[test]
+    fn make_request_with_revision() {
+
+        let content = serde_json::builder::ObjectBuilder::new()
+            .insert("seq", 42)
+            .build();
+
+        let transport = MockTransport::new();
+        let expected = transport
+            .put(vec!["foo", "_local", "bar"])
+            .with_accept_json()
+            .with_json_content(&content)
+            .unwrap()
+            .with_query_literal("rev", "0-3");
+
+        let got = {
+            let rev = LocalRevision::parse("0-3").unwrap();
+            let mut action = WriteLocalDocument::new(&transport, "/foo", "bar", &content).with_revision(&rev);
+            action.make_request().unwrap()
+        };
+
+        assert_eq!(expected, got);
+    }
+
+    #[test]
+    fn handle_response_created() {
+
+        let response = JsonResponseBuilder::new(StatusCode::Created)
+            .with_json_content_raw(r#"{"ok":true,"id":"_local/bar","rev":"0-4"}"#)
+            .unwrap();
+
+        let expected = LocalRevision::parse("0-4").unwrap();
+        let got = super::handle_response(response).unwrap();
+        assert_eq!(expected, got);
+    }
+
+    #[test]
+    fn handle_response_conflict() {
+
+        let response = JsonResponseBuilder::new(StatusCode::Conflict)
+            .with_json_content_raw(
+                r#"{"error":"conflict","reason":"Document update conflict."}"#,
+            )
+            .unwrap();
+
+        match super::handle_response(response) {
+            Err(Error::DocumentConflict(ref error_response))
+                if error_response.error() == "conflict" && error_response.reason() == "Document update conflict." => (),
+            x @ _ => unexpected_result!(x),
+        }
+    }
+
+    #[test]
+    fn handle_response_unauthorized() {
+
+        let response = JsonResponseBuilder::new(StatusCode::Unauthorized)
+            .with_json_content_raw(
+                r#"{"error":"unauthorized","reason":"Authentication required."}"#,
+            )
+            .unwrap();
+
+        match super::handle_response(response) {
+            Err(Error::Unauthorized(ref error_response))
+                if error_response.error() == "unauthorized" && error_response.reason() == "Authentication required." =>
+                (),
+            x @ _ => unexpected_result!(x),
+        }
+    }
+}
@@ -91,6 +91,50 @@ impl serde::Deserialize for Attachment {
     }
 }
 
+/// Contains the meta-information of an attachment, as read via an HTTP `HEAD`
+/// request.
+///
+/// An `AttachmentInfo` is the result of the `HeadAttachment` action, which
+/// reads an attachment's meta-information without downloading the
+/// attachment's content.
+///
+#[derive(Clone, Debug, PartialEq)]
+pub struct AttachmentInfo {
+    content_type: Option<mime::Mime>,
+    content_length: u64,
+    digest: Option<String>,
+}
+
+impl AttachmentInfo {
+    #[doc(hidden)]
+    pub fn new(content_type: Option<mime::Mime>, content_length: u64, digest: Option<String>) -> Self {
+        AttachmentInfo {
+            content_type: content_type,
+            content_length: content_length,
+            digest: digest,
+        }
+    }
+
+    /// Returns the attachment's content type, if the server sent one.
+    pub fn content_type(&self) -> Option<&mime::Mime> {
+        self.content_type.as_ref()
+    }
+
+    /// Returns the attachment's content size, in bytes.
+    pub fn content_length(&self) -> u64 {
+        self.content_length
+    }
+
+    /// Returns the attachment's digest, if the server sent one.
+    ///
+    /// The digest has the same format as the `digest` field of an attachment
+    /// stub—e.g., <q>md5-XHJUuKUpWl6fV5wSJZYqQA==</q>.
+    ///
+    pub fn digest(&self) -> Option<&str> {
+        self.digest.as_ref().map(|x| x.as_str())
+    }
+}
+
 #[derive(Clone, Debug, PartialEq)]
 enum SavedAttachmentContent {
     LengthOnly(u64),
@@ -112,6 +156,10 @@ impl SavedAttachment {
         &self.content_type
     }
 
+    pub fn digest(&self) -> &str {
+        &self.digest
+    }
+
     pub fn sequence_number(&self) -> u64 {
         self.sequence_number
     }
@@ -1,4 +1,7 @@
-use {Document, Error, IntoDatabasePath, IntoDocumentPath, IntoViewPath, Revision, action, serde, url};
+use {ConflictResolver, Design, DesignDocumentName, Document, DocumentId, Error, IntoAttachmentPath,
+     IntoDatabasePath, IntoDesignDocumentPath, IntoDocumentPath, IntoViewPath, ListName, LocalDocumentName,
+     LocalRevision, Revision, ServerVersion, ShowName, UpdatableDocument, UpdateName, UuidPool, action, mango, serde,
+     std, url};
 use transport::HyperTransport;
 
 /// Describes a type that may be converted into a URL.
@@ -40,6 +43,8 @@ impl<'a> IntoUrl for &'a String {
 #[derive(Debug)]
 pub struct Client {
     transport: HyperTransport,
+    server_version: std::sync::Mutex<Option<ServerVersion>>,
+    uuid_pool: Option<UuidPool>,
 }
 
 impl Client {
@@ -47,7 +52,58 @@ impl Client {
     pub fn new<U: IntoUrl>(server_url: U) -> Result<Self, Error> {
         let server_url = try!(server_url.into_url());
         let transport = HyperTransport::new(server_url);
-        Ok((Client { transport: transport }))
+        Ok(Client {
+            transport: transport,
+            server_version: std::sync::Mutex::new(None),
+            uuid_pool: None,
+        })
+    }
+
+    /// Modifies the client to assign server-generated ids to new documents.
+    ///
+    /// With a `UuidPool`, the `CreateDocument` action takes each new
+    /// document's id from the pool unless the application sets the id
+    /// explicitly. See `UuidPool` for details.
+    ///
+    pub fn with_uuid_pool(mut self, uuid_pool: UuidPool) -> Self {
+        self.uuid_pool = Some(uuid_pool);
+        self
+    }
+
+    /// Builds an action to read information about the server.
+    pub fn server_info<'a>(&'a self) -> action::ReadServerInfo<'a, HyperTransport> {
+        action::ReadServerInfo::new(&self.transport)
+    }
+
+    /// Returns the server's version, reading it from the server if not yet
+    /// known.
+    ///
+    /// The `server_version` method reads the server's version via the
+    /// `server_info` action the first time it's called and thereafter returns
+    /// the cached version. Applications may use the version to choose behavior
+    /// that differs between major versions of CouchDB.
+    ///
+    pub fn server_version(&self) -> Result<ServerVersion, Error> {
+
+        // The lock guards only the cache, not the round trip to the server, so
+        // concurrent callers may each read the version the first time. A
+        // poisoned lock still holds a valid cache because the cache is written
+        // in one step.
+
+        let cached = *self.server_version.lock().unwrap_or_else(std::sync::PoisonError::into_inner);
+
+        if let Some(version) = cached {
+            return Ok(version);
+        }
+
+        let version = try!(self.server_info().run()).version();
+        *self.server_version.lock().unwrap_or_else(std::sync::PoisonError::into_inner) = Some(version);
+        Ok(version)
+    }
+
+    /// Builds an action to read UUIDs generated by the server.
+    pub fn uuids<'a>(&'a self, count: u64) -> action::ReadUuids<'a, HyperTransport> {
+        action::ReadUuids::new(&self.transport, count)
     }
 
     /// Builds an action to create a database.
@@ -68,7 +124,23 @@ impl Client {
         C: serde::Serialize,
         P: IntoDatabasePath,
     {
-        action::CreateDocument::new(&self.transport, db_path, content)
+        let action = action::CreateDocument::new(&self.transport, db_path, content);
+        match self.uuid_pool {
+            None => action,
+            Some(ref uuid_pool) => action.with_uuid_pool(uuid_pool),
+        }
+    }
+
+    /// Builds an action to create a Mango index.
+    pub fn create_index<'a, P>(
+        &'a self,
+        db_path: P,
+        definition: mango::IndexDefinition,
+    ) -> action::CreateIndex<'a, HyperTransport, P>
+    where
+        P: IntoDatabasePath,
+    {
+        action::CreateIndex::new(&self.transport, db_path, definition)
     }
 
     /// Builds an action to read a document.
@@ -79,11 +151,78 @@ impl Client {
         action::ReadDocument::new(&self.transport, doc_path)
     }
 
+    /// Builds an action to check whether a document exists and read its current
+    /// revision.
+    pub fn head_document<'a, P>(&'a self, doc_path: P) -> action::HeadDocument<'a, HyperTransport, P>
+    where
+        P: IntoDocumentPath,
+    {
+        action::HeadDocument::new(&self.transport, doc_path)
+    }
+
+    /// Builds an action to read an attachment's meta-information without its
+    /// content.
+    pub fn head_attachment<'a, P>(&'a self, att_path: P) -> action::HeadAttachment<'a, HyperTransport, P>
+    where
+        P: IntoAttachmentPath,
+    {
+        action::HeadAttachment::new(&self.transport, att_path)
+    }
+
     /// Builds an action to update a document.
-    pub fn update_document<'a>(&'a self, doc: &'a Document) -> action::UpdateDocument<'a, HyperTransport> {
+    ///
+    /// The document may be either a `Document` or a `TypedDocument`. The
+    /// action fails with an `Error::JsonEncode` error if the document content
+    /// doesn't encode to a JSON object.
+    ///
+    pub fn update_document<'a, D>(&'a self, doc: &'a D) -> action::UpdateDocument<'a, HyperTransport, D>
+    where
+        D: UpdatableDocument,
+    {
         action::UpdateDocument::new(&self.transport, doc)
     }
 
+    /// Builds an action to modify a document via a closure, retrying if the
+    /// document changes concurrently.
+    pub fn modify_document<'a, P, F>(
+        &'a self,
+        doc_path: P,
+        modifier: F,
+    ) -> action::ModifyDocument<'a, HyperTransport, P, F>
+    where
+        P: IntoDocumentPath,
+        F: FnMut(Option<&mut Document>) -> Result<action::Modification, Error>,
+    {
+        action::ModifyDocument::new(&self.transport, doc_path, modifier)
+    }
+
+    /// Builds an action to copy a document to a new document id within the
+    /// same database.
+    pub fn copy_document<'a, P, D>(
+        &'a self,
+        source_path: P,
+        destination: D,
+    ) -> action::CopyDocument<'a, HyperTransport, P>
+    where
+        P: IntoDocumentPath,
+        D: Into<DocumentId>,
+    {
+        action::CopyDocument::new(&self.transport, source_path, destination)
+    }
+
+    /// Builds an action to resolve a document's conflicts.
+    pub fn resolve_conflicts<'a, P, R>(
+        &'a self,
+        doc_path: P,
+        resolver: R,
+    ) -> action::ResolveConflicts<'a, HyperTransport, P, R>
+    where
+        P: IntoDocumentPath,
+        R: ConflictResolver,
+    {
+        action::ResolveConflicts::new(&self.transport, doc_path, resolver)
+    }
+
     /// Builds an action to delete a document.
     pub fn delete_document<'a, P>(
         &'a self,
@@ -96,6 +235,118 @@ impl Client {
         action::DeleteDocument::new(&self.transport, doc_path, revision)
     }
 
+    /// Builds an action to read a local document.
+    pub fn read_local_document<'a, P, N>(
+        &'a self,
+        db_path: P,
+        name: N,
+    ) -> action::ReadLocalDocument<'a, HyperTransport, P>
+    where
+        P: IntoDatabasePath,
+        N: Into<LocalDocumentName>,
+    {
+        action::ReadLocalDocument::new(&self.transport, db_path, name)
+    }
+
+    /// Builds an action to create or update a local document.
+    pub fn write_local_document<'a, C, P, N>(
+        &'a self,
+        db_path: P,
+        name: N,
+        content: &'a C,
+    ) -> action::WriteLocalDocument<'a, HyperTransport, P, C>
+    where
+        C: serde::Serialize,
+        P: IntoDatabasePath,
+        N: Into<LocalDocumentName>,
+    {
+        action::WriteLocalDocument::new(&self.transport, db_path, name, content)
+    }
+
+    /// Builds an action to delete a local document.
+    pub fn delete_local_document<'a, P, N>(
+        &'a self,
+        db_path: P,
+        name: N,
+        revision: &'a LocalRevision,
+    ) -> action::DeleteLocalDocument<'a, HyperTransport, P>
+    where
+        P: IntoDatabasePath,
+        N: Into<LocalDocumentName>,
+    {
+        action::DeleteLocalDocument::new(&self.transport, db_path, name, revision)
+    }
+
+    /// Builds an action to list the local documents in a database.
+    pub fn list_local_documents<'a, P>(&'a self, db_path: P) -> action::ListLocalDocuments<'a, HyperTransport, P>
+    where
+        P: IntoDatabasePath,
+    {
+        action::ListLocalDocuments::new(&self.transport, db_path)
+    }
+
+    /// Builds an action to delete a Mango index.
+    pub fn delete_index<'a, P, D, N>(
+        &'a self,
+        db_path: P,
+        ddoc_name: D,
+        index_name: N,
+    ) -> action::DeleteIndex<'a, HyperTransport, P>
+    where
+        D: Into<DesignDocumentName>,
+        N: Into<String>,
+        P: IntoDatabasePath,
+    {
+        action::DeleteIndex::new(&self.transport, db_path, ddoc_name, index_name)
+    }
+
+    /// Builds an action to deploy a design document.
+    pub fn deploy_design<'a, P>(
+        &'a self,
+        ddoc_path: P,
+        design: &'a Design,
+    ) -> action::DeployDesign<'a, HyperTransport, P>
+    where
+        P: IntoDesignDocumentPath,
+    {
+        action::DeployDesign::new(&self.transport, ddoc_path, design)
+    }
+
+    /// Builds an action to execute a list function over a view.
+    pub fn execute_list<'a, P, L>(
+        &'a self,
+        view_path: P,
+        list_name: L,
+    ) -> action::ExecuteList<'a, HyperTransport, P, (), ()>
+    where
+        L: Into<ListName>,
+        P: IntoViewPath,
+    {
+        action::ExecuteList::new(&self.transport, view_path, list_name)
+    }
+
+    /// Builds an action to execute a show function.
+    pub fn execute_show<'a, P, S>(&'a self, ddoc_path: P, show_name: S) -> action::ExecuteShow<'a, HyperTransport, P>
+    where
+        P: IntoDesignDocumentPath,
+        S: Into<ShowName>,
+    {
+        action::ExecuteShow::new(&self.transport, ddoc_path, show_name)
+    }
+
+    /// Builds an action to execute an update handler.
+    pub fn execute_update_handler<'a, P, U>(
+        &'a self,
+        ddoc_path: P,
+        update_name: U,
+    ) -> action::ExecuteUpdateHandler<'a, HyperTransport, P>
+    where
+        P: IntoDesignDocumentPath,
+        U: Into<UpdateName>,
+    {
+        action::ExecuteUpdateHandler::new(&self.transport, ddoc_path, update_name)
+    }
+
     /// Builds an action to execute a view.
     pub fn execute_view<'a, P>(&'a self, view_path: P) -> action::ExecuteView<'a, HyperTransport, P, (), ()>
     where
@@ -103,4 +354,28 @@ impl Client {
     {
         action::ExecuteView::new(&self.transport, view_path)
     }
+
+    /// Builds an action to explain how the server would execute a Mango query.
+    pub fn explain<'a, P>(&'a self, db_path: P, selector: mango::Selector) -> action::Explain<'a, HyperTransport, P>
+    where
+        P: IntoDatabasePath,
+    {
+        action::Explain::new(&self.transport, db_path, selector)
+    }
+
+    /// Builds an action to execute a Mango query.
+    pub fn find<'a, P>(&'a self, db_path: P, selector: mango::Selector) -> action::Find<'a, HyperTransport, P>
+    where
+        P: IntoDatabasePath,
+    {
+        action::Find::new(&self.transport, db_path, selector)
+    }
+
+    /// Builds an action to list the Mango indexes in a database.
+    pub fn list_indexes<'a, P>(&'a self, db_path: P) -> action::ListIndexes<'a, HyperTransport, P>
+    where
+        P: IntoDatabasePath,
+    {
+        action::ListIndexes::new(&self.transport, db_path)
+    }
 }
@@ -0,0 +1,490 @@
+//! Utilities for ordering JSON values the same way CouchDB orders view keys.
+//!
+//! CouchDB sorts view rows by key according to its own collation rules, which
+//! differ from both Rust's and JavaScript's natural ordering. Values of
+//! different types sort in this order:
+//!
+//! 1. `null`,
+//! 2. `false`,
+//! 3. `true`,
+//! 4. numbers,
+//! 5. strings,
+//! 6. arrays, and
+//! 7. objects.
+//!
+//! Numbers sort numerically, regardless of whether they're integers or
+//! floating-point. Arrays sort element-by-element, with an array sorting
+//! before any longer array it's a prefix of. Objects sort member-by-member,
+//! comparing each member's key and then its value, with an object sorting
+//! before any larger object it's a prefix of.
+//!
+//! Strings sort using the Unicode Collation Algorithm, as implemented by the
+//! ICU library on the CouchDB server. Chill approximates this: ASCII
+//! characters sort exactly as they do on the server—whitespace, then
+//! punctuation and symbols, then digits, then letters, with letters compared
+//! case-insensitively before lowercase sorts before uppercase—but non-ASCII
+//! characters sort by code point after case folding.
+//!
+//! # Object member order
+//!
+//! CouchDB compares object members in the order they appear in the object.
+//! However, `serde_json::Value` stores object members sorted by key, so Chill
+//! compares object members in key order. The two orderings differ only for
+//! objects whose members aren't already sorted by key.
+//!
+//! # Key ranges
+//!
+//! This module also provides the _high sentinel_ values that applications use
+//! to construct key ranges—e.g., all keys beginning with a given prefix. See
+//! the `upper_bound_for_prefix` function.
+//!
+
+use {serde, serde_json, std};
+use std::cmp::Ordering;
+
+/// The string that sorts after all strings that applications commonly use.
+///
+/// `HIGH_STRING` is U+FFF0, which CouchDB recommends appending to a string
+/// prefix to make the inclusive upper bound of all strings beginning with that
+/// prefix.
+///
+pub const HIGH_STRING: &'static str = "\u{fff0}";
+
+// Primary collation order for ASCII punctuation, symbols, and digits, as given
+// in the CouchDB documentation. Letters sort after all of these.
+const ASCII_ORDER: &'static str = "`^_-,;:!?.'\"()[]{}@*/\\&#%+<=>|~$0123456789";
+
+/// Returns the object that sorts after all non-object values: `{}`.
+///
+/// Applications append the empty object to an array prefix to make the
+/// inclusive upper bound of all arrays beginning with that prefix.
+///
+pub fn high_object() -> serde_json::Value {
+    serde_json::Value::Object(serde_json::Map::new())
+}
+
+/// Returns the inclusive upper bound of all keys beginning with the given
+/// prefix, if the prefix is an array or string.
+///
+/// For an array prefix, the upper bound is the prefix with an empty object
+/// appended—e.g., `["foo", 2016]` becomes `["foo", 2016, {}]`. For a string
+/// prefix, the upper bound is the prefix with `HIGH_STRING` appended.
+///
+/// This function returns `None` if the prefix is neither an array nor a
+/// string.
+///
+pub fn upper_bound_for_prefix(prefix: &serde_json::Value) -> Option<serde_json::Value> {
+    match prefix {
+        &serde_json::Value::Array(ref elements) => {
+            let mut elements = elements.clone();
+            elements.push(high_object());
+            Some(serde_json::Value::Array(elements))
+        }
+        &serde_json::Value::String(ref s) => Some(serde_json::Value::String(format!("{}{}", s, HIGH_STRING))),
+        _ => None,
+    }
+}
+
+/// Compares two JSON values using CouchDB's view collation rules.
+pub fn compare(a: &serde_json::Value, b: &serde_json::Value) -> Ordering {
+
+    use serde_json::Value;
+
+    match type_rank(a).cmp(&type_rank(b)) {
+        Ordering::Equal => (),
+        x @ _ => return x,
+    }
+
+    match (a, b) {
+        (&Value::String(ref a), &Value::String(ref b)) => compare_strings(a, b),
+        (&Value::Array(ref a), &Value::Array(ref b)) => {
+            for (x, y) in a.iter().zip(b.iter()) {
+                match compare(x, y) {
+                    Ordering::Equal => (),
+                    x @ _ => return x,
+                }
+            }
+            a.len().cmp(&b.len())
+        }
+        (&Value::Object(ref a), &Value::Object(ref b)) => {
+            for ((k1, v1), (k2, v2)) in a.iter().zip(b.iter()) {
+                match compare_strings(k1, k2) {
+                    Ordering::Equal => (),
+                    x @ _ => return x,
+                }
+                match compare(v1, v2) {
+                    Ordering::Equal => (),
+                    x @ _ => return x,
+                }
+            }
+            a.len().cmp(&b.len())
+        }
+        _ if a.is_number() => compare_numbers(a, b),
+        _ => Ordering::Equal, // null and Booleans are fully ordered by rank
+    }
+}
+
+fn type_rank(x: &serde_json::Value) -> u8 {
+    use serde_json::Value;
+    match x {
+        &Value::Null => 0,
+        &Value::Bool(false) => 1,
+        &Value::Bool(true) => 2,
+        &Value::I64(..) |
+        &Value::U64(..) |
+        &Value::F64(..) => 3,
+        &Value::String(..) => 4,
+        &Value::Array(..) => 5,
+        &Value::Object(..) => 6,
+    }
+}
+
+fn compare_numbers(a: &serde_json::Value, b: &serde_json::Value) -> Ordering {
+
+    use serde_json::Value;
+
+    // Compare integers exactly, to avoid losing precision for large values.
+    match (a, b) {
+        (&Value::U64(a), &Value::U64(b)) => return a.cmp(&b),
+        (&Value::I64(a), &Value::I64(b)) => return a.cmp(&b),
+        (&Value::I64(a), &Value::U64(b)) => return (a as i128).cmp(&(b as i128)),
+        (&Value::U64(a), &Value::I64(b)) => return (a as i128).cmp(&(b as i128)),
+        _ => (),
+    }
+
+    let a = a.as_f64().unwrap();
+    let b = b.as_f64().unwrap();
+    a.partial_cmp(&b).unwrap_or(Ordering::Equal)
+}
+
+/// Compares two strings using CouchDB's string collation rules.
+///
+/// Strings are compared first by each character's primary weight, ignoring
+/// case, then by case, with lowercase sorting before uppercase, and finally by
+/// code point.
+///
+pub fn compare_strings(a: &str, b: &str) -> Ordering {
+
+    let primary = a.chars().map(primary_weight).cmp(b.chars().map(primary_weight));
+    if primary != Ordering::Equal {
+        return primary;
+    }
+
+    let tertiary = a.chars().map(|c| c.is_uppercase()).cmp(b.chars().map(|c| c.is_uppercase()));
+    if tertiary != Ordering::Equal {
+        return tertiary;
+    }
+
+    a.cmp(b)
+}
+
+fn primary_weight(c: char) -> u32 {
+
+    const LETTER_BASE: u32 = 0x100;
+    const OTHER_BASE: u32 = 0x200;
+
+    if c.is_whitespace() || c.is_control() {
+        return 0;
+    }
+
+    if c.is_ascii() {
+        if c.is_ascii_alphabetic() {
+            return LETTER_BASE + (c.to_ascii_lowercase() as u32 - 'a' as u32);
+        }
+        return match ASCII_ORDER.find(c) {
+            Some(index) => 1 + index as u32,
+            None => 1 + ASCII_ORDER.len() as u32,
+        };
+    }
+
+    let folded = c.to_lowercase().next().unwrap_or(c);
+    OTHER_BASE + folded as u32
+}
+
+/// Wraps a JSON value so that it's ordered using CouchDB's view collation
+/// rules.
+///
+/// `CollatedKey` implements `Ord`, so applications may use it to sort and merge
+/// view keys on the client side—e.g., as the key type of a `BTreeMap` or as the
+/// element type of a sorted `Vec`—and get the same order as the CouchDB server.
+///
+/// # Examples
+///
+/// ```
+/// extern crate chill;
+/// extern crate serde_json;
+///
+/// use chill::collation::CollatedKey;
+///
+/// let mut keys = vec![
+///     CollatedKey::from(serde_json::Value::String(String::from("b"))),
+///     CollatedKey::from(serde_json::Value::U64(42)),
+///     CollatedKey::from(serde_json::Value::String(String::from("A"))),
+///     CollatedKey::from(serde_json::Value::Null),
+///     CollatedKey::from(serde_json::Value::String(String::from("a"))),
+/// ];
+///
+/// keys.sort();
+///
+/// let expected = vec![
+///     serde_json::Value::Null,
+///     serde_json::Value::U64(42),
+///     serde_json::Value::String(String::from("a")),
+///     serde_json::Value::String(String::from("A")),
+///     serde_json::Value::String(String::from("b")),
+/// ];
+///
+/// let got = keys.into_iter().map(|x| x.into_inner()).collect::<Vec<_>>();
+/// assert_eq!(expected, got);
+/// ```
+///
+#[derive(Clone, Debug)]
+pub struct CollatedKey(pub serde_json::Value);
+
+impl CollatedKey {
+    /// Returns the underlying JSON value.
+    pub fn into_inner(self) -> serde_json::Value {
+        self.0
+    }
+}
+
+impl From<serde_json::Value> for CollatedKey {
+    fn from(x: serde_json::Value) -> Self {
+        CollatedKey(x)
+    }
+}
+
+impl AsRef<serde_json::Value> for CollatedKey {
+    fn as_ref(&self) -> &serde_json::Value {
+        &self.0
+    }
+}
+
+impl Ord for CollatedKey {
+    fn cmp(&self, other: &Self) -> Ordering {
+        compare(&self.0, &other.0)
+    }
+}
+
+impl PartialOrd for CollatedKey {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl PartialEq for CollatedKey {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for CollatedKey {}
+
+impl serde::Serialize for CollatedKey {
+    fn serialize<S>(&self, serializer: &mut S) -> Result<(), S::Error>
+    where
+        S: serde::Serializer,
+    {
+        self.0.serialize(serializer)
+    }
+}
+
+impl serde::Deserialize for CollatedKey {
+    fn deserialize<D>(deserializer: &mut D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer,
+    {
+        Ok(CollatedKey(try!(serde_json::Value::deserialize(deserializer))))
+    }
+}
+
+impl std::fmt::Display for CollatedKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use serde_json;
+    use std::cmp::Ordering;
+
+    fn json(s: &str) -> serde_json::Value {
+        serde_json::from_str(s).unwrap()
+    }
+
+    // Checks that the given JSON values are in strictly ascending order, both
+    // as given and after sorting a reversed copy.
+    fn expect_ascending(sources: &[&str]) {
+
+        let values = sources.iter().map(|x| json(x)).collect::<Vec<_>>();
+
+        for (i, a) in values.iter().enumerate() {
+            for (j, b) in values.iter().enumerate() {
+                assert_eq!(
+                    i.cmp(&j),
+                    compare(a, b),
+                    "Comparing {} with {}",
+                    a,
+                    b
+                );
+            }
+        }
+
+        let mut got = values
+            .iter()
+            .rev()
+            .cloned()
+            .map(CollatedKey::from)
+            .collect::<Vec<_>>();
+        got.sort();
+        let got = got.into_iter().map(|x| x.into_inner()).collect::<Vec<_>>();
+        assert_eq!(values, got);
+    }
+
+    #[test]
+    fn compare_types() {
+        expect_ascending(
+            &[
+                "null",
+                "false",
+                "true",
+                "-1",
+                "0",
+                "\"\"",
+                "\"a\"",
+                "[]",
+                "[null]",
+                "{}",
+                r#"{"a":null}"#,
+            ],
+        );
+    }
+
+    #[test]
+    fn compare_numbers() {
+        expect_ascending(
+            &[
+                "-9223372036854775808",
+                "-1.5",
+                "-1",
+                "0",
+                "1",
+                "2",
+                "3.5",
+                "4",
+                "9223372036854775807",
+                "18446744073709551615",
+            ],
+        );
+    }
+
+    #[test]
+    fn compare_numbers_integer_equals_float() {
+        assert_eq!(Ordering::Equal, compare(&json("3"), &json("3.0")));
+        assert_eq!(Ordering::Equal, compare(&json("-3"), &json("-3.0")));
+    }
+
+    #[test]
+    fn compare_strings_case() {
+        // This sequence comes from the CouchDB documentation.
+        expect_ascending(
+            &[
+                r#""a""#,
+                r#""A""#,
+                r#""aa""#,
+                r#""b""#,
+                r#""B""#,
+                r#""ba""#,
+                r#""bb""#,
+            ],
+        );
+    }
+
+    #[test]
+    fn compare_strings_ascii() {
+        // This sequence comes from the CouchDB documentation.
+        let source = "`^_-,;:!?.'\"()[]{}@*/\\&#%+<=>|~$0123456789aAbBcCdDeEfFgGhHiIjJkKlLmMnNoOpPqQrRsStTuUvVwWxXyYzZ";
+        let strings = source
+            .chars()
+            .map(|c| serde_json::to_string(&c.to_string()).unwrap())
+            .collect::<Vec<_>>();
+        let strings = strings.iter().map(|x| x.as_str()).collect::<Vec<_>>();
+        expect_ascending(&strings);
+    }
+
+    #[test]
+    fn compare_strings_high_string_sorts_last() {
+        let high = serde_json::to_string(&format!("foo{}", HIGH_STRING)).unwrap();
+        expect_ascending(&[r#""foo""#, r#""foo bar""#, r#""foo_bar""#, r#""fooZZZ""#, &high]);
+    }
+
+    #[test]
+    fn compare_arrays() {
+        // This sequence comes from the CouchDB documentation.
+        expect_ascending(
+            &[
+                r#"["a"]"#,
+                r#"["b"]"#,
+                r#"["b","c"]"#,
+                r#"["b","c","a"]"#,
+                r#"["b","d"]"#,
+                r#"["b","d","e"]"#,
+            ],
+        );
+    }
+
+    #[test]
+    fn compare_objects() {
+        expect_ascending(
+            &[
+                r#"{"a":1}"#,
+                r#"{"a":2}"#,
+                r#"{"b":1}"#,
+                r#"{"b":2}"#,
+                r#"{"b":2,"c":2}"#,
+            ],
+        );
+    }
+
+    #[test]
+    fn upper_bound_for_prefix_array() {
+        let expected = json(r#"["customer-42",2016,{}]"#);
+        let got = upper_bound_for_prefix(&json(r#"["customer-42",2016]"#)).unwrap();
+        assert_eq!(expected, got);
+        expect_ascending(
+            &[
+                r#"["customer-42",2016]"#,
+                r#"["customer-42",2016,12,31]"#,
+                r#"["customer-42",2016,"zzz"]"#,
+                r#"["customer-42",2016,[1]]"#,
+                r#"["customer-42",2016,{}]"#,
+                r#"["customer-42",2017]"#,
+            ],
+        );
+    }
+
+    #[test]
+    fn upper_bound_for_prefix_string() {
+        let expected = serde_json::Value::String(format!("foo{}", HIGH_STRING));
+        let got = upper_bound_for_prefix(&json(r#""foo""#)).unwrap();
+        assert_eq!(expected, got);
+    }
+
+    #[test]
+    fn upper_bound_for_prefix_other() {
+        assert_eq!(None, upper_bound_for_prefix(&json("null")));
+        assert_eq!(None, upper_bound_for_prefix(&json("42")));
+        assert_eq!(None, upper_bound_for_prefix(&json("{}")));
+    }
+
+    #[test]
+    fn collated_key_serialize_and_deserialize() {
+        let source = json(r#"["a",1,{"b":null}]"#);
+        let encoded = serde_json::to_string(&CollatedKey::from(source.clone())).unwrap();
+        let got: CollatedKey = serde_json::from_str(&encoded).unwrap();
+        assert_eq!(source, got.into_inner());
+    }
+}
@@ -0,0 +1,209 @@
+use {Document, Error, Revision, serde_json};
+
+/// Specifies how to resolve a document's conflicts.
+///
+/// A `Resolution` is the result of a `ConflictResolver`.
+///
+#[derive(Clone, Debug, PartialEq)]
+pub enum Resolution {
+    /// Keeps the leaf of the given revision as is and deletes all other
+    /// leaves.
+    ///
+    /// The revision must be one of the leaves given to the resolver.
+    ///
+    Keep(Revision),
+
+    /// Writes the given content as a new revision of the document and deletes
+    /// all other leaves.
+    ///
+    /// The new revision descends from the leaf that the CouchDB server
+    /// currently picks as the winner, and it keeps that leaf's attachments.
+    ///
+    Merge(serde_json::Value),
+}
+
+/// Describes a type that resolves a document's conflicts.
+///
+/// A `ConflictResolver` is given all undeleted leaf revisions of a
+/// conflicted document—at least two—and decides which content should win.
+/// The `ResolveConflicts` action then writes the winner and deletes the losing
+/// leaves.
+///
+/// Chill provides two resolvers:
+///
+/// * `HighestSequenceWins` keeps the leaf with the highest sequence number.
+/// * `MergeResolver` merges the leaves' content via a closure.
+///
+/// Any closure of type `Fn(&[Document]) -> Result<Resolution, Error>` is also
+/// a `ConflictResolver`.
+///
+pub trait ConflictResolver {
+    /// Decides how to resolve the conflict between the given leaves.
+    fn resolve(&self, leaves: &[Document]) -> Result<Resolution, Error>;
+}
+
+impl<F> ConflictResolver for F
+where
+    F: Fn(&[Document]) -> Result<Resolution, Error>,
+{
+    fn resolve(&self, leaves: &[Document]) -> Result<Resolution, Error> {
+        self(leaves)
+    }
+}
+
+/// Resolves conflicts by keeping the leaf with the highest sequence number.
+///
+/// If two or more leaves have the highest sequence number, the resolver keeps
+/// the one the CouchDB server picks as the winner—i.e., the one with the
+/// greatest digest.
+///
+#[derive(Debug, Default)]
+pub struct HighestSequenceWins;
+
+impl HighestSequenceWins {
+    /// Constructs a resolver that keeps the leaf with the highest sequence
+    /// number.
+    pub fn new() -> Self {
+        HighestSequenceWins
+    }
+}
+
+impl ConflictResolver for HighestSequenceWins {
+    fn resolve(&self, leaves: &[Document]) -> Result<Resolution, Error> {
+        let winner = try!(winning_leaf(leaves).ok_or(Error::UnexpectedResponse("The document has no leaves")));
+        Ok(Resolution::Keep(winner.revision().clone()))
+    }
+}
+
+/// Resolves conflicts by merging the content of all leaves via a closure.
+///
+/// The closure receives the content of each leaf, as JSON, and returns the
+/// merged content.
+///
+/// # Examples
+///
+/// ```no_run
+/// extern crate chill;
+/// extern crate serde_json;
+///
+/// // Merges the "tags" arrays of all leaves.
+/// let resolver = chill::MergeResolver::new(|leaves: &[serde_json::Value]| {
+///     let mut tags = Vec::new();
+///     for leaf in leaves {
+///         if let Some(&serde_json::Value::Array(ref x)) = leaf.find("tags") {
+///             tags.extend(x.iter().cloned());
+///         }
+///     }
+///     serde_json::builder::ObjectBuilder::new()
+///         .insert("tags", tags)
+///         .build()
+/// });
+///
+/// let client = chill::Client::new("http://localhost:5984").unwrap();
+/// client.resolve_conflicts("/baseball/babe_ruth", resolver).run().unwrap();
+/// ```
+///
+pub struct MergeResolver<F>
+where
+    F: Fn(&[serde_json::Value]) -> serde_json::Value,
+{
+    merge: F,
+}
+
+impl<F> MergeResolver<F>
+where
+    F: Fn(&[serde_json::Value]) -> serde_json::Value,
+{
+    /// Constructs a resolver that merges leaves via the given closure.
+    pub fn new(merge: F) -> Self {
+        MergeResolver { merge: merge }
+    }
+}
+
+impl<F> ConflictResolver for MergeResolver<F>
+where
+    F: Fn(&[serde_json::Value]) -> serde_json::Value,
+{
+    fn resolve(&self, leaves: &[Document]) -> Result<Resolution, Error> {
+        let mut contents = Vec::with_capacity(leaves.len());
+        for leaf in leaves {
+            contents.push(try!(leaf.get_content::<serde_json::Value>()));
+        }
+        Ok(Resolution::Merge((self.merge)(&contents)))
+    }
+}
+
+// Returns the leaf the CouchDB server picks among undeleted leaves as the
+// winner: the one with the greatest revision.
+pub fn winning_leaf(leaves: &[Document]) -> Option<&Document> {
+    leaves.iter().max_by(|a, b| a.revision().cmp(b.revision()))
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use {Document, Error, Revision, serde_json};
+    use document::DocumentBuilder;
+
+    fn make_leaf(rev: &str, field: i32) -> Document {
+        DocumentBuilder::new("/foo/bar", Revision::parse(rev).unwrap())
+            .build_content(|x| x.insert("field", field))
+            .unwrap()
+    }
+
+    #[test]
+    fn highest_sequence_wins_ok() {
+
+        let leaves = vec![
+            make_leaf("2-abcdef1234567890abcdef1234567890", 1),
+            make_leaf("3-1234567890abcdef1234567890abcdef", 2),
+            make_leaf("3-00000000000000000000000000000000", 3),
+        ];
+
+        let expected = Resolution::Keep(Revision::parse("3-1234567890abcdef1234567890abcdef").unwrap());
+        let got = HighestSequenceWins::new().resolve(&leaves).unwrap();
+        assert_eq!(expected, got);
+    }
+
+    #[test]
+    fn merge_resolver_ok() {
+
+        let leaves = vec![
+            make_leaf("2-abcdef1234567890abcdef1234567890", 1),
+            make_leaf("2-1234567890abcdef1234567890abcdef", 2),
+        ];
+
+        let resolver = MergeResolver::new(|contents: &[serde_json::Value]| {
+            let sum = contents
+                .iter()
+                .map(|x| x.find("field").and_then(|x| x.as_i64()).unwrap())
+                .sum::<i64>();
+            serde_json::builder::ObjectBuilder::new()
+                .insert("field", sum)
+                .build()
+        });
+
+        let expected = Resolution::Merge(
+            serde_json::builder::ObjectBuilder::new()
+                .insert("field", 3)
+                .build(),
+        );
+        let got = resolver.resolve(&leaves).unwrap();
+        assert_eq!(expected, got);
+    }
+
+    #[test]
+    fn closure_resolver_ok() {
+
+        let leaves = vec![make_leaf("2-abcdef1234567890abcdef1234567890", 1)];
+
+        let resolver = |leaves: &[Document]| -> Result<Resolution, Error> {
+            Ok(Resolution::Keep(leaves[0].revision().clone()))
+        };
+
+        let expected = Resolution::Keep(Revision::parse("2-abcdef1234567890abcdef1234567890").unwrap());
+        let got = resolver.resolve(&leaves).unwrap();
+        assert_eq!(expected, got);
+    }
+}
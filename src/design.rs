@@ -1,4 +1,4 @@
-use {ViewName, serde, std};
+use {Error, FilterName, ListName, Revision, ShowName, UpdateName, ViewName, serde, serde_json, std};
 
 /// Container for a _map_ and optional _reduce_ function of a view.
 ///
@@ -6,6 +6,9 @@ use {ViewName, serde, std};
 /// functions in design documents. For more information about view functions and
 /// design documents, please see the CouchDB documentation.
 ///
+/// Any other member of the view—e.g., `options`—is kept in `extra_fields` so
+/// that reading and re-saving a design document loses nothing.
+///
 /// # Examples
 ///
 /// ```
@@ -15,18 +18,22 @@ use {ViewName, serde, std};
 ///     "function(doc) { emit(doc.key_thing, doc.value_thing); }",
 ///     "_sum");
 ///
-/// assert_eq!("function(doc) { emit(doc.key_thing, doc.value_thing); }",
-///            view_function.map);
+/// assert_eq!(Some("function(doc) { emit(doc.key_thing, doc.value_thing); }"),
+///            view_function.map.as_str());
 /// assert_eq!(Some(String::from("_sum")), view_function.reduce);
 /// ```
-#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+#[derive(Clone, Debug, PartialEq)]
 pub struct ViewFunction {
     /// The view's map function.
     ///
+    /// The map function is usually a string containing JavaScript source code,
+    /// but CouchDB allows other JSON values—e.g., the map function of a Mango
+    /// index is a JSON object.
+    ///
     /// For more information about _map functions_, please see the CouchDB
     /// documentation.
     ///
-    pub map: String,
+    pub map: serde_json::Value,
 
     /// The view's reduce function, if available.
     ///
@@ -35,6 +42,9 @@ pub struct ViewFunction {
     ///
     pub reduce: Option<String>,
 
+    /// All other members of the view, keyed by name.
+    pub extra_fields: std::collections::BTreeMap<String, serde_json::Value>,
+
     // This field exists to prevent applications from directly constructing this
     // struct.
     _dummy: std::marker::PhantomData<()>,
@@ -44,8 +54,9 @@ impl ViewFunction {
     /// Constructs a new `ViewFunction` that has no _reduce function_.
     pub fn new<M: Into<String>>(map: M) -> Self {
         ViewFunction {
-            map: map.into(),
+            map: serde_json::Value::String(map.into()),
             reduce: None,
+            extra_fields: std::collections::BTreeMap::new(),
             _dummy: std::marker::PhantomData,
         }
     }
@@ -53,8 +64,9 @@ impl ViewFunction {
     /// Constructs a new `ViewFunction` that has a _reduce_ function.
     pub fn new_with_reduce<M: Into<String>, R: Into<String>>(map: M, reduce: R) -> Self {
         ViewFunction {
-            map: map.into(),
+            map: serde_json::Value::String(map.into()),
             reduce: Some(reduce.into()),
+            extra_fields: std::collections::BTreeMap::new(),
             _dummy: std::marker::PhantomData,
         }
     }
@@ -66,6 +78,7 @@ impl serde::Deserialize for ViewFunction {
         D: serde::Deserializer,
     {
         enum Field {
+            Extra(String),
             Map,
             Reduce,
         }
@@ -87,7 +100,7 @@ impl serde::Deserialize for ViewFunction {
                         match value {
                             "map" => Ok(Field::Map),
                             "reduce" => Ok(Field::Reduce),
-                            _ => Err(E::unknown_field(value)),
+                            _ => Ok(Field::Extra(value.to_string())),
                         }
                     }
                 }
@@ -105,11 +118,16 @@ impl serde::Deserialize for ViewFunction {
             where
                 V: serde::de::MapVisitor,
             {
+                let mut extra_fields = std::collections::BTreeMap::new();
                 let mut map = None;
                 let mut reduce = None;
 
                 loop {
                     match try!(visitor.visit_key()) {
+                        Some(Field::Extra(name)) => {
+                            let value = try!(visitor.visit_value::<serde_json::Value>());
+                            extra_fields.insert(name, value);
+                        }
                         Some(Field::Map) => {
                             map = Some(try!(visitor.visit_value()));
                         }
@@ -132,13 +150,14 @@ impl serde::Deserialize for ViewFunction {
                 Ok(ViewFunction {
                     map: map,
                     reduce: reduce,
+                    extra_fields: extra_fields,
                     _dummy: std::marker::PhantomData,
                 })
             }
         }
 
         static FIELDS: &'static [&'static str] = &["map", "reduce"];
-        deserializer.deserialize_struct("SavedAttachment", FIELDS, Visitor)
+        deserializer.deserialize_struct("ViewFunction", FIELDS, Visitor)
     }
 }
 
@@ -147,21 +166,23 @@ impl serde::Serialize for ViewFunction {
     where
         S: serde::Serializer,
     {
-        let len = if self.reduce.is_some() { 2 } else { 1 };
-        let mut state = try!(serializer.serialize_struct("ViewFunction", len));
-        try!(serializer.serialize_struct_elt(
-            &mut state,
-            "map",
-            &self.map,
-        ));
+        let len = 1 + self.reduce.iter().count() + self.extra_fields.len();
+        let mut state = try!(serializer.serialize_map(Some(len)));
+
+        try!(serializer.serialize_map_key(&mut state, "map"));
+        try!(serializer.serialize_map_value(&mut state, &self.map));
+
         if let Some(ref reduce) = self.reduce {
-            try!(serializer.serialize_struct_elt(
-                &mut state,
-                "reduce",
-                reduce,
-            ));
+            try!(serializer.serialize_map_key(&mut state, "reduce"));
+            try!(serializer.serialize_map_value(&mut state, reduce));
         }
-        serializer.serialize_struct_end(state)
+
+        for (name, value) in self.extra_fields.iter() {
+            try!(serializer.serialize_map_key(&mut state, name));
+            try!(serializer.serialize_map_value(&mut state, value));
+        }
+
+        serializer.serialize_map_end(state)
     }
 }
 
@@ -170,26 +191,264 @@ impl serde::Serialize for ViewFunction {
 /// `Design` is a convenience type for applications that create, read, or update
 /// design documents.
 ///
-/// Currently, `Design` supports only the `views` field of a design document.
-/// For more information about design documents, please see the CouchDB
-/// documentation.
+/// `Design` supports the standard fields of a design document—views, shows,
+/// lists, update handlers, filters, `validate_doc_update`, rewrites, and the
+/// like. Any other field is kept in `extra_fields` so that reading and
+/// re-saving a design document loses nothing. For more information about
+/// design documents, please see the CouchDB documentation.
+///
+/// Encoding normalizes the design document's JSON: the `views` field is always
+/// present, even if empty, whereas the `shows`, `lists`, `updates`, and
+/// `filters` fields are present only if non-empty. Consequently, a design
+/// document lacking a `views` field or having, say, an empty `shows` field
+/// doesn't encode back to identical JSON. Nevertheless, it decodes back to an
+/// equal `Design`, because a missing field and an empty field decode the same
+/// way—which is why the `DeployDesign` action compares `Design` values, not
+/// JSON.
 ///
-#[derive(Clone, Debug, Eq, PartialEq)]
+#[derive(Clone, Debug, PartialEq)]
 pub struct Design {
+    /// The query language of the design document's functions, if set.
+    pub language: Option<String>,
+
+    /// The design document's view options, if set.
+    pub options: Option<std::collections::BTreeMap<String, serde_json::Value>>,
+
     /// The view functions stored within the design document, if any.
     pub views: std::collections::HashMap<ViewName, ViewFunction>,
 
+    /// The show functions stored within the design document, if any.
+    pub shows: std::collections::HashMap<ShowName, String>,
+
+    /// The list functions stored within the design document, if any.
+    pub lists: std::collections::HashMap<ListName, String>,
+
+    /// The update handlers stored within the design document, if any.
+    pub updates: std::collections::HashMap<UpdateName, String>,
+
+    /// The filter functions stored within the design document, if any.
+    pub filters: std::collections::HashMap<FilterName, String>,
+
+    /// The design document's validation function, if set.
+    pub validate_doc_update: Option<String>,
+
+    /// The design document's rewrite rules, if set.
+    ///
+    /// CouchDB accepts rewrites as either an array of rules or a JavaScript
+    /// function, so this field holds raw JSON.
+    ///
+    pub rewrites: Option<serde_json::Value>,
+
+    /// Whether CouchDB automatically updates the design document's views, if
+    /// set.
+    pub autoupdate: Option<bool>,
+
+    /// All other fields of the design document, keyed by field name.
+    pub extra_fields: std::collections::BTreeMap<String, serde_json::Value>,
+
     // This field exists to prevent applications from directly constructing this
     // struct.
     _dummy: std::marker::PhantomData<()>,
 }
 
+impl Design {
+    /// Constructs design document content from the files in a directory.
+    ///
+    /// The directory may contain any of the following files, each of which
+    /// maps to the design document field of the same name. Missing files and
+    /// directories are skipped.
+    ///
+    /// * `language`—plain text, with surrounding whitespace removed,
+    /// * `options.json`—a JSON object,
+    /// * `views/<name>/map.js` and optionally `views/<name>/reduce.js`,
+    /// * `shows/<name>.js`, `lists/<name>.js`, `updates/<name>.js`, and
+    ///   `filters/<name>.js`,
+    /// * `validate_doc_update.js`, and
+    /// * `rewrites.json`—any JSON value.
+    ///
+    /// Function files are read verbatim.
+    ///
+    pub fn from_directory<P: AsRef<std::path::Path>>(dir_path: P) -> Result<Self, Error> {
+
+        let dir_path = dir_path.as_ref();
+        let mut builder = DesignBuilder::new();
+
+        if let Some(language) = try!(read_optional_file(&dir_path.join("language"))) {
+            builder = builder.with_language(language.trim());
+        }
+
+        if let Some(options) = try!(read_optional_file(&dir_path.join("options.json"))) {
+            let options = try!(serde_json::from_str(&options).map_err(|e| Error::JsonDecode { cause: e }));
+            builder.inner.options = Some(options);
+        }
+
+        for view_name in try!(read_optional_dir(&dir_path.join("views"), None)) {
+            let view_path = dir_path.join("views").join(&view_name);
+            let map = match try!(read_optional_file(&view_path.join("map.js"))) {
+                Some(x) => x,
+                None => {
+                    return Err(Error::Io {
+                        cause: std::io::Error::new(
+                            std::io::ErrorKind::NotFound,
+                            format!("{} has no map.js file", view_path.display()),
+                        ),
+                        description: "Failed to read design document view",
+                    })
+                }
+            };
+            let view_function = match try!(read_optional_file(&view_path.join("reduce.js"))) {
+                Some(reduce) => ViewFunction::new_with_reduce(map, reduce),
+                None => ViewFunction::new(map),
+            };
+            builder = builder.insert_view(view_name, view_function);
+        }
+
+        for name in try!(read_optional_dir(&dir_path.join("shows"), Some("js"))) {
+            let f = try!(read_file(&dir_path.join("shows").join(format!("{}.js", name))));
+            builder = builder.insert_show(name, f);
+        }
+
+        for name in try!(read_optional_dir(&dir_path.join("lists"), Some("js"))) {
+            let f = try!(read_file(&dir_path.join("lists").join(format!("{}.js", name))));
+            builder = builder.insert_list(name, f);
+        }
+
+        for name in try!(read_optional_dir(&dir_path.join("updates"), Some("js"))) {
+            let f = try!(read_file(&dir_path.join("updates").join(format!("{}.js", name))));
+            builder = builder.insert_update(name, f);
+        }
+
+        for name in try!(read_optional_dir(&dir_path.join("filters"), Some("js"))) {
+            let f = try!(read_file(&dir_path.join("filters").join(format!("{}.js", name))));
+            builder = builder.insert_filter(name, f);
+        }
+
+        if let Some(f) = try!(read_optional_file(&dir_path.join("validate_doc_update.js"))) {
+            builder = builder.with_validate_doc_update(f);
+        }
+
+        if let Some(rewrites) = try!(read_optional_file(&dir_path.join("rewrites.json"))) {
+            let rewrites = try!(serde_json::from_str(&rewrites).map_err(|e| Error::JsonDecode { cause: e }));
+            builder.inner.rewrites = Some(rewrites);
+        }
+
+        Ok(builder.unwrap())
+    }
+}
+
+fn read_file(path: &std::path::Path) -> Result<String, Error> {
+    use std::io::Read;
+    let mut s = String::new();
+    try!(
+        std::fs::File::open(path)
+            .and_then(|mut f| f.read_to_string(&mut s))
+            .map_err(|e| {
+                Error::Io {
+                    cause: e,
+                    description: "Failed to read design document file",
+                }
+            })
+    );
+    Ok(s)
+}
+
+fn read_optional_file(path: &std::path::Path) -> Result<Option<String>, Error> {
+    if !path.is_file() {
+        return Ok(None);
+    }
+    read_file(path).map(|x| Some(x))
+}
+
+// Returns the sorted names of the entries in the given directory. If an
+// extension is given then only files with that extension are included, and
+// their names are returned without the extension. Otherwise, only
+// subdirectories are included.
+fn read_optional_dir(path: &std::path::Path, extension: Option<&str>) -> Result<Vec<String>, Error> {
+
+    if !path.is_dir() {
+        return Ok(Vec::new());
+    }
+
+    let make_error = |e| {
+        Error::Io {
+            cause: e,
+            description: "Failed to read design document directory",
+        }
+    };
+
+    let mut names = Vec::new();
+
+    for entry in try!(std::fs::read_dir(path).map_err(&make_error)) {
+        let entry_path = try!(entry.map_err(&make_error)).path();
+        let name = match extension {
+            None if entry_path.is_dir() => entry_path.file_name(),
+            Some(extension) if entry_path.is_file() &&
+                                   entry_path.extension().and_then(|x| x.to_str()) == Some(extension) => {
+                entry_path.file_stem()
+            }
+            _ => continue,
+        };
+        match name.and_then(|x| x.to_str()) {
+            Some(x) => names.push(x.to_string()),
+            None => {
+                return Err(make_error(std::io::Error::new(
+                    std::io::ErrorKind::InvalidData,
+                    format!("{} is not a valid UTF-8 path", entry_path.display()),
+                )))
+            }
+        }
+    }
+
+    names.sort();
+    Ok(names)
+}
+
+/// Outcome of deploying a design document.
+///
+/// `Deployment` is returned by the `DeployDesign` action and states whether the
+/// design document on the server needed changing. Each variant holds the
+/// design document's current revision.
+///
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub enum Deployment {
+    /// The design document on the server already had the same content, so
+    /// nothing was written.
+    Unchanged(Revision),
+
+    /// The design document did not exist and was created.
+    Created(Revision),
+
+    /// The design document existed with different content and was updated.
+    Updated(Revision),
+}
+
+impl Deployment {
+    /// Returns the design document's revision after deployment.
+    pub fn revision(&self) -> &Revision {
+        match self {
+            &Deployment::Unchanged(ref x) => x,
+            &Deployment::Created(ref x) => x,
+            &Deployment::Updated(ref x) => x,
+        }
+    }
+}
+
 impl serde::Deserialize for Design {
     fn deserialize<D>(deserializer: &mut D) -> Result<Self, D::Error>
     where
         D: serde::Deserializer,
     {
         enum Field {
+            Autoupdate,
+            Extra(String),
+            Filters,
+            Language,
+            Lists,
+            Options,
+            Rewrites,
+            Shows,
+            Updates,
+            ValidateDocUpdate,
             Views,
         }
 
@@ -208,8 +467,17 @@ impl serde::Deserialize for Design {
                         E: serde::de::Error,
                     {
                         match value {
+                            "autoupdate" => Ok(Field::Autoupdate),
+                            "filters" => Ok(Field::Filters),
+                            "language" => Ok(Field::Language),
+                            "lists" => Ok(Field::Lists),
+                            "options" => Ok(Field::Options),
+                            "rewrites" => Ok(Field::Rewrites),
+                            "shows" => Ok(Field::Shows),
+                            "updates" => Ok(Field::Updates),
+                            "validate_doc_update" => Ok(Field::ValidateDocUpdate),
                             "views" => Ok(Field::Views),
-                            _ => Err(E::unknown_field(value)),
+                            _ => Ok(Field::Extra(value.to_string())),
                         }
                     }
                 }
@@ -227,10 +495,51 @@ impl serde::Deserialize for Design {
             where
                 V: serde::de::MapVisitor,
             {
+                let mut autoupdate = None;
+                let mut extra_fields = std::collections::BTreeMap::new();
+                let mut filters = None;
+                let mut language = None;
+                let mut lists = None;
+                let mut options = None;
+                let mut rewrites = None;
+                let mut shows = None;
+                let mut updates = None;
+                let mut validate_doc_update = None;
                 let mut views = None;
 
                 loop {
                     match try!(visitor.visit_key()) {
+                        Some(Field::Autoupdate) => {
+                            autoupdate = Some(try!(visitor.visit_value()));
+                        }
+                        Some(Field::Extra(name)) => {
+                            let value = try!(visitor.visit_value::<serde_json::Value>());
+                            extra_fields.insert(name, value);
+                        }
+                        Some(Field::Filters) => {
+                            filters = Some(try!(visitor.visit_value()));
+                        }
+                        Some(Field::Language) => {
+                            language = Some(try!(visitor.visit_value()));
+                        }
+                        Some(Field::Lists) => {
+                            lists = Some(try!(visitor.visit_value()));
+                        }
+                        Some(Field::Options) => {
+                            options = Some(try!(visitor.visit_value()));
+                        }
+                        Some(Field::Rewrites) => {
+                            rewrites = Some(try!(visitor.visit_value()));
+                        }
+                        Some(Field::Shows) => {
+                            shows = Some(try!(visitor.visit_value()));
+                        }
+                        Some(Field::Updates) => {
+                            updates = Some(try!(visitor.visit_value()));
+                        }
+                        Some(Field::ValidateDocUpdate) => {
+                            validate_doc_update = Some(try!(visitor.visit_value()));
+                        }
                         Some(Field::Views) => {
                             views = Some(try!(visitor.visit_value()));
                         }
@@ -242,19 +551,35 @@ impl serde::Deserialize for Design {
 
                 try!(visitor.end());
 
-                let views = match views {
-                    Some(x) => x,
-                    None => std::collections::HashMap::new(),
-                };
-
                 Ok(Design {
-                    views: views,
+                    language: language,
+                    options: options,
+                    views: views.unwrap_or_else(std::collections::HashMap::new),
+                    shows: shows.unwrap_or_else(std::collections::HashMap::new),
+                    lists: lists.unwrap_or_else(std::collections::HashMap::new),
+                    updates: updates.unwrap_or_else(std::collections::HashMap::new),
+                    filters: filters.unwrap_or_else(std::collections::HashMap::new),
+                    validate_doc_update: validate_doc_update,
+                    rewrites: rewrites,
+                    autoupdate: autoupdate,
+                    extra_fields: extra_fields,
                     _dummy: std::marker::PhantomData,
                 })
             }
         }
 
-        static FIELDS: &'static [&'static str] = &["views"];
+        static FIELDS: &'static [&'static str] = &[
+            "autoupdate",
+            "filters",
+            "language",
+            "lists",
+            "options",
+            "rewrites",
+            "shows",
+            "updates",
+            "validate_doc_update",
+            "views",
+        ];
         deserializer.deserialize_struct("Design", FIELDS, Visitor)
     }
 }
@@ -264,13 +589,78 @@ impl serde::Serialize for Design {
     where
         S: serde::Serializer,
     {
-        let mut state = try!(serializer.serialize_struct("Design", 1));
-        try!(serializer.serialize_struct_elt(
-            &mut state,
-            "views",
-            &self.views,
-        ));
-        serializer.serialize_struct_end(state)
+        // The views field is always present, for compatibility with earlier
+        // versions. All other fields are present only if set or non-empty. See
+        // the type's documentation about this normalization.
+
+        let len = 1 + self.language.iter().count() + self.options.iter().count() +
+            if self.shows.is_empty() { 0 } else { 1 } + if self.lists.is_empty() { 0 } else { 1 } +
+            if self.updates.is_empty() { 0 } else { 1 } + if self.filters.is_empty() { 0 } else { 1 } +
+            self.validate_doc_update.iter().count() + self.rewrites.iter().count() +
+            self.autoupdate.iter().count() + self.extra_fields.len();
+
+        let mut state = try!(serializer.serialize_map(Some(len)));
+
+        if let Some(ref language) = self.language {
+            try!(serializer.serialize_map_key(&mut state, "language"));
+            try!(serializer.serialize_map_value(&mut state, language));
+        }
+
+        if let Some(ref options) = self.options {
+            try!(serializer.serialize_map_key(&mut state, "options"));
+            try!(serializer.serialize_map_value(&mut state, options));
+        }
+
+        try!(serializer.serialize_map_key(&mut state, "views"));
+        try!(serializer.serialize_map_value(&mut state, &self.views));
+
+        if !self.shows.is_empty() {
+            try!(serializer.serialize_map_key(&mut state, "shows"));
+            try!(serializer.serialize_map_value(&mut state, &self.shows));
+        }
+
+        if !self.lists.is_empty() {
+            try!(serializer.serialize_map_key(&mut state, "lists"));
+            try!(serializer.serialize_map_value(&mut state, &self.lists));
+        }
+
+        if !self.updates.is_empty() {
+            try!(serializer.serialize_map_key(&mut state, "updates"));
+            try!(serializer.serialize_map_value(&mut state, &self.updates));
+        }
+
+        if !self.filters.is_empty() {
+            try!(serializer.serialize_map_key(&mut state, "filters"));
+            try!(serializer.serialize_map_value(&mut state, &self.filters));
+        }
+
+        if let Some(ref validate_doc_update) = self.validate_doc_update {
+            try!(serializer.serialize_map_key(
+                &mut state,
+                "validate_doc_update",
+            ));
+            try!(serializer.serialize_map_value(
+                &mut state,
+                validate_doc_update,
+            ));
+        }
+
+        if let Some(ref rewrites) = self.rewrites {
+            try!(serializer.serialize_map_key(&mut state, "rewrites"));
+            try!(serializer.serialize_map_value(&mut state, rewrites));
+        }
+
+        if let Some(ref autoupdate) = self.autoupdate {
+            try!(serializer.serialize_map_key(&mut state, "autoupdate"));
+            try!(serializer.serialize_map_value(&mut state, autoupdate));
+        }
+
+        for (name, value) in self.extra_fields.iter() {
+            try!(serializer.serialize_map_key(&mut state, name));
+            try!(serializer.serialize_map_value(&mut state, value));
+        }
+
+        serializer.serialize_map_end(state)
     }
 }
 
@@ -290,7 +680,17 @@ impl DesignBuilder {
     pub fn new() -> Self {
         DesignBuilder {
             inner: Design {
+                language: None,
+                options: None,
                 views: std::collections::HashMap::new(),
+                shows: std::collections::HashMap::new(),
+                lists: std::collections::HashMap::new(),
+                updates: std::collections::HashMap::new(),
+                filters: std::collections::HashMap::new(),
+                validate_doc_update: None,
+                rewrites: None,
+                autoupdate: None,
+                extra_fields: std::collections::BTreeMap::new(),
                 _dummy: std::marker::PhantomData,
             },
         }
@@ -301,6 +701,25 @@ impl DesignBuilder {
         self.inner
     }
 
+    /// Sets the query language of the design document's functions.
+    pub fn with_language<L: Into<String>>(mut self, language: L) -> Self {
+        self.inner.language = Some(language.into());
+        self
+    }
+
+    /// Inserts a view option into the design document content.
+    pub fn insert_option<K, V>(mut self, key: K, value: V) -> Self
+    where
+        K: Into<String>,
+        V: serde::Serialize,
+    {
+        self.inner
+            .options
+            .get_or_insert_with(std::collections::BTreeMap::new)
+            .insert(key.into(), serde_json::to_value(&value));
+        self
+    }
+
     /// Inserts a view into the design document content.
     pub fn insert_view<V>(mut self, view_name: V, view_function: ViewFunction) -> Self
     where
@@ -309,13 +728,98 @@ impl DesignBuilder {
         self.inner.views.insert(view_name.into(), view_function);
         self
     }
+
+    /// Inserts a show function into the design document content.
+    pub fn insert_show<N, F>(mut self, show_name: N, show_function: F) -> Self
+    where
+        N: Into<ShowName>,
+        F: Into<String>,
+    {
+        self.inner.shows.insert(show_name.into(), show_function.into());
+        self
+    }
+
+    /// Inserts a list function into the design document content.
+    pub fn insert_list<N, F>(mut self, list_name: N, list_function: F) -> Self
+    where
+        N: Into<ListName>,
+        F: Into<String>,
+    {
+        self.inner.lists.insert(list_name.into(), list_function.into());
+        self
+    }
+
+    /// Inserts an update handler into the design document content.
+    pub fn insert_update<N, F>(mut self, update_name: N, update_function: F) -> Self
+    where
+        N: Into<UpdateName>,
+        F: Into<String>,
+    {
+        self.inner.updates.insert(
+            update_name.into(),
+            update_function.into(),
+        );
+        self
+    }
+
+    /// Inserts a filter function into the design document content.
+    pub fn insert_filter<N, F>(mut self, filter_name: N, filter_function: F) -> Self
+    where
+        N: Into<FilterName>,
+        F: Into<String>,
+    {
+        self.inner.filters.insert(
+            filter_name.into(),
+            filter_function.into(),
+        );
+        self
+    }
+
+    /// Sets the design document's validation function.
+    pub fn with_validate_doc_update<F: Into<String>>(mut self, validate_function: F) -> Self {
+        self.inner.validate_doc_update = Some(validate_function.into());
+        self
+    }
+
+    /// Sets the design document's rewrite rules.
+    pub fn with_rewrites<R: serde::Serialize>(mut self, rewrites: R) -> Self {
+        self.inner.rewrites = Some(serde_json::to_value(&rewrites));
+        self
+    }
+
+    /// Sets whether CouchDB automatically updates the design document's views.
+    pub fn with_autoupdate(mut self, autoupdate: bool) -> Self {
+        self.inner.autoupdate = Some(autoupdate);
+        self
+    }
+
+    /// Inserts a field not otherwise supported by `Design` into the design
+    /// document content.
+    pub fn insert_extra_field<K, V>(mut self, key: K, value: V) -> Self
+    where
+        K: Into<String>,
+        V: serde::Serialize,
+    {
+        self.inner.extra_fields.insert(
+            key.into(),
+            serde_json::to_value(&value),
+        );
+        self
+    }
 }
 
 #[cfg(test)]
 mod tests {
 
     use super::*;
-    use {serde_json, std};
+    use {Error, serde_json, std, tempdir};
+
+    fn write_file<P: AsRef<std::path::Path>>(path: P, content: &str) {
+        use std::io::Write;
+        let path = path.as_ref();
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::File::create(path).unwrap().write_all(content.as_bytes()).unwrap();
+    }
 
     #[test]
     fn view_function_new() {
@@ -323,8 +827,9 @@ mod tests {
         let map_function = r#"function(doc) { emit(doc.key, doc.value); }"#;
 
         let expected = ViewFunction {
-            map: String::from(map_function),
+            map: serde_json::Value::String(String::from(map_function)),
             reduce: None,
+            extra_fields: std::collections::BTreeMap::new(),
             _dummy: std::marker::PhantomData,
         };
 
@@ -340,8 +845,9 @@ mod tests {
         let reduce_function = r#"_count"#;
 
         let expected = ViewFunction {
-            map: String::from(map_function),
+            map: serde_json::Value::String(String::from(map_function)),
             reduce: Some(String::from(reduce_function)),
+            extra_fields: std::collections::BTreeMap::new(),
             _dummy: std::marker::PhantomData,
         };
 
@@ -422,6 +928,28 @@ mod tests {
         assert_eq!(expected, got);
     }
 
+    #[test]
+    fn view_function_deserialize_ok_with_options() {
+
+        let source = serde_json::builder::ObjectBuilder::new()
+            .insert("map", "function(doc) { emit(doc.key_thing, doc.value_thing); }")
+            .insert_object("options", |x| x.insert("local_seq", true))
+            .build();
+
+        let got: ViewFunction = serde_json::from_value(source.clone()).unwrap();
+
+        let mut expected = ViewFunction::new("function(doc) { emit(doc.key_thing, doc.value_thing); }");
+        expected.extra_fields.insert(
+            String::from("options"),
+            serde_json::builder::ObjectBuilder::new()
+                .insert("local_seq", true)
+                .build(),
+        );
+        assert_eq!(expected, got);
+
+        assert_eq!(source, serde_json::to_value(&got));
+    }
+
     #[test]
     fn view_function_deserialize_nok_missing_map() {
 
@@ -473,6 +1001,49 @@ mod tests {
         assert_eq!(expected, got);
     }
 
+    #[test]
+    fn design_serialize_with_all_fields() {
+
+        let design = DesignBuilder::new()
+            .with_language("javascript")
+            .insert_option("local_seq", true)
+            .insert_view("alpha", ViewFunction::new("function(doc) { emit(doc.key, doc.value); }"))
+            .insert_show("charlie", "function(doc, req) { return 'charlie'; }")
+            .insert_list("delta", "function(head, req) { send('delta'); }")
+            .insert_update("echo", "function(doc, req) { return [doc, 'echo']; }")
+            .insert_filter("foxtrot", "function(doc, req) { return true; }")
+            .with_validate_doc_update("function(newDoc, oldDoc, userCtx) {}")
+            .with_rewrites(serde_json::builder::ArrayBuilder::new()
+                .push_object(|x| x.insert("from", "/golf").insert("to", "_show/charlie"))
+                .build())
+            .with_autoupdate(false)
+            .insert_extra_field("hotel", 42)
+            .unwrap();
+
+        let encoded = serde_json::to_string(&design).unwrap();
+
+        let expected = serde_json::builder::ObjectBuilder::new()
+            .insert("language", "javascript")
+            .insert_object("options", |x| x.insert("local_seq", true))
+            .insert_object("views", |x| {
+                x.insert_object("alpha", |x| x.insert("map", "function(doc) { emit(doc.key, doc.value); }"))
+            })
+            .insert_object("shows", |x| x.insert("charlie", "function(doc, req) { return 'charlie'; }"))
+            .insert_object("lists", |x| x.insert("delta", "function(head, req) { send('delta'); }"))
+            .insert_object("updates", |x| x.insert("echo", "function(doc, req) { return [doc, 'echo']; }"))
+            .insert_object("filters", |x| x.insert("foxtrot", "function(doc, req) { return true; }"))
+            .insert("validate_doc_update", "function(newDoc, oldDoc, userCtx) {}")
+            .insert_array("rewrites", |x| {
+                x.push_object(|x| x.insert("from", "/golf").insert("to", "_show/charlie"))
+            })
+            .insert("autoupdate", false)
+            .insert("hotel", 42)
+            .build();
+
+        let got = serde_json::from_str(&encoded).unwrap();
+        assert_eq!(expected, got);
+    }
+
     #[test]
     fn design_deserialize_ok_empty() {
         let expected = DesignBuilder::new().unwrap();
@@ -519,4 +1090,187 @@ mod tests {
         let got = serde_json::from_str(&source).unwrap();
         assert_eq!(expected, got);
     }
+
+    #[test]
+    fn design_deserialize_ok_with_all_fields() {
+
+        let expected = DesignBuilder::new()
+            .with_language("javascript")
+            .insert_option("local_seq", true)
+            .insert_view("alpha", ViewFunction::new("function(doc) { emit(doc.key, doc.value); }"))
+            .insert_show("charlie", "function(doc, req) { return 'charlie'; }")
+            .insert_list("delta", "function(head, req) { send('delta'); }")
+            .insert_update("echo", "function(doc, req) { return [doc, 'echo']; }")
+            .insert_filter("foxtrot", "function(doc, req) { return true; }")
+            .with_validate_doc_update("function(newDoc, oldDoc, userCtx) {}")
+            .with_rewrites("function(req) { return '/golf'; }")
+            .with_autoupdate(false)
+            .unwrap();
+
+        let source = serde_json::builder::ObjectBuilder::new()
+            .insert("language", "javascript")
+            .insert_object("options", |x| x.insert("local_seq", true))
+            .insert_object("views", |x| {
+                x.insert_object("alpha", |x| x.insert("map", "function(doc) { emit(doc.key, doc.value); }"))
+            })
+            .insert_object("shows", |x| x.insert("charlie", "function(doc, req) { return 'charlie'; }"))
+            .insert_object("lists", |x| x.insert("delta", "function(head, req) { send('delta'); }"))
+            .insert_object("updates", |x| x.insert("echo", "function(doc, req) { return [doc, 'echo']; }"))
+            .insert_object("filters", |x| x.insert("foxtrot", "function(doc, req) { return true; }"))
+            .insert("validate_doc_update", "function(newDoc, oldDoc, userCtx) {}")
+            .insert("rewrites", "function(req) { return '/golf'; }")
+            .insert("autoupdate", false)
+            .build();
+
+        let source = serde_json::to_string(&source).unwrap();
+        let got = serde_json::from_str(&source).unwrap();
+        assert_eq!(expected, got);
+    }
+
+    #[test]
+    fn design_round_trip_preserves_unknown_fields() {
+
+        let source = serde_json::builder::ObjectBuilder::new()
+            .insert_object("views", |x| x)
+            .insert("hotel", 42)
+            .insert_object("india", |x| x.insert("juliett", "kilo"))
+            .build();
+
+        let design: Design = serde_json::from_str(&serde_json::to_string(&source).unwrap()).unwrap();
+        assert_eq!(Some(&serde_json::Value::U64(42)), design.extra_fields.get("hotel"));
+
+        let encoded = serde_json::to_string(&design).unwrap();
+        let got: serde_json::Value = serde_json::from_str(&encoded).unwrap();
+        assert_eq!(source, got);
+    }
+
+    #[test]
+    fn design_round_trip_preserves_mango_index() {
+
+        let source = serde_json::builder::ObjectBuilder::new()
+            .insert("language", "query")
+            .insert_object("views", |x| {
+                x.insert_object("foo-index", |x| {
+                    x.insert_object("map", |x| {
+                        x.insert_object("fields", |x| x.insert("foo", "asc"))
+                            .insert_object("partial_filter_selector", |x| x)
+                    }).insert("reduce", "_count")
+                        .insert_object("options", |x| {
+                            x.insert_object("def", |x| x.insert_array("fields", |x| x.push("foo")))
+                        })
+                })
+            })
+            .build();
+
+        let design: Design = serde_json::from_value(source.clone()).unwrap();
+        assert_eq!(Some(String::from("query")), design.language);
+
+        let view_function = design.views.get(&ViewName::from("foo-index")).unwrap();
+        assert!(view_function.map.is_object());
+        assert!(view_function.extra_fields.contains_key("options"));
+
+        assert_eq!(source, serde_json::to_value(&design));
+    }
+
+    #[test]
+    fn design_round_trip_normalizes_views_and_empty_functions() {
+
+        let source = serde_json::builder::ObjectBuilder::new()
+            .insert_object("shows", |x| x)
+            .insert("hotel", 42)
+            .build();
+
+        let design: Design = serde_json::from_value(source).unwrap();
+        assert!(design.views.is_empty());
+        assert!(design.shows.is_empty());
+        assert_eq!(Some(&serde_json::Value::U64(42)), design.extra_fields.get("hotel"));
+
+        let encoded = serde_json::to_string(&design).unwrap();
+
+        let expected_json = serde_json::builder::ObjectBuilder::new()
+            .insert_object("views", |x| x)
+            .insert("hotel", 42)
+            .build();
+        let got_json: serde_json::Value = serde_json::from_str(&encoded).unwrap();
+        assert_eq!(expected_json, got_json);
+
+        let got: Design = serde_json::from_str(&encoded).unwrap();
+        assert_eq!(design, got);
+    }
+
+    #[test]
+    fn design_from_directory_ok_empty() {
+        let tmp = tempdir::TempDir::new("chill_test").unwrap();
+        let expected = DesignBuilder::new().unwrap();
+        let got = Design::from_directory(tmp.path()).unwrap();
+        assert_eq!(expected, got);
+    }
+
+    #[test]
+    fn design_from_directory_ok_with_all_files() {
+
+        let tmp = tempdir::TempDir::new("chill_test").unwrap();
+        let root = tmp.path();
+        write_file(root.join("language"), "javascript\n");
+        write_file(root.join("options.json"), r#"{"local_seq":true}"#);
+        write_file(root.join("views/alpha/map.js"), "function(doc) { emit(doc.key, doc.value); }");
+        write_file(root.join("views/bravo/map.js"), "function(doc) { emit(doc.key_2, doc.value_2); }");
+        write_file(root.join("views/bravo/reduce.js"), "_sum");
+        write_file(root.join("shows/charlie.js"), "function(doc, req) { return 'charlie'; }");
+        write_file(root.join("shows/README.md"), "Not a function");
+        write_file(root.join("lists/delta.js"), "function(head, req) { send('delta'); }");
+        write_file(root.join("updates/echo.js"), "function(doc, req) { return [doc, 'echo']; }");
+        write_file(root.join("filters/foxtrot.js"), "function(doc, req) { return true; }");
+        write_file(root.join("validate_doc_update.js"), "function(newDoc, oldDoc, userCtx) {}");
+        write_file(root.join("rewrites.json"), r#"[{"from":"/golf","to":"_show/charlie"}]"#);
+
+        let expected = DesignBuilder::new()
+            .with_language("javascript")
+            .insert_option("local_seq", true)
+            .insert_view("alpha", ViewFunction::new("function(doc) { emit(doc.key, doc.value); }"))
+            .insert_view(
+                "bravo",
+                ViewFunction::new_with_reduce("function(doc) { emit(doc.key_2, doc.value_2); }", "_sum"),
+            )
+            .insert_show("charlie", "function(doc, req) { return 'charlie'; }")
+            .insert_list("delta", "function(head, req) { send('delta'); }")
+            .insert_update("echo", "function(doc, req) { return [doc, 'echo']; }")
+            .insert_filter("foxtrot", "function(doc, req) { return true; }")
+            .with_validate_doc_update("function(newDoc, oldDoc, userCtx) {}")
+            .with_rewrites(serde_json::builder::ArrayBuilder::new()
+                .push_object(|x| x.insert("from", "/golf").insert("to", "_show/charlie"))
+                .build())
+            .unwrap();
+
+        let got = Design::from_directory(root).unwrap();
+        assert_eq!(expected, got);
+    }
+
+    #[test]
+    fn design_from_directory_nok_view_without_map() {
+        let tmp = tempdir::TempDir::new("chill_test").unwrap();
+        write_file(tmp.path().join("views/alpha/reduce.js"), "_sum");
+        match Design::from_directory(tmp.path()) {
+            Err(Error::Io { .. }) => (),
+            x @ _ => unexpected_result!(x),
+        }
+    }
+
+    #[test]
+    fn design_from_directory_nok_bad_options() {
+        let tmp = tempdir::TempDir::new("chill_test").unwrap();
+        write_file(tmp.path().join("options.json"), "not JSON");
+        match Design::from_directory(tmp.path()) {
+            Err(Error::JsonDecode { .. }) => (),
+            x @ _ => unexpected_result!(x),
+        }
+    }
+
+    #[test]
+    fn deployment_revision() {
+        let revision = Revision::parse("1-1234567890abcdef1234567890abcdef").unwrap();
+        assert_eq!(&revision, Deployment::Unchanged(revision.clone()).revision());
+        assert_eq!(&revision, Deployment::Created(revision.clone()).revision());
+        assert_eq!(&revision, Deployment::Updated(revision.clone()).revision());
+    }
 }
@@ -1,4 +1,5 @@
-use {Attachment, AttachmentName, AttachmentPath, DatabaseName, DocumentId, DocumentPath, Error, Revision};
+use {Attachment, AttachmentName, AttachmentPath, DatabaseName, DocumentId, DocumentPath, Error, Revision,
+     RevisionStatus};
 use {mime, serde, serde_json, std};
 use IntoDocumentPath;
 use attachment::AttachmentBuilder;
@@ -14,6 +15,10 @@ pub struct Document {
     doc_path: DocumentPath,
     revision: Revision,
     deleted: bool,
+    conflicts: Vec<Revision>,
+    deleted_conflicts: Vec<Revision>,
+    revisions: Vec<Revision>,
+    revisions_info: Vec<(Revision, RevisionStatus)>,
     attachments: std::collections::HashMap<AttachmentName, Attachment>,
     content: serde_json::Value,
 }
@@ -25,11 +30,16 @@ impl Document {
             doc_path: DocumentPath::from((db_name, doc.doc_id)),
             revision: doc.revision,
             deleted: doc.deleted,
+            conflicts: doc.conflicts,
+            deleted_conflicts: doc.deleted_conflicts,
+            revisions: doc.revisions,
+            revisions_info: doc.revisions_info,
             attachments: doc.attachments,
             content: doc.content,
         }
     }
 
+    /// Returns the document's path.
     pub fn path(&self) -> &DocumentPath {
         &self.doc_path
     }
@@ -51,12 +61,77 @@ impl Document {
         self.deleted
     }
 
+    /// Returns the revisions of the document's conflicts.
+    ///
+    /// A document has conflicts when two or more revisions of it are leaves of
+    /// its revision tree—e.g., after replicating concurrent updates. The
+    /// server picks one of the leaves as the winning revision, which is the
+    /// revision of this `Document`, and the others are its conflicts.
+    ///
+    /// The CouchDB server sends conflicts only if the application requests
+    /// them, via the `ReadDocument::with_conflicts` method. Otherwise, this
+    /// method returns an empty slice.
+    ///
+    pub fn conflicts(&self) -> &[Revision] {
+        &self.conflicts
+    }
+
+    /// Returns the revisions of the document's deleted conflicts.
+    ///
+    /// A deleted conflict is a conflicting leaf revision that has since been
+    /// deleted. The CouchDB server sends deleted conflicts only if the
+    /// application requests them, via the `ReadDocument::with_deleted_conflicts`
+    /// method. Otherwise, this method returns an empty slice.
+    ///
+    pub fn deleted_conflicts(&self) -> &[Revision] {
+        &self.deleted_conflicts
+    }
+
+    /// Returns the document's revision history, newest first.
+    ///
+    /// The history starts with the document's own revision and continues with
+    /// each ancestor revision, back to the first revision the server knows of.
+    /// The CouchDB server sends the history only if the application requests
+    /// it, via the `ReadDocument::with_revisions` method. Otherwise, this
+    /// method returns an empty slice.
+    ///
+    pub fn revisions(&self) -> &[Revision] {
+        &self.revisions
+    }
+
+    /// Returns the availability of each revision in the document's history,
+    /// newest first.
+    ///
+    /// The CouchDB server sends this information only if the application
+    /// requests it, via the `ReadDocument::with_revisions_info` method.
+    /// Otherwise, this method returns an empty slice.
+    ///
+    pub fn revisions_info(&self) -> &[(Revision, RevisionStatus)] {
+        &self.revisions_info
+    }
+
     /// Decodes and returns the document content, from a JSON object into a Rust
     /// type.
     pub fn get_content<C: serde::Deserialize>(&self) -> Result<C, Error> {
         serde_json::from_value(self.content.clone()).map_err(|e| Error::JsonDecode { cause: e })
     }
 
+    /// Converts the document into a `TypedDocument` by decoding the document
+    /// content, from a JSON object into a Rust type.
+    ///
+    /// The typed document keeps the document's path, revision, and
+    /// attachments but discards its conflicts and revision history.
+    ///
+    pub fn into_typed<C: serde::Deserialize>(self) -> Result<TypedDocument<C>, Error> {
+        let content = try!(serde_json::from_value(self.content).map_err(|e| Error::JsonDecode { cause: e }));
+        Ok(TypedDocument {
+            doc_path: self.doc_path,
+            revision: self.revision,
+            attachments: self.attachments,
+            content: content,
+        })
+    }
+
     /// Encodes the document content, from a Rust type into a JSON object.
     ///
     /// The `set_content` method modifies the `Document` instance but doesn't
@@ -128,30 +203,64 @@ impl serde::Serialize for Document {
     where
         S: serde::Serializer,
     {
-        // Serde requires structure field names to have static lifetimes.
-        // However, our document content is dynamic. As a workaround, we construct a
-        // serde_json::Value instance containing both the document's content and
-        // its attachments.
-
-        let mut value = self.content.clone();
-
-        if let serde_json::Value::Object(ref mut fields) = value {
-            if !self.attachments.is_empty() {
-                let mut attachments = std::collections::BTreeMap::new();
-                for (name, attachment) in self.attachments.iter() {
-                    attachments.insert(String::from(name.clone()), serde_json::to_value(attachment));
-                }
-                fields.insert(
-                    "_attachments".to_string(),
-                    serde_json::Value::Object(attachments),
-                );
+        serialize_with_attachments(self.content.clone(), &self.attachments, serializer)
+    }
+}
+
+impl UpdatableDocument for Document {
+    fn path(&self) -> &DocumentPath {
+        &self.doc_path
+    }
+
+    fn revision(&self) -> &Revision {
+        &self.revision
+    }
+}
+
+fn serialize_with_attachments<S>(
+    mut value: serde_json::Value,
+    attachments: &std::collections::HashMap<AttachmentName, Attachment>,
+    serializer: &mut S,
+) -> Result<(), S::Error>
+where
+    S: serde::Serializer,
+{
+    // Serde requires structure field names to have static lifetimes.
+    // However, our document content is dynamic. As a workaround, we construct a
+    // serde_json::Value instance containing both the document's content and
+    // its attachments.
+
+    if let serde_json::Value::Object(ref mut fields) = value {
+        if !attachments.is_empty() {
+            let mut attachments_value = std::collections::BTreeMap::new();
+            for (name, attachment) in attachments.iter() {
+                attachments_value.insert(String::from(name.clone()), serde_json::to_value(attachment));
             }
-        } else {
-            panic!("Document content is not a JSON object");
+            fields.insert(
+                "_attachments".to_string(),
+                serde_json::Value::Object(attachments_value),
+            );
         }
-
-        value.serialize(serializer)
+    } else {
+        use serde::ser::Error;
+        return Err(S::Error::custom("Document content is not a JSON object"));
     }
+
+    serde::Serialize::serialize(&value, serializer)
+}
+
+/// Describes a document type that the `UpdateDocument` action can write.
+///
+/// Both `Document` and `TypedDocument` are updatable. An updatable document
+/// serializes to the request body the CouchDB server expects when updating
+/// the document—i.e., the document's content plus its attachments.
+///
+pub trait UpdatableDocument: serde::Serialize {
+    /// Returns the document's path.
+    fn path(&self) -> &DocumentPath;
+
+    /// Returns the document's revision, which the update replaces.
+    fn revision(&self) -> &Revision;
 }
 
 pub struct AttachmentIter<'a> {
@@ -175,6 +284,135 @@ impl<'a> Iterator for AttachmentIter<'a> {
     }
 }
 
+/// Contains a specific version of a document, with its content decoded into
+/// a Rust type.
+///
+/// A `TypedDocument` is like a `Document` except that it holds the document
+/// content as an instance of `C` rather than as JSON. The content is decoded
+/// once, when the document is read—e.g., via the `ReadDocument::run_typed`
+/// method—and encoded once, when the document is written via the
+/// `UpdateDocument` action. In between, the application accesses the content
+/// directly, without converting to or from JSON.
+///
+/// The content type must decode from—and, for updating, encode to—a JSON
+/// object. Updating a document whose content encodes to anything else fails
+/// with an `Error::JsonEncode` error. Fields that begin with an underscore,
+/// such as `_id` and `_rev`, are reserved by the CouchDB server and shouldn't
+/// be part of the content type.
+///
+#[derive(Clone, Debug, PartialEq)]
+pub struct TypedDocument<C> {
+    doc_path: DocumentPath,
+    revision: Revision,
+    attachments: std::collections::HashMap<AttachmentName, Attachment>,
+    content: C,
+}
+
+impl<C> TypedDocument<C> {
+    /// Returns the document's path.
+    pub fn path(&self) -> &DocumentPath {
+        &self.doc_path
+    }
+
+    /// Returns the document's revision.
+    pub fn revision(&self) -> &Revision {
+        &self.revision
+    }
+
+    /// Returns the document content.
+    pub fn content(&self) -> &C {
+        &self.content
+    }
+
+    /// Returns the document content for modification.
+    ///
+    /// Modifying the content doesn't update the document on the CouchDB
+    /// server. To update the document on the server, the application must use
+    /// the `UpdateDocument` action upon the modified `TypedDocument`.
+    ///
+    pub fn content_mut(&mut self) -> &mut C {
+        &mut self.content
+    }
+
+    /// Replaces the document content.
+    pub fn set_content(&mut self, new_content: C) {
+        self.content = new_content;
+    }
+
+    /// Consumes the document and returns its content.
+    pub fn into_content(self) -> C {
+        self.content
+    }
+
+    /// Returns the document's attachment of a given name, if the attachment
+    /// exists.
+    pub fn get_attachment<A>(&self, att_name: A) -> Option<&Attachment>
+    where
+        A: Into<AttachmentName>,
+    {
+        let att_name = att_name.into();
+        self.attachments.get(&att_name)
+    }
+
+    /// Creates or replaces the document's attachment of a given name.
+    ///
+    /// As with `Document::insert_attachment`, the attachment exists only in
+    /// memory until the application updates the document.
+    ///
+    pub fn insert_attachment<A>(&mut self, att_name: A, content_type: mime::Mime, content: Vec<u8>)
+    where
+        A: Into<AttachmentName>,
+    {
+        let att_name = att_name.into();
+        self.attachments.insert(
+            att_name,
+            AttachmentBuilder::new_unsaved(content_type, content).unwrap(),
+        );
+    }
+
+    /// Deletes the document's attachment of a given name, if the attachment
+    /// exists.
+    ///
+    /// As with `Document::remove_attachment`, the attachment continues to
+    /// exist on the CouchDB server until the application updates the document.
+    ///
+    pub fn remove_attachment<A>(&mut self, att_name: A)
+    where
+        A: Into<AttachmentName>,
+    {
+        let att_name = att_name.into();
+        self.attachments.remove(&att_name);
+    }
+
+    /// Returns an iterator to all attachments to the document.
+    pub fn attachments(&self) -> AttachmentIter {
+        AttachmentIter {
+            doc_path: &self.doc_path,
+            inner: self.attachments.iter(),
+        }
+    }
+}
+
+#[doc(hidden)]
+impl<C: serde::Serialize> serde::Serialize for TypedDocument<C> {
+    fn serialize<S>(&self, serializer: &mut S) -> Result<(), S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serialize_with_attachments(serde_json::to_value(&self.content), &self.attachments, serializer)
+    }
+}
+
+impl<C: serde::Serialize> UpdatableDocument for TypedDocument<C> {
+    fn path(&self) -> &DocumentPath {
+        &self.doc_path
+    }
+
+    fn revision(&self) -> &Revision {
+        &self.revision
+    }
+}
+
 #[cfg(test)]
 mod document_tests {
 
@@ -195,6 +433,10 @@ mod document_tests {
             doc_path: "/database/document_id".into_document_path().unwrap(),
             revision: "1-1234567890abcdef1234567890abcdef".parse().unwrap(),
             deleted: false,
+            conflicts: Vec::new(),
+            deleted_conflicts: Vec::new(),
+            revisions: Vec::new(),
+            revisions_info: Vec::new(),
             attachments: std::collections::HashMap::new(),
             content: content.clone(),
         };
@@ -213,6 +455,10 @@ mod document_tests {
             doc_path: "/database/document_id".into_document_path().unwrap(),
             revision: "1-1234567890abcdef1234567890abcdef".parse().unwrap(),
             deleted: true,
+            conflicts: Vec::new(),
+            deleted_conflicts: Vec::new(),
+            revisions: Vec::new(),
+            revisions_info: Vec::new(),
             attachments: std::collections::HashMap::new(),
             content: content.clone(),
         };
@@ -229,6 +475,10 @@ mod document_tests {
             doc_path: "/database/document_id".into_document_path().unwrap(),
             revision: "1-1234567890abcdef1234567890abcdef".parse().unwrap(),
             deleted: false,
+            conflicts: Vec::new(),
+            deleted_conflicts: Vec::new(),
+            revisions: Vec::new(),
+            revisions_info: Vec::new(),
             attachments: std::collections::HashMap::new(),
             content: serde_json::builder::ObjectBuilder::new().build(),
         };
@@ -246,6 +496,10 @@ mod document_tests {
             doc_path: "/database/document_id".into_document_path().unwrap(),
             revision: Revision::parse("42-1234567890abcdef1234567890abcdef").unwrap(),
             deleted: true, // This value should have no effect.
+            conflicts: Vec::new(),
+            deleted_conflicts: Vec::new(),
+            revisions: Vec::new(),
+            revisions_info: Vec::new(),
             attachments: std::collections::HashMap::new(),
             content: serde_json::builder::ObjectBuilder::new().build(),
         };
@@ -263,6 +517,10 @@ mod document_tests {
             doc_path: "/database/document_id".into_document_path().unwrap(),
             revision: Revision::parse("42-1234567890abcdef1234567890abcdef").unwrap(),
             deleted: true, // This value should have no effect.
+            conflicts: Vec::new(),
+            deleted_conflicts: Vec::new(),
+            revisions: Vec::new(),
+            revisions_info: Vec::new(),
             attachments: {
                 let mut m = std::collections::HashMap::new();
                 m.insert(
@@ -327,6 +585,10 @@ mod document_tests {
             doc_path: "/database/document_id".into_document_path().unwrap(),
             revision: Revision::parse("42-1234567890abcdef1234567890abcdef").unwrap(),
             deleted: false,
+            conflicts: Vec::new(),
+            deleted_conflicts: Vec::new(),
+            revisions: Vec::new(),
+            revisions_info: Vec::new(),
             attachments: {
                 let mut m = std::collections::HashMap::new();
                 m.insert(AttachmentName::from("attachment_1"), attachment_1.clone());
@@ -346,6 +608,10 @@ mod document_tests {
             doc_path: "/database/document_id".into_document_path().unwrap(),
             revision: Revision::parse("42-1234567890abcdef1234567890abcdef").unwrap(),
             deleted: false,
+            conflicts: Vec::new(),
+            deleted_conflicts: Vec::new(),
+            revisions: Vec::new(),
+            revisions_info: Vec::new(),
             attachments: std::collections::HashMap::new(),
             content: serde_json::builder::ObjectBuilder::new().build(),
         };
@@ -361,6 +627,10 @@ mod document_tests {
             doc_path: "/database/document_id".into_document_path().unwrap(),
             revision: Revision::parse("42-1234567890abcdef1234567890abcdef").unwrap(),
             deleted: false,
+            conflicts: Vec::new(),
+            deleted_conflicts: Vec::new(),
+            revisions: Vec::new(),
+            revisions_info: Vec::new(),
             attachments: std::collections::HashMap::new(),
             content: serde_json::builder::ObjectBuilder::new().build(),
         };
@@ -376,6 +646,10 @@ mod document_tests {
             doc_path: "/database/document_id".into_document_path().unwrap(),
             revision: Revision::parse("42-1234567890abcdef1234567890abcdef").unwrap(),
             deleted: false,
+            conflicts: Vec::new(),
+            deleted_conflicts: Vec::new(),
+            revisions: Vec::new(),
+            revisions_info: Vec::new(),
             attachments: {
                 let mut m = std::collections::HashMap::new();
                 m.insert(
@@ -401,6 +675,10 @@ mod document_tests {
             doc_path: "/database/document_id".into_document_path().unwrap(),
             revision: Revision::parse("42-1234567890abcdef1234567890abcdef").unwrap(),
             deleted: false,
+            conflicts: Vec::new(),
+            deleted_conflicts: Vec::new(),
+            revisions: Vec::new(),
+            revisions_info: Vec::new(),
             attachments: std::collections::HashMap::new(),
             content: serde_json::builder::ObjectBuilder::new().build(),
         };
@@ -416,6 +694,10 @@ mod document_tests {
             doc_path: "/database/document_id".into_document_path().unwrap(),
             revision: Revision::parse("42-1234567890abcdef1234567890abcdef").unwrap(),
             deleted: false,
+            conflicts: Vec::new(),
+            deleted_conflicts: Vec::new(),
+            revisions: Vec::new(),
+            revisions_info: Vec::new(),
             attachments: {
                 let mut m = std::collections::HashMap::new();
                 m.insert(
@@ -437,6 +719,10 @@ mod document_tests {
             doc_path: "/database/document_id".into_document_path().unwrap(),
             revision: Revision::parse("42-1234567890abcdef1234567890abcdef").unwrap(),
             deleted: false,
+            conflicts: Vec::new(),
+            deleted_conflicts: Vec::new(),
+            revisions: Vec::new(),
+            revisions_info: Vec::new(),
             attachments: {
                 let mut m = std::collections::HashMap::new();
                 m.insert(
@@ -454,6 +740,10 @@ mod document_tests {
             doc_path: "/database/document_id".into_document_path().unwrap(),
             revision: Revision::parse("42-1234567890abcdef1234567890abcdef").unwrap(),
             deleted: false,
+            conflicts: Vec::new(),
+            deleted_conflicts: Vec::new(),
+            revisions: Vec::new(),
+            revisions_info: Vec::new(),
             attachments: std::collections::HashMap::new(),
             content: serde_json::builder::ObjectBuilder::new().build(),
         };
@@ -468,6 +758,10 @@ mod document_tests {
             doc_path: "/database/document_id".into_document_path().unwrap(),
             revision: Revision::parse("42-1234567890abcdef1234567890abcdef").unwrap(),
             deleted: false,
+            conflicts: Vec::new(),
+            deleted_conflicts: Vec::new(),
+            revisions: Vec::new(),
+            revisions_info: Vec::new(),
             attachments: {
                 let mut m = std::collections::HashMap::new();
                 m.insert(
@@ -485,6 +779,10 @@ mod document_tests {
             doc_path: "/database/document_id".into_document_path().unwrap(),
             revision: Revision::parse("42-1234567890abcdef1234567890abcdef").unwrap(),
             deleted: false,
+            conflicts: Vec::new(),
+            deleted_conflicts: Vec::new(),
+            revisions: Vec::new(),
+            revisions_info: Vec::new(),
             attachments: {
                 let mut m = std::collections::HashMap::new();
                 m.insert(
@@ -519,6 +817,10 @@ mod document_tests {
             doc_path: "/database/document_id".into_document_path().unwrap(),
             revision: Revision::parse("42-1234567890abcdef1234567890abcdef").unwrap(),
             deleted: false,
+            conflicts: Vec::new(),
+            deleted_conflicts: Vec::new(),
+            revisions: Vec::new(),
+            revisions_info: Vec::new(),
             attachments: attachments.clone(),
             content: serde_json::builder::ObjectBuilder::new().build(),
         };
@@ -531,6 +833,80 @@ mod document_tests {
             .collect();
         assert_eq!(attachments, got);
     }
+
+    #[test]
+    fn into_typed_ok() {
+
+        let document = DocumentBuilder::new(
+            "/database/document_id",
+            Revision::parse("42-1234567890abcdef1234567890abcdef").unwrap(),
+        ).build_content(|x| x.insert("field_1", 17).insert("field_2", 18))
+            .unwrap();
+
+        let expected = {
+            let mut m = std::collections::BTreeMap::new();
+            m.insert(String::from("field_1"), 17);
+            m.insert(String::from("field_2"), 18);
+            m
+        };
+
+        let got = document.into_typed::<std::collections::BTreeMap<String, i64>>().unwrap();
+        assert_eq!(&"/database/document_id".into_document_path().unwrap(), got.path());
+        assert_eq!(&Revision::parse("42-1234567890abcdef1234567890abcdef").unwrap(), got.revision());
+        assert_eq!(&expected, got.content());
+    }
+
+    #[test]
+    fn into_typed_nok_decode_error() {
+
+        let document = DocumentBuilder::new(
+            "/database/document_id",
+            Revision::parse("42-1234567890abcdef1234567890abcdef").unwrap(),
+        ).build_content(|x| x.insert("field_1", "hello"))
+            .unwrap();
+
+        match document.into_typed::<std::collections::BTreeMap<String, i64>>() {
+            Err(Error::JsonDecode { .. }) => (),
+            x @ _ => unexpected_result!(x),
+        }
+    }
+
+    #[test]
+    fn typed_document_serialize_with_content_and_attachments() {
+
+        let mut document = DocumentBuilder::new(
+            "/database/document_id",
+            Revision::parse("42-1234567890abcdef1234567890abcdef").unwrap(),
+        ).build_content(|x| x.insert("field_1", 17))
+            .unwrap()
+            .into_typed::<std::collections::BTreeMap<String, i64>>()
+            .unwrap();
+
+        document.content_mut().insert(String::from("field_2"), 18);
+        document.insert_attachment(
+            "attachment_1",
+            mime!(Text / Html),
+            "<p>Yak yak yak</p>".to_string().into_bytes(),
+        );
+
+        let encoded = serde_json::to_string(&document).unwrap();
+
+        let expected = serde_json::builder::ObjectBuilder::new()
+            .insert("field_1", 17)
+            .insert("field_2", 18)
+            .insert_object("_attachments", |x| {
+                x.insert_object("attachment_1", |x| {
+                    x.insert("content_type", "text/html").insert(
+                        "data",
+                        base64::encode("<p>Yak yak yak</p>".as_bytes()),
+                    )
+                })
+            })
+            .build();
+
+        let got = serde_json::from_str(&encoded).unwrap();
+        assert_eq!(expected, got);
+    }
 }
 
 // JsonDecodableDocument is necessary because the Document type is not
@@ -541,6 +917,10 @@ pub struct JsonDecodableDocument {
     pub doc_id: DocumentId,
     pub revision: Revision,
     pub deleted: bool,
+    pub conflicts: Vec<Revision>,
+    pub deleted_conflicts: Vec<Revision>,
+    pub revisions: Vec<Revision>,
+    pub revisions_info: Vec<(Revision, RevisionStatus)>,
     pub attachments: std::collections::HashMap<AttachmentName, Attachment>,
     pub content: serde_json::Value,
 }
@@ -552,10 +932,14 @@ impl serde::Deserialize for JsonDecodableDocument {
     {
         enum Field {
             Attachments,
+            Conflicts,
             Content(String),
             Deleted,
+            DeletedConflicts,
             Id,
             Rev,
+            Revisions,
+            RevsInfo,
         }
 
         impl serde::Deserialize for Field {
@@ -574,9 +958,13 @@ impl serde::Deserialize for JsonDecodableDocument {
                     {
                         match value {
                             "_attachments" => Ok(Field::Attachments),
+                            "_conflicts" => Ok(Field::Conflicts),
                             "_deleted" => Ok(Field::Deleted),
+                            "_deleted_conflicts" => Ok(Field::DeletedConflicts),
                             "_id" => Ok(Field::Id),
                             "_rev" => Ok(Field::Rev),
+                            "_revisions" => Ok(Field::Revisions),
+                            "_revs_info" => Ok(Field::RevsInfo),
                             _ => Ok(Field::Content(value.to_string())),
                         }
                     }
@@ -596,9 +984,13 @@ impl serde::Deserialize for JsonDecodableDocument {
                 V: serde::de::MapVisitor,
             {
                 let mut attachments = None;
+                let mut conflicts = None;
                 let mut deleted = None;
+                let mut deleted_conflicts = None;
                 let mut id = None;
                 let mut revision = None;
+                let mut revisions = None;
+                let mut revs_info = None;
                 let mut content_builder = serde_json::builder::ObjectBuilder::new();
 
                 loop {
@@ -606,6 +998,9 @@ impl serde::Deserialize for JsonDecodableDocument {
                         Some(Field::Attachments) => {
                             attachments = Some(try!(visitor.visit_value()));
                         }
+                        Some(Field::Conflicts) => {
+                            conflicts = Some(try!(visitor.visit_value()));
+                        }
                         Some(Field::Content(name)) => {
                             let value = Some(try!(visitor.visit_value::<serde_json::Value>()));
                             content_builder = content_builder.insert(name, value);
@@ -613,12 +1008,27 @@ impl serde::Deserialize for JsonDecodableDocument {
                         Some(Field::Deleted) => {
                             deleted = Some(try!(visitor.visit_value()));
                         }
+                        Some(Field::DeletedConflicts) => {
+                            deleted_conflicts = Some(try!(visitor.visit_value()));
+                        }
                         Some(Field::Id) => {
                             id = Some(try!(visitor.visit_value()));
                         }
                         Some(Field::Rev) => {
                             revision = Some(try!(visitor.visit_value()));
                         }
+                        Some(Field::Revisions) => {
+                            let x: RevisionsJsonable = try!(visitor.visit_value());
+                            revisions = Some(try!(x.into_revisions().map_err(|e| {
+                                use serde::de::Error;
+                                use std::error::Error as StdError;
+                                V::Error::invalid_value(e.description())
+                            })));
+                        }
+                        Some(Field::RevsInfo) => {
+                            let x: Vec<RevisionInfoJsonable> = try!(visitor.visit_value());
+                            revs_info = Some(x.into_iter().map(|x| (x.rev, x.status)).collect());
+                        }
                         None => {
                             break;
                         }
@@ -637,17 +1047,233 @@ impl serde::Deserialize for JsonDecodableDocument {
                         None => try!(visitor.missing_field("_rev")),
                     },
                     deleted: deleted.unwrap_or(false),
+                    conflicts: conflicts.unwrap_or(Vec::new()),
+                    deleted_conflicts: deleted_conflicts.unwrap_or(Vec::new()),
+                    revisions: revisions.unwrap_or(Vec::new()),
+                    revisions_info: revs_info.unwrap_or(Vec::new()),
                     attachments: attachments.unwrap_or(std::collections::HashMap::new()),
                     content: content_builder.build(),
                 })
             }
         }
 
-        static FIELDS: &'static [&'static str] = &["_attachments", "_deleted", "_id", "_rev"];
+        static FIELDS: &'static [&'static str] = &[
+            "_attachments",
+            "_conflicts",
+            "_deleted",
+            "_deleted_conflicts",
+            "_id",
+            "_rev",
+            "_revisions",
+            "_revs_info",
+        ];
         deserializer.deserialize_struct("JsonDecodableDocument", FIELDS, Visitor)
     }
 }
 
+// RevisionsJsonable is an intermediate type for decoding a document's
+// `_revisions` field, which comprises the sequence number of the newest
+// revision and the digests of all revisions, newest first.
+struct RevisionsJsonable {
+    start: u64,
+    ids: Vec<String>,
+}
+
+impl RevisionsJsonable {
+    fn into_revisions(self) -> Result<Vec<Revision>, Error> {
+        let start = self.start;
+        self.ids
+            .into_iter()
+            .enumerate()
+            .map(|(i, id)| {
+                let sequence_number = try!(start.checked_sub(i as u64).ok_or(
+                    Error::UnexpectedResponse("The revision history is longer than its start"),
+                ));
+                Revision::parse(&format!("{}-{}", sequence_number, id))
+            })
+            .collect()
+    }
+}
+
+impl serde::Deserialize for RevisionsJsonable {
+    fn deserialize<D>(deserializer: &mut D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer,
+    {
+        enum Field {
+            Ids,
+            Ignored,
+            Start,
+        }
+
+        impl serde::Deserialize for Field {
+            fn deserialize<D>(deserializer: &mut D) -> Result<Field, D::Error>
+            where
+                D: serde::Deserializer,
+            {
+                struct Visitor;
+
+                impl serde::de::Visitor for Visitor {
+                    type Value = Field;
+
+                    fn visit_str<E>(&mut self, value: &str) -> Result<Field, E>
+                    where
+                        E: serde::de::Error,
+                    {
+                        match value {
+                            "ids" => Ok(Field::Ids),
+                            "start" => Ok(Field::Start),
+                            _ => Ok(Field::Ignored),
+                        }
+                    }
+                }
+
+                deserializer.deserialize(Visitor)
+            }
+        }
+
+        struct Visitor;
+
+        impl serde::de::Visitor for Visitor {
+            type Value = RevisionsJsonable;
+
+            fn visit_map<V>(&mut self, mut visitor: V) -> Result<Self::Value, V::Error>
+            where
+                V: serde::de::MapVisitor,
+            {
+                let mut ids = None;
+                let mut start = None;
+
+                loop {
+                    match try!(visitor.visit_key()) {
+                        Some(Field::Ids) => {
+                            ids = Some(try!(visitor.visit_value()));
+                        }
+                        Some(Field::Ignored) => {
+                            try!(visitor.visit_value::<serde_json::Value>());
+                        }
+                        Some(Field::Start) => {
+                            start = Some(try!(visitor.visit_value()));
+                        }
+                        None => {
+                            break;
+                        }
+                    }
+                }
+
+                try!(visitor.end());
+
+                Ok(RevisionsJsonable {
+                    start: match start {
+                        Some(x) => x,
+                        None => try!(visitor.missing_field("start")),
+                    },
+                    ids: match ids {
+                        Some(x) => x,
+                        None => try!(visitor.missing_field("ids")),
+                    },
+                })
+            }
+        }
+
+        static FIELDS: &'static [&'static str] = &["ids", "start"];
+        deserializer.deserialize_struct("RevisionsJsonable", FIELDS, Visitor)
+    }
+}
+
+// RevisionInfoJsonable is an intermediate type for decoding one item of a
+// document's `_revs_info` field.
+struct RevisionInfoJsonable {
+    rev: Revision,
+    status: RevisionStatus,
+}
+
+impl serde::Deserialize for RevisionInfoJsonable {
+    fn deserialize<D>(deserializer: &mut D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer,
+    {
+        enum Field {
+            Ignored,
+            Rev,
+            Status,
+        }
+
+        impl serde::Deserialize for Field {
+            fn deserialize<D>(deserializer: &mut D) -> Result<Field, D::Error>
+            where
+                D: serde::Deserializer,
+            {
+                struct Visitor;
+
+                impl serde::de::Visitor for Visitor {
+                    type Value = Field;
+
+                    fn visit_str<E>(&mut self, value: &str) -> Result<Field, E>
+                    where
+                        E: serde::de::Error,
+                    {
+                        match value {
+                            "rev" => Ok(Field::Rev),
+                            "status" => Ok(Field::Status),
+                            _ => Ok(Field::Ignored),
+                        }
+                    }
+                }
+
+                deserializer.deserialize(Visitor)
+            }
+        }
+
+        struct Visitor;
+
+        impl serde::de::Visitor for Visitor {
+            type Value = RevisionInfoJsonable;
+
+            fn visit_map<V>(&mut self, mut visitor: V) -> Result<Self::Value, V::Error>
+            where
+                V: serde::de::MapVisitor,
+            {
+                let mut rev = None;
+                let mut status = None;
+
+                loop {
+                    match try!(visitor.visit_key()) {
+                        Some(Field::Ignored) => {
+                            try!(visitor.visit_value::<serde_json::Value>());
+                        }
+                        Some(Field::Rev) => {
+                            rev = Some(try!(visitor.visit_value()));
+                        }
+                        Some(Field::Status) => {
+                            status = Some(try!(visitor.visit_value()));
+                        }
+                        None => {
+                            break;
+                        }
+                    }
+                }
+
+                try!(visitor.end());
+
+                Ok(RevisionInfoJsonable {
+                    rev: match rev {
+                        Some(x) => x,
+                        None => try!(visitor.missing_field("rev")),
+                    },
+                    status: match status {
+                        Some(x) => x,
+                        None => try!(visitor.missing_field("status")),
+                    },
+                })
+            }
+        }
+
+        static FIELDS: &'static [&'static str] = &["rev", "status"];
+        deserializer.deserialize_struct("RevisionInfoJsonable", FIELDS, Visitor)
+    }
+}
+
 #[derive(Debug, PartialEq)]
 pub struct WriteDocumentResponse {
     pub ok: bool,
@@ -755,6 +1381,10 @@ impl DocumentBuilder {
             doc_path: doc_path.into_document_path().unwrap().into(),
             revision: revision,
             deleted: false,
+            conflicts: Vec::new(),
+            deleted_conflicts: Vec::new(),
+            revisions: Vec::new(),
+            revisions_info: Vec::new(),
             attachments: std::collections::HashMap::new(),
             content: serde_json::builder::ObjectBuilder::new().build(),
         })
@@ -798,6 +1428,10 @@ mod tests {
             doc_id: DocumentId::from("document_id"),
             revision: "42-1234567890abcdef1234567890abcdef".parse().unwrap(),
             deleted: false,
+            conflicts: Vec::new(),
+            deleted_conflicts: Vec::new(),
+            revisions: Vec::new(),
+            revisions_info: Vec::new(),
             attachments: std::collections::HashMap::new(),
             content: serde_json::builder::ObjectBuilder::new().build(),
         };
@@ -819,6 +1453,10 @@ mod tests {
             doc_id: DocumentId::from("document_id"),
             revision: "42-1234567890abcdef1234567890abcdef".parse().unwrap(),
             deleted: true,
+            conflicts: Vec::new(),
+            deleted_conflicts: Vec::new(),
+            revisions: Vec::new(),
+            revisions_info: Vec::new(),
             attachments: std::collections::HashMap::new(),
             content: serde_json::builder::ObjectBuilder::new().build(),
         };
@@ -841,6 +1479,10 @@ mod tests {
             doc_id: DocumentId::from("document_id"),
             revision: "42-1234567890abcdef1234567890abcdef".parse().unwrap(),
             deleted: false,
+            conflicts: Vec::new(),
+            deleted_conflicts: Vec::new(),
+            revisions: Vec::new(),
+            revisions_info: Vec::new(),
             attachments: std::collections::HashMap::new(),
             content: serde_json::builder::ObjectBuilder::new()
                 .insert("field_1", 42)
@@ -867,6 +1509,10 @@ mod tests {
             doc_id: DocumentId::from("document_id"),
             revision: "42-1234567890abcdef1234567890abcdef".parse().unwrap(),
             deleted: false,
+            conflicts: Vec::new(),
+            deleted_conflicts: Vec::new(),
+            revisions: Vec::new(),
+            revisions_info: Vec::new(),
             attachments: {
                 let mut map = std::collections::HashMap::new();
                 map.insert(
@@ -902,6 +1548,106 @@ mod tests {
         assert_eq!(expected, got);
     }
 
+    #[test]
+    fn json_decodable_document_deserialize_ok_with_conflicts() {
+
+        let expected = JsonDecodableDocument {
+            doc_id: DocumentId::from("document_id"),
+            revision: "42-1234567890abcdef1234567890abcdef".parse().unwrap(),
+            deleted: false,
+            conflicts: vec!["42-abcdef1234567890abcdef1234567890".parse().unwrap()],
+            deleted_conflicts: vec!["41-00000000000000000000000000000000".parse().unwrap()],
+            revisions: Vec::new(),
+            revisions_info: Vec::new(),
+            attachments: std::collections::HashMap::new(),
+            content: serde_json::builder::ObjectBuilder::new().build(),
+        };
+
+        let source = serde_json::builder::ObjectBuilder::new()
+            .insert("_id", "document_id")
+            .insert("_rev", "42-1234567890abcdef1234567890abcdef")
+            .insert_array("_conflicts", |x| x.push("42-abcdef1234567890abcdef1234567890"))
+            .insert_array("_deleted_conflicts", |x| x.push("41-00000000000000000000000000000000"))
+            .build();
+
+        let source = serde_json::to_string(&source).unwrap();
+        let got = serde_json::from_str(&source).unwrap();
+        assert_eq!(expected, got);
+    }
+
+    #[test]
+    fn json_decodable_document_deserialize_ok_with_revisions() {
+
+        let expected = JsonDecodableDocument {
+            doc_id: DocumentId::from("document_id"),
+            revision: "3-1234567890abcdef1234567890abcdef".parse().unwrap(),
+            deleted: false,
+            conflicts: Vec::new(),
+            deleted_conflicts: Vec::new(),
+            revisions: vec![
+                "3-1234567890abcdef1234567890abcdef".parse().unwrap(),
+                "2-abcdef1234567890abcdef1234567890".parse().unwrap(),
+                "1-00000000000000000000000000000000".parse().unwrap(),
+            ],
+            revisions_info: vec![
+                (
+                    "3-1234567890abcdef1234567890abcdef".parse().unwrap(),
+                    RevisionStatus::Available,
+                ),
+                (
+                    "2-abcdef1234567890abcdef1234567890".parse().unwrap(),
+                    RevisionStatus::Missing,
+                ),
+            ],
+            attachments: std::collections::HashMap::new(),
+            content: serde_json::builder::ObjectBuilder::new().build(),
+        };
+
+        let source = serde_json::builder::ObjectBuilder::new()
+            .insert("_id", "document_id")
+            .insert("_rev", "3-1234567890abcdef1234567890abcdef")
+            .insert_object("_revisions", |x| {
+                x.insert("start", 3).insert_array("ids", |x| {
+                    x.push("1234567890abcdef1234567890abcdef")
+                        .push("abcdef1234567890abcdef1234567890")
+                        .push("00000000000000000000000000000000")
+                })
+            })
+            .insert_array("_revs_info", |x| {
+                x.push_object(|x| {
+                    x.insert("rev", "3-1234567890abcdef1234567890abcdef")
+                        .insert("status", "available")
+                }).push_object(|x| {
+                        x.insert("rev", "2-abcdef1234567890abcdef1234567890")
+                            .insert("status", "missing")
+                    })
+            })
+            .build();
+
+        let source = serde_json::to_string(&source).unwrap();
+        let got = serde_json::from_str(&source).unwrap();
+        assert_eq!(expected, got);
+    }
+
+    #[test]
+    fn json_decodable_document_deserialize_nok_revisions_longer_than_start() {
+
+        let source = serde_json::builder::ObjectBuilder::new()
+            .insert("_id", "document_id")
+            .insert("_rev", "1-1234567890abcdef1234567890abcdef")
+            .insert_object("_revisions", |x| {
+                x.insert("start", 1).insert_array("ids", |x| {
+                    x.push("1234567890abcdef1234567890abcdef")
+                        .push("abcdef1234567890abcdef1234567890")
+                })
+            })
+            .build();
+
+        let source = serde_json::to_string(&source).unwrap();
+        let got = serde_json::from_str::<JsonDecodableDocument>(&source);
+        expect_json_error_invalid_value!(got);
+    }
+
     #[test]
     fn json_decodable_document_deserialize_nok_missing_id() {
 
@@ -1,4 +1,4 @@
-use {hyper, mime, serde, serde_json, std, url, uuid};
+use {DocumentPath, hyper, mime, serde, serde_json, std, url, uuid};
 use transport::{JsonResponse, StatusCode};
 
 /// Contains information for an error originating from or propagated by Chill.
@@ -17,6 +17,9 @@ pub enum Error {
     /// the latest revision for the document.
     DocumentConflict(ErrorResponse),
 
+    #[doc(hidden)]
+    DocumentExists,
+
     #[doc(hidden)]
     DocumentIsDeleted,
 
@@ -32,6 +35,9 @@ pub enum Error {
     #[doc(hidden)]
     JsonEncode { cause: serde_json::Error },
 
+    #[doc(hidden)]
+    KeyPrefixNotArrayOrString,
+
     #[doc(hidden)]
     Mock { extra_description: String },
 
@@ -45,6 +51,9 @@ pub enum Error {
     #[doc(hidden)]
     ResponseNotJson(Option<mime::Mime>),
 
+    #[doc(hidden)]
+    ResolvedRevisionNotLeaf,
+
     #[doc(hidden)]
     RevisionParse { kind: RevisionParseErrorKind },
 
@@ -54,6 +63,9 @@ pub enum Error {
         error_response: Option<ErrorResponse>,
     },
 
+    #[doc(hidden)]
+    ServerVersionParse(String),
+
     #[doc(hidden)]
     Transport { kind: TransportErrorKind },
 
@@ -68,6 +80,16 @@ pub enum Error {
 
     #[doc(hidden)]
     UrlParse { cause: url::ParseError },
+
+    /// A row in a view response could not be decoded into the application's
+    /// key, value, or document type.
+    ///
+    /// The `doc_path` field identifies the row's document, if the row has one.
+    ///
+    ViewRowDecode {
+        doc_path: Option<DocumentPath>,
+        cause: serde_json::Error,
+    },
 }
 
 impl Error {
@@ -119,15 +141,18 @@ impl std::error::Error for Error {
             &ChannelReceive { description, .. } => description,
             &DatabaseExists(..) => "The database already exists",
             &DocumentConflict(..) => "A conflicting document with the same id exists",
+            &DocumentExists => "The document already exists",
             &DocumentIsDeleted => "The document is deleted",
             &Io { description, .. } => description,
             &JsonDecode { .. } => "An error occurred while decoding JSON",
             &JsonEncode { .. } => "An error occurred while encoding JSON",
+            &KeyPrefixNotArrayOrString => "The view key prefix is neither an array nor a string",
             &Mock { .. } => "A error occurred while test-mocking",
             &NotFound(..) => "The resource cannot be found",
             &PathParse(..) => "The path is badly formatted",
             &ResponseNotJson(Some(..)) => "The response has non-JSON content",
             &ResponseNotJson(None) => "The response content has no type",
+            &ResolvedRevisionNotLeaf => "The conflict resolver kept a revision that is not a leaf",
             &RevisionParse { .. } => "The revision is badly formatted",
             &ServerResponse { ref status_code, .. } => {
                 match status_code.class() {
@@ -136,11 +161,13 @@ impl std::error::Error for Error {
                     _ => "The CouchDB server responded with an unexpected status",
                 }
             }
+            &ServerVersionParse(..) => "The server version is badly formatted",
             &Transport { .. } => "An HTTP transport error occurred",
             &Unauthorized(..) => "The CouchDB client has insufficient privilege",
             &UnexpectedResponse(..) => "The CouchDB server responded unexpectedly",
             &UrlNotSchemeRelative => "The URL is not scheme relative",
             &UrlParse { .. } => "The URL is badly formatted",
+            &ViewRowDecode { .. } => "A view row could not be decoded",
         }
     }
 
@@ -150,21 +177,26 @@ impl std::error::Error for Error {
             &ChannelReceive { ref cause, .. } => Some(cause),
             &DatabaseExists(..) => None,
             &DocumentConflict(..) => None,
+            &DocumentExists => None,
             &DocumentIsDeleted => None,
             &Io { ref cause, .. } => Some(cause),
             &JsonDecode { ref cause } => Some(cause),
             &JsonEncode { ref cause } => Some(cause),
+            &KeyPrefixNotArrayOrString => None,
             &Mock { .. } => None,
             &NotFound(..) => None,
             &PathParse(ref kind) => kind.cause(),
             &ResponseNotJson(..) => None,
+            &ResolvedRevisionNotLeaf => None,
             &RevisionParse { ref kind } => kind.cause(),
             &ServerResponse { .. } => None,
+            &ServerVersionParse(..) => None,
             &Transport { ref kind } => kind.cause(),
             &Unauthorized(..) => None,
             &UnexpectedResponse(..) => None,
             &UrlNotSchemeRelative => None,
             &UrlParse { ref cause } => Some(cause),
+            &ViewRowDecode { ref cause, .. } => Some(cause),
         }
     }
 }
@@ -180,6 +212,7 @@ impl std::fmt::Display for Error {
             } => write!(f, "{}: {}", description, cause),
             &DatabaseExists(ref error_response) => write!(f, "{}: {}", description, error_response),
             &DocumentConflict(ref error_response) => write!(f, "{}: {}", description, error_response),
+            &DocumentExists => write!(f, "{}", description),
             &DocumentIsDeleted => write!(f, "{}", description),
             &Io {
                 ref cause,
@@ -187,11 +220,13 @@ impl std::fmt::Display for Error {
             } => write!(f, "{}: {}", description, cause),
             &JsonDecode { ref cause } => write!(f, "{}: {}", description, cause),
             &JsonEncode { ref cause } => write!(f, "{}: {}", description, cause),
+            &KeyPrefixNotArrayOrString => write!(f, "{}", description),
             &Mock { ref extra_description } => write!(f, "{}: {}", description, extra_description),
             &NotFound(ref error_response) => write!(f, "{}: {}", description, error_response),
             &PathParse(ref kind) => write!(f, "{}: {}", description, kind),
             &ResponseNotJson(Some(ref content_type)) => write!(f, "{}: Content type is {}", description, content_type),
             &ResponseNotJson(None) => write!(f, "{}", description),
+            &ResolvedRevisionNotLeaf => write!(f, "{}", description),
             &RevisionParse { ref kind } => write!(f, "{}: {}", description, kind),
             &ServerResponse {
                 ref status_code,
@@ -207,11 +242,20 @@ impl std::fmt::Display for Error {
                 }
                 Ok(())
             }
+            &ServerVersionParse(ref version) => write!(f, "{}: {:?}", description, version),
             &Transport { ref kind } => write!(f, "{}: {}", description, kind),
             &Unauthorized(ref error_response) => write!(f, "{}: {}", description, error_response),
             &UnexpectedResponse(sub_description) => write!(f, "{}: {}", description, sub_description),
             &UrlNotSchemeRelative => write!(f, "{}", description),
             &UrlParse { ref cause } => write!(f, "{}: {}", description, cause),
+            &ViewRowDecode {
+                doc_path: Some(ref doc_path),
+                ref cause,
+            } => write!(f, "{} (document {}): {}", description, doc_path, cause),
+            &ViewRowDecode {
+                doc_path: None,
+                ref cause,
+            } => write!(f, "{}: {}", description, cause),
         }
     }
 }
@@ -250,6 +294,7 @@ impl std::fmt::Display for PathParseErrorKind {
 pub enum RevisionParseErrorKind {
     DigestNotAllHex,
     DigestParse(uuid::ParseError),
+    NonzeroLocalPrefix,
     NumberParse(std::num::ParseIntError),
     TooFewParts,
     ZeroSequenceNumber,
@@ -261,6 +306,7 @@ impl RevisionParseErrorKind {
         match self {
             &DigestNotAllHex => None,
             &DigestParse(ref cause) => Some(cause),
+            &NonzeroLocalPrefix => None,
             &NumberParse(ref cause) => Some(cause),
             &TooFewParts => None,
             &ZeroSequenceNumber => None,
@@ -279,6 +325,7 @@ impl std::fmt::Display for RevisionParseErrorKind {
                 )
             }
             &DigestParse(ref cause) => write!(f, "The digest part is invalid: {}", cause),
+            &NonzeroLocalPrefix => write!(f, "The local revision does not begin with '0-'"),
             &NumberParse(ref cause) => write!(f, "The number part is invalid: {}", cause),
             &TooFewParts => write!(f, "Too few parts, missing number part and/or digest part"),
             &ZeroSequenceNumber => write!(f, "The number part is zero"),
@@ -0,0 +1,268 @@
+use {Error, ErrorResponse, mime, serde, serde_json, std};
+use transport::{RawResponse, StatusCode, StreamingResponse};
+
+/// Response generated by a design document function, such as a show function.
+///
+/// A `FunctionResponse` holds the HTTP status code, content type, and body
+/// exactly as returned by the server. Because design document functions may
+/// return content of any type—e.g., HTML, CSV, or JSON—the body is held as
+/// raw bytes. The `decode_json` method decodes a JSON body.
+///
+#[derive(Clone, Debug, PartialEq)]
+pub struct FunctionResponse {
+    status_code: u16,
+    content_type: Option<mime::Mime>,
+    content: Vec<u8>,
+}
+
+impl FunctionResponse {
+    // CouchDB reports a missing design document or function, an
+    // authentication failure, a document conflict, and an exception thrown by
+    // the function as a JSON error object. Any other response is the
+    // function's own output.
+    #[doc(hidden)]
+    pub fn from_raw_response(response: RawResponse) -> Result<Self, Error> {
+
+        match response.status_code() {
+            StatusCode::Conflict |
+            StatusCode::NotFound |
+            StatusCode::Unauthorized |
+            StatusCode::InternalServerError => {
+                if let Some(json_response) = response.to_json_response() {
+                    if json_response.decode_content::<ErrorResponse>().is_ok() {
+                        return Err(match json_response.status_code() {
+                            StatusCode::Conflict => Error::document_conflict(&json_response),
+                            StatusCode::NotFound => Error::not_found(&json_response),
+                            StatusCode::Unauthorized => Error::unauthorized(&json_response),
+                            _ => Error::server_response(&json_response),
+                        });
+                    }
+                }
+            }
+            _ => {}
+        }
+
+        Ok(FunctionResponse {
+            status_code: response.status_code().to_u16(),
+            content_type: response.headers().content_type(),
+            content: response.into_content(),
+        })
+    }
+
+    /// Returns the HTTP status code of the response.
+    pub fn status_code(&self) -> u16 {
+        self.status_code
+    }
+
+    /// Returns the content type of the response, if available.
+    pub fn content_type(&self) -> Option<&mime::Mime> {
+        self.content_type.as_ref()
+    }
+
+    /// Borrows the response body.
+    pub fn content(&self) -> &[u8] {
+        &self.content
+    }
+
+    /// Converts the response into its body.
+    pub fn into_content(self) -> Vec<u8> {
+        self.content
+    }
+
+    /// Decodes the response body as JSON.
+    ///
+    /// The `decode_json` method returns an error if the response's content
+    /// type is not JSON.
+    ///
+    pub fn decode_json<T: serde::Deserialize>(&self) -> Result<T, Error> {
+
+        use mime::{Mime, SubLevel, TopLevel};
+
+        match self.content_type {
+            Some(Mime(TopLevel::Application, SubLevel::Json, _)) => (),
+            ref x @ _ => return Err(Error::ResponseNotJson(x.clone())),
+        }
+
+        serde_json::from_slice(&self.content).map_err(|e| Error::JsonDecode { cause: e })
+    }
+}
+
+/// Streaming response generated by a design document function, such as a list
+/// function.
+///
+/// A `FunctionStream` is like a `FunctionResponse` except that the body is read
+/// incrementally, via the `std::io::Read` trait, as the server sends it. This
+/// suits large responses, such as a CSV export of an entire view.
+///
+pub struct FunctionStream {
+    status_code: u16,
+    content_type: Option<mime::Mime>,
+    content: Box<std::io::Read>,
+}
+
+impl FunctionStream {
+    // As with FunctionResponse, some error responses come from CouchDB and not
+    // the function. Such responses are small, so we read them in full.
+    #[doc(hidden)]
+    pub fn from_streaming_response(response: StreamingResponse) -> Result<Self, Error> {
+
+        use mime::{Mime, SubLevel, TopLevel};
+
+        let maybe_error = match (response.status_code(), response.headers().content_type()) {
+            (StatusCode::NotFound, Some(Mime(TopLevel::Application, SubLevel::Json, _))) |
+            (StatusCode::Unauthorized, Some(Mime(TopLevel::Application, SubLevel::Json, _))) |
+            (StatusCode::InternalServerError, Some(Mime(TopLevel::Application, SubLevel::Json, _))) => true,
+            _ => false,
+        };
+
+        if maybe_error {
+            let response = try!(FunctionResponse::from_raw_response(try!(response.into_raw_response())));
+            return Ok(FunctionStream {
+                status_code: response.status_code,
+                content_type: response.content_type,
+                content: Box::new(std::io::Cursor::new(response.content)),
+            });
+        }
+
+        Ok(FunctionStream {
+            status_code: response.status_code().to_u16(),
+            content_type: response.headers().content_type(),
+            content: response.into_content(),
+        })
+    }
+
+    /// Returns the HTTP status code of the response.
+    pub fn status_code(&self) -> u16 {
+        self.status_code
+    }
+
+    /// Returns the content type of the response, if available.
+    pub fn content_type(&self) -> Option<&mime::Mime> {
+        self.content_type.as_ref()
+    }
+}
+
+impl std::fmt::Debug for FunctionStream {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> Result<(), std::fmt::Error> {
+        f.debug_struct("FunctionStream")
+            .field("status_code", &self.status_code)
+            .field("content_type", &self.content_type)
+            .finish()
+    }
+}
+
+impl std::io::Read for FunctionStream {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        self.content.read(buf)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use {Error, serde_json};
+    use std::io::Read;
+    use transport::{RawResponseBuilder, StatusCode};
+
+    #[test]
+    fn from_raw_response_ok_html() {
+        let response = RawResponseBuilder::new(StatusCode::Ok)
+            .with_content(mime!(Text / Html), "<p>Babe Ruth</p>")
+            .unwrap();
+        let got = FunctionResponse::from_raw_response(response).unwrap();
+        assert_eq!(200, got.status_code());
+        assert_eq!(Some(&mime!(Text / Html)), got.content_type());
+        assert_eq!(b"<p>Babe Ruth</p>", got.content());
+    }
+
+    #[test]
+    fn from_raw_response_ok_custom_not_found() {
+        let response = RawResponseBuilder::new(StatusCode::NotFound)
+            .with_content(mime!(Text / Plain), "No such player")
+            .unwrap();
+        let got = FunctionResponse::from_raw_response(response).unwrap();
+        assert_eq!(404, got.status_code());
+        assert_eq!(b"No such player", got.content());
+    }
+
+    #[test]
+    fn from_raw_response_nok_not_found() {
+        let response = RawResponseBuilder::new(StatusCode::NotFound)
+            .with_content(
+                mime!(Application / Json),
+                r#"{"error":"not_found","reason":"missing show function player on design doc _design/stats"}"#,
+            )
+            .unwrap();
+        match FunctionResponse::from_raw_response(response) {
+            Err(Error::NotFound(ref error_response)) if error_response.error() == "not_found" => (),
+            x @ _ => unexpected_result!(x),
+        }
+    }
+
+    #[test]
+    fn from_raw_response_nok_unauthorized() {
+        let response = RawResponseBuilder::new(StatusCode::Unauthorized)
+            .with_content(
+                mime!(Application / Json),
+                r#"{"error":"unauthorized","reason":"Authentication required."}"#,
+            )
+            .unwrap();
+        match FunctionResponse::from_raw_response(response) {
+            Err(Error::Unauthorized(ref error_response)) if error_response.error() == "unauthorized" => (),
+            x @ _ => unexpected_result!(x),
+        }
+    }
+
+    #[test]
+    fn decode_json_ok() {
+        let response = RawResponseBuilder::new(StatusCode::Ok)
+            .with_content(mime!(Application / Json), r#"{"name":"Babe Ruth"}"#)
+            .unwrap();
+        let response = FunctionResponse::from_raw_response(response).unwrap();
+        let expected = serde_json::builder::ObjectBuilder::new()
+            .insert("name", "Babe Ruth")
+            .build();
+        let got: serde_json::Value = response.decode_json().unwrap();
+        assert_eq!(expected, got);
+    }
+
+    #[test]
+    fn decode_json_nok_not_json() {
+        let response = RawResponseBuilder::new(StatusCode::Ok)
+            .with_content(mime!(Text / Html), "<p>Babe Ruth</p>")
+            .unwrap();
+        let response = FunctionResponse::from_raw_response(response).unwrap();
+        match response.decode_json::<serde_json::Value>() {
+            Err(Error::ResponseNotJson(Some(ref x))) if *x == mime!(Text / Html) => (),
+            x @ _ => unexpected_result!(x),
+        }
+    }
+
+    #[test]
+    fn function_stream_ok_csv() {
+        let response = RawResponseBuilder::new(StatusCode::Ok)
+            .with_content(mime!(Text / ("csv")), "Babe Ruth,714\nHank Aaron,755\n")
+            .unwrap_streaming();
+        let mut got = FunctionStream::from_streaming_response(response).unwrap();
+        assert_eq!(200, got.status_code());
+        assert_eq!(Some(&mime!(Text / ("csv"))), got.content_type());
+        let mut content = String::new();
+        got.read_to_string(&mut content).unwrap();
+        assert_eq!("Babe Ruth,714\nHank Aaron,755\n", content);
+    }
+
+    #[test]
+    fn function_stream_nok_not_found() {
+        let response = RawResponseBuilder::new(StatusCode::NotFound)
+            .with_content(
+                mime!(Application / Json),
+                r#"{"error":"not_found","reason":"missing list function csv on design doc _design/stats"}"#,
+            )
+            .unwrap_streaming();
+        match FunctionStream::from_streaming_response(response) {
+            Err(Error::NotFound(ref error_response)) if error_response.error() == "not_found" => (),
+            x @ _ => unexpected_result!(x),
+        }
+    }
+}
@@ -0,0 +1,259 @@
+use {DocumentId, NormalDocumentName, rand, std, uuid};
+
+/// Describes a type that generates document ids locally, without a round trip
+/// to the CouchDB server.
+///
+/// A `DocumentIdGenerator` may be plugged into the `CreateDocument` action,
+/// via the `CreateDocument::with_document_id_generator` method, so that each
+/// new document gets an id from the generator unless the application sets the
+/// id explicitly. With a document id, the action creates the document via
+/// `PUT` instead of `POST`. The action takes the id when it's built, and the
+/// application may read it via `CreateDocument::document_id` and, after a
+/// failure, safely retry with the same id via
+/// `CreateDocument::with_document_id`.
+///
+/// Chill provides three generators:
+///
+/// * `RandomIdGenerator` generates random (version 4) UUIDs.
+/// * `SequentialIdGenerator` generates ids like CouchDB's `sequential` UUID
+///   algorithm—i.e., a random prefix followed by an increasing suffix.
+/// * `UlidIdGenerator` generates time-sortable ids like
+///   [ULIDs](https://github.com/ulid/spec).
+///
+pub trait DocumentIdGenerator {
+    /// Generates the name of a new normal document.
+    fn next_name(&self) -> NormalDocumentName;
+
+    /// Generates the id of a new normal document.
+    fn next_id(&self) -> DocumentId {
+        DocumentId::from(self.next_name())
+    }
+}
+
+/// Generates document ids that are random (version 4) UUIDs.
+///
+/// Each id comprises 32 lowercase hexadecimal digits—e.g.,
+/// `4b3e4f2c0cd04a0c9dbe6b5a9d0e7f21`. Ids are unordered.
+///
+#[derive(Debug, Default)]
+pub struct RandomIdGenerator;
+
+impl RandomIdGenerator {
+    /// Constructs a generator of random ids.
+    pub fn new() -> Self {
+        RandomIdGenerator
+    }
+}
+
+impl DocumentIdGenerator for RandomIdGenerator {
+    fn next_name(&self) -> NormalDocumentName {
+        NormalDocumentName::from(uuid::Uuid::new_v4().simple().to_string())
+    }
+}
+
+const SEQUENTIAL_PREFIX_LEN: usize = 26;
+const SEQUENTIAL_SUFFIX_MAX: u32 = 0xfff000;
+const SEQUENTIAL_INCREMENT_MAX: u32 = 0xffe;
+
+/// Generates document ids like CouchDB's `sequential` UUID algorithm.
+///
+/// Each id comprises 32 lowercase hexadecimal digits: a random 26-digit
+/// prefix followed by a 6-digit suffix. The suffix increases by a random
+/// amount with each id, and when the suffix would overflow, the generator
+/// starts over with a new random prefix. Thus, consecutive ids share a prefix
+/// and sort in the order generated, which improves the server's write
+/// performance over random ids.
+///
+#[derive(Debug)]
+pub struct SequentialIdGenerator {
+    state: std::sync::Mutex<(String, u32)>,
+}
+
+impl SequentialIdGenerator {
+    /// Constructs a generator of sequential ids, starting with a random prefix.
+    pub fn new() -> Self {
+        SequentialIdGenerator { state: std::sync::Mutex::new(SequentialIdGenerator::new_state()) }
+    }
+
+    fn new_state() -> (String, u32) {
+        use rand::Rng;
+        let mut rng = rand::thread_rng();
+        let prefix = uuid::Uuid::new_v4().simple().to_string()[..SEQUENTIAL_PREFIX_LEN].to_string();
+        (prefix, rng.gen_range(0, SEQUENTIAL_INCREMENT_MAX))
+    }
+}
+
+impl Default for SequentialIdGenerator {
+    fn default() -> Self {
+        SequentialIdGenerator::new()
+    }
+}
+
+impl DocumentIdGenerator for SequentialIdGenerator {
+    fn next_name(&self) -> NormalDocumentName {
+
+        use rand::Rng;
+
+        let mut state = self.state.lock().unwrap();
+
+        let increment = rand::thread_rng().gen_range(1, SEQUENTIAL_INCREMENT_MAX + 1);
+        state.1 += increment;
+        if state.1 >= SEQUENTIAL_SUFFIX_MAX {
+            *state = SequentialIdGenerator::new_state();
+        }
+
+        NormalDocumentName::from(format!("{}{:06x}", state.0, state.1))
+    }
+}
+
+const ULID_ALPHABET: &'static [u8; 32] = b"0123456789ABCDEFGHJKMNPQRSTVWXYZ";
+const ULID_RANDOMNESS_MASK: u128 = (1 << 80) - 1;
+
+/// Generates time-sortable document ids like
+/// [ULIDs](https://github.com/ulid/spec).
+///
+/// Each id comprises 26 characters of Crockford's base 32: a 10-character
+/// timestamp, in milliseconds since the Unix epoch, followed by 16 characters
+/// of randomness—e.g., `01ARYZ6S41TSV4RRFFQ69G5FAV`. Ids sort in the order
+/// generated, even within the same millisecond, because the generator
+/// increments the randomness of the previous id instead of choosing new
+/// randomness when the timestamp doesn't advance.
+///
+#[derive(Debug, Default)]
+pub struct UlidIdGenerator {
+    state: std::sync::Mutex<(u64, u128)>,
+}
+
+impl UlidIdGenerator {
+    /// Constructs a generator of ULID-like ids.
+    pub fn new() -> Self {
+        UlidIdGenerator { state: std::sync::Mutex::new((0, 0)) }
+    }
+
+    fn now_ms() -> u64 {
+        let since_epoch = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap_or(std::time::Duration::from_secs(0));
+        since_epoch.as_secs() * 1000 + since_epoch.subsec_nanos() as u64 / 1_000_000
+    }
+
+    fn next_with_time(&self, now_ms: u64) -> String {
+
+        let mut state = self.state.lock().unwrap();
+
+        let (ms, randomness) = if now_ms > state.0 {
+            let randomness = ((rand::random::<u64>() as u128) << 16) | rand::random::<u16>() as u128;
+            (now_ms, randomness)
+        } else if state.1 < ULID_RANDOMNESS_MASK {
+            (state.0, state.1 + 1)
+        } else {
+            (state.0 + 1, 0)
+        };
+
+        *state = (ms, randomness);
+        encode_ulid(ms, randomness)
+    }
+}
+
+impl DocumentIdGenerator for UlidIdGenerator {
+    fn next_name(&self) -> NormalDocumentName {
+        NormalDocumentName::from(self.next_with_time(UlidIdGenerator::now_ms()))
+    }
+}
+
+// A ULID is 128 bits--a 48-bit timestamp followed by 80 bits of
+// randomness--encoded as 26 base-32 characters, most significant first. The
+// 26 characters hold 130 bits, so the two most significant bits are zero.
+fn encode_ulid(ms: u64, randomness: u128) -> String {
+    let value = ((ms as u128) << 80) | (randomness & ULID_RANDOMNESS_MASK);
+    (0..26)
+        .map(|i| {
+            let shift = 125 - 5 * i;
+            ULID_ALPHABET[((value >> shift) & 0x1f) as usize] as char
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use DocumentId;
+
+    fn is_lower_hex(s: &str) -> bool {
+        s.chars().all(|c| c.is_digit(16) && !c.is_uppercase())
+    }
+
+    #[test]
+    fn random_id_generator_format() {
+        let generator = RandomIdGenerator::new();
+        let name = generator.next_name();
+        assert_eq!(32, name.as_ref().len());
+        assert!(is_lower_hex(name.as_ref()));
+        assert!(generator.next_name() != name);
+    }
+
+    #[test]
+    fn random_id_generator_next_id_is_normal() {
+        match RandomIdGenerator::new().next_id() {
+            DocumentId::Normal(..) => (),
+            x @ _ => panic!("Got unexpected document id {:?}", x),
+        }
+    }
+
+    #[test]
+    fn sequential_id_generator_format_and_order() {
+
+        let generator = SequentialIdGenerator::new();
+        let names = (0..1000).map(|_| generator.next_name()).collect::<Vec<_>>();
+
+        for name in &names {
+            assert_eq!(32, name.as_ref().len());
+            assert!(is_lower_hex(name.as_ref()));
+        }
+
+        // Ids are increasing while the prefix stays the same.
+        for pair in names.windows(2) {
+            if pair[0].as_ref()[..26] == pair[1].as_ref()[..26] {
+                assert!(pair[0] < pair[1]);
+            }
+        }
+    }
+
+    #[test]
+    fn sequential_id_generator_starts_new_prefix_on_overflow() {
+        let generator = SequentialIdGenerator::new();
+        let first = generator.next_name();
+        generator.state.lock().unwrap().1 = SEQUENTIAL_SUFFIX_MAX - 1;
+        let second = generator.next_name();
+        assert!(first.as_ref()[..26] != second.as_ref()[..26]);
+    }
+
+    #[test]
+    fn encode_ulid_ok() {
+        assert_eq!("00000000000000000000000000", encode_ulid(0, 0));
+        assert_eq!("0000000000000000000000000Z", encode_ulid(0, 31));
+        assert_eq!("01ARYZ6S41", &encode_ulid(1469918176385, 0)[..10]);
+        assert_eq!("7ZZZZZZZZZZZZZZZZZZZZZZZZZ", encode_ulid((1 << 48) - 1, ULID_RANDOMNESS_MASK));
+    }
+
+    #[test]
+    fn ulid_id_generator_monotonic_within_same_millisecond() {
+        let generator = UlidIdGenerator::new();
+        let first = generator.next_with_time(1469918176385);
+        let second = generator.next_with_time(1469918176385);
+        let third = generator.next_with_time(1469918176384);
+        assert_eq!("01ARYZ6S41", &first[..10]);
+        assert!(first < second);
+        assert!(second < third);
+    }
+
+    #[test]
+    fn ulid_id_generator_sortable_by_time() {
+        let generator = UlidIdGenerator::new();
+        let first = generator.next_with_time(1469918176385);
+        let second = generator.next_with_time(1469918176386);
+        assert!(first < second);
+        assert_eq!(26, generator.next_name().as_ref().len());
+    }
+}
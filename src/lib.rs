@@ -1,7 +1,9 @@
 extern crate base64;
 extern crate hyper;
+extern crate md5;
 #[macro_use(mime, __mime__ident_or_ext)]
 extern crate mime;
+extern crate rand;
 extern crate regex;
 extern crate serde;
 extern crate serde_json;
@@ -15,24 +17,40 @@ mod test_macro;
 
 mod attachment;
 mod client;
+mod conflict;
 mod design;
 mod document;
 mod error;
+mod function;
+mod id_generator;
+mod local_document;
 mod revision;
+mod server;
+mod term;
 mod transport;
+mod uuids;
 mod view;
 
 pub mod action;
+pub mod collation;
+pub mod mango;
 pub mod path;
 pub mod testing;
 
-pub use attachment::{Attachment, SavedAttachment, UnsavedAttachment};
+pub use attachment::{Attachment, AttachmentInfo, SavedAttachment, UnsavedAttachment};
 pub use client::{Client, IntoUrl};
-pub use design::{Design, DesignBuilder, ViewFunction};
-pub use document::Document;
+pub use conflict::{ConflictResolver, HighestSequenceWins, MergeResolver, Resolution};
+pub use design::{Deployment, Design, DesignBuilder, ViewFunction};
+pub use document::{Document, TypedDocument, UpdatableDocument};
 pub use error::{Error, ErrorResponse};
+pub use function::{FunctionResponse, FunctionStream};
+pub use id_generator::{DocumentIdGenerator, RandomIdGenerator, SequentialIdGenerator, UlidIdGenerator};
+pub use local_document::LocalDocument;
 pub use path::{AttachmentName, AttachmentPath, DatabaseName, DatabasePath, DesignDocumentName, DesignDocumentPath,
-               DocumentId, DocumentPath, IntoAttachmentPath, IntoDatabasePath, IntoDesignDocumentPath,
-               IntoDocumentPath, IntoViewPath, LocalDocumentName, NormalDocumentName, ViewName, ViewPath};
-pub use revision::Revision;
-pub use view::{ViewResponse, ViewRow};
+               DocumentId, DocumentPath, FilterName, IntoAttachmentPath, IntoDatabasePath, IntoDesignDocumentPath,
+               IntoDocumentPath, IntoViewPath, ListName, LocalDocumentName, NormalDocumentName, ShowName, UpdateName,
+               ViewName, ViewPath};
+pub use revision::{LocalRevision, Revision, RevisionStatus, RevisionTree};
+pub use server::{ServerInfo, ServerVersion, Vendor};
+pub use uuids::UuidPool;
+pub use view::{TypedViewResponse, TypedViewRow, ViewResponse, ViewRow};
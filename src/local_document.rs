@@ -0,0 +1,273 @@
+use {DocumentId, Error, LocalDocumentName, LocalRevision, serde, serde_json, std};
+
+/// Contains the current version of a local document.
+///
+/// A local document is a document whose id begins with `_local/`. The CouchDB
+/// server doesn't replicate local documents, doesn't keep their revision
+/// history, and doesn't include them in views, which makes them useful for
+/// storing per-database state such as replication checkpoints.
+///
+/// Unlike a `Document`, a `LocalDocument` has a `LocalRevision` and no
+/// attachments.
+///
+#[derive(Clone, Debug, PartialEq)]
+pub struct LocalDocument {
+    name: LocalDocumentName,
+    revision: LocalRevision,
+    content: serde_json::Value,
+}
+
+impl LocalDocument {
+    /// Returns the local document's name—i.e., its id without the `_local/`
+    /// prefix.
+    pub fn name(&self) -> &LocalDocumentName {
+        &self.name
+    }
+
+    /// Returns the local document's revision.
+    pub fn revision(&self) -> &LocalRevision {
+        &self.revision
+    }
+
+    /// Decodes and returns the local document's content, from a JSON object
+    /// into a Rust type.
+    pub fn get_content<C: serde::Deserialize>(&self) -> Result<C, Error> {
+        serde_json::from_value(self.content.clone()).map_err(|e| Error::JsonDecode { cause: e })
+    }
+}
+
+impl serde::Deserialize for LocalDocument {
+    fn deserialize<D>(deserializer: &mut D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer,
+    {
+        struct Visitor;
+
+        impl serde::de::Visitor for Visitor {
+            type Value = LocalDocument;
+
+            fn visit_map<V>(&mut self, mut visitor: V) -> Result<Self::Value, V::Error>
+            where
+                V: serde::de::MapVisitor,
+            {
+                use serde::de::Error;
+
+                let mut id = None;
+                let mut rev = None;
+                let mut content = std::collections::BTreeMap::new();
+
+                while let Some(key) = try!(visitor.visit_key::<String>()) {
+                    match key.as_str() {
+                        "_id" => {
+                            id = Some(try!(visitor.visit_value::<DocumentId>()));
+                        }
+                        "_rev" => {
+                            rev = Some(try!(visitor.visit_value()));
+                        }
+                        _ => {
+                            let value = try!(visitor.visit_value::<serde_json::Value>());
+                            content.insert(key, value);
+                        }
+                    }
+                }
+
+                try!(visitor.end());
+
+                let name = match id {
+                    Some(DocumentId::Local(x)) => x,
+                    Some(_) => return Err(V::Error::invalid_value("Document id is not that of a local document")),
+                    None => try!(visitor.missing_field("_id")),
+                };
+
+                Ok(LocalDocument {
+                    name: name,
+                    revision: match rev {
+                        Some(x) => x,
+                        None => try!(visitor.missing_field("_rev")),
+                    },
+                    content: serde_json::Value::Object(content),
+                })
+            }
+        }
+
+        deserializer.deserialize_map(Visitor)
+    }
+}
+
+// Decodes any JSON object containing a local revision in its "rev" field—e.g.,
+// the server's response to writing a local document.
+#[derive(Debug, PartialEq)]
+pub struct LocalRevisionJsonable {
+    pub revision: LocalRevision,
+}
+
+impl serde::Deserialize for LocalRevisionJsonable {
+    fn deserialize<D>(deserializer: &mut D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer,
+    {
+        enum Field {
+            Ignored,
+            Rev,
+        }
+
+        impl serde::Deserialize for Field {
+            fn deserialize<D>(deserializer: &mut D) -> Result<Self, D::Error>
+            where
+                D: serde::Deserializer,
+            {
+                struct Visitor;
+
+                impl serde::de::Visitor for Visitor {
+                    type Value = Field;
+
+                    fn visit_str<E>(&mut self, value: &str) -> Result<Self::Value, E>
+                    where
+                        E: serde::de::Error,
+                    {
+                        match value {
+                            "rev" => Ok(Field::Rev),
+                            _ => Ok(Field::Ignored),
+                        }
+                    }
+                }
+
+                deserializer.deserialize(Visitor)
+            }
+        }
+
+        struct Visitor;
+
+        impl serde::de::Visitor for Visitor {
+            type Value = LocalRevisionJsonable;
+
+            fn visit_map<V>(&mut self, mut visitor: V) -> Result<Self::Value, V::Error>
+            where
+                V: serde::de::MapVisitor,
+            {
+                let mut rev = None;
+                loop {
+                    match try!(visitor.visit_key()) {
+                        Some(Field::Ignored) => {
+                            try!(visitor.visit_value::<serde_json::Value>());
+                        }
+                        Some(Field::Rev) => {
+                            rev = Some(try!(visitor.visit_value()));
+                        }
+                        None => {
+                            break;
+                        }
+                    }
+                }
+
+                try!(visitor.end());
+
+                Ok(LocalRevisionJsonable {
+                    revision: match rev {
+                        Some(x) => x,
+                        None => try!(visitor.missing_field("rev")),
+                    },
+                })
+            }
+        }
+
+        static FIELDS: &'static [&'static str] = &["rev"];
+        deserializer.deserialize_struct("LocalRevisionJsonable", FIELDS, Visitor)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use {LocalDocumentName, LocalRevision, serde_json};
+
+    #[test]
+    fn local_document_get_content() {
+
+        let doc = LocalDocument {
+            name: LocalDocumentName::from("checkpoint"),
+            revision: LocalRevision::parse("0-3").unwrap(),
+            content: serde_json::builder::ObjectBuilder::new()
+                .insert("seq", 42)
+                .build(),
+        };
+
+        let expected = serde_json::builder::ObjectBuilder::new()
+            .insert("seq", 42)
+            .build();
+        let got = doc.get_content::<serde_json::Value>().unwrap();
+        assert_eq!(expected, got);
+    }
+
+    #[test]
+    fn local_document_deserialize_ok() {
+
+        let expected = LocalDocument {
+            name: LocalDocumentName::from("checkpoint"),
+            revision: LocalRevision::parse("0-3").unwrap(),
+            content: serde_json::builder::ObjectBuilder::new()
+                .insert("seq", 42)
+                .build(),
+        };
+
+        let source = serde_json::builder::ObjectBuilder::new()
+            .insert("_id", "_local/checkpoint")
+            .insert("_rev", "0-3")
+            .insert("seq", 42)
+            .build();
+
+        let got = serde_json::from_value(source).unwrap();
+        assert_eq!(expected, got);
+    }
+
+    #[test]
+    fn local_document_deserialize_nok_missing_id() {
+        let source = serde_json::builder::ObjectBuilder::new()
+            .insert("_rev", "0-3")
+            .build();
+        let got = serde_json::from_value::<LocalDocument>(source);
+        expect_json_error_missing_field!(got, "_id");
+    }
+
+    #[test]
+    fn local_document_deserialize_nok_missing_rev() {
+        let source = serde_json::builder::ObjectBuilder::new()
+            .insert("_id", "_local/checkpoint")
+            .build();
+        let got = serde_json::from_value::<LocalDocument>(source);
+        expect_json_error_missing_field!(got, "_rev");
+    }
+
+    #[test]
+    fn local_document_deserialize_nok_normal_document_id() {
+        let source = serde_json::builder::ObjectBuilder::new()
+            .insert("_id", "checkpoint")
+            .insert("_rev", "0-3")
+            .build();
+        let got = serde_json::from_value::<LocalDocument>(source);
+        expect_json_error_invalid_value!(got);
+    }
+
+    #[test]
+    fn local_revision_jsonable_deserialize_ok() {
+        let expected = LocalRevisionJsonable { revision: LocalRevision::parse("0-4").unwrap() };
+        let source = serde_json::builder::ObjectBuilder::new()
+            .insert("ok", true)
+            .insert("id", "_local/checkpoint")
+            .insert("rev", "0-4")
+            .build();
+        let got = serde_json::from_value(source).unwrap();
+        assert_eq!(expected, got);
+    }
+
+    #[test]
+    fn local_revision_jsonable_deserialize_nok_missing_rev() {
+        let source = serde_json::builder::ObjectBuilder::new()
+            .insert("ok", true)
+            .insert("id", "_local/checkpoint")
+            .build();
+        let got = serde_json::from_value::<LocalRevisionJsonable>(source);
+        expect_json_error_missing_field!(got, "rev");
+    }
+}
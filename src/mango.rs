@@ -0,0 +1,1577 @@
+//! Defines types for declarative (Mango) queries.
+//!
+//! CouchDB 2.0 and later support _Mango_ queries, which find documents
+//! matching a JSON _selector_ without requiring a view. This module provides a
+//! builder for selectors, types for defining the indexes that back queries,
+//! plus the types making up the result of a query. For more information about
+//! Mango queries, please see the CouchDB documentation.
+//!
+//! # Examples
+//!
+//! ```
+//! extern crate chill;
+//! extern crate serde_json;
+//!
+//! use chill::mango::Selector;
+//!
+//! // Find players who hit at least 700 home runs and whose name starts with
+//! // "B" or who played for the Giants.
+//!
+//! let selector = Selector::new()
+//!     .gte("home_runs", 700)
+//!     .or(vec![
+//!         Selector::new().regex("name", "^B"),
+//!         Selector::new().elem_match("teams", Selector::new().eq("name", "Giants")),
+//!     ]);
+//!
+//! let expected = serde_json::builder::ObjectBuilder::new()
+//!     .insert_object("home_runs", |x| x.insert("$gte", 700))
+//!     .insert_array("$or", |x| {
+//!         x.push_object(|x| x.insert_object("name", |x| x.insert("$regex", "^B")))
+//!          .push_object(|x| {
+//!              x.insert_object("teams", |x| {
+//!                  x.insert_object("$elemMatch", |x| {
+//!                      x.insert_object("name", |x| x.insert("$eq", "Giants"))
+//!                  })
+//!              })
+//!          })
+//!     })
+//!     .build();
+//!
+//! assert_eq!(expected, serde_json::to_value(&selector));
+//! ```
+
+use {DatabaseName, DesignDocumentName, Document, DocumentId, serde, serde_json, std};
+use document::JsonDecodableDocument;
+
+/// Builder for a Mango query's selector.
+///
+/// A `Selector` is a JSON object that documents must match to be included in
+/// a Mango query's result. An empty selector matches all documents.
+///
+/// Each field-condition method—e.g., `eq`, `gt`, `regex`—adds an operator for
+/// the given field. Fields may use dot notation to refer to nested fields,
+/// e.g., `"address.city"`. Adding multiple operators for the same field
+/// requires a document to satisfy all of them.
+///
+/// The combination methods—`and`, `or`, `nor`, and `not`—add an operator
+/// that combines other selectors. Calling the same combination method twice
+/// replaces the earlier operator.
+///
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Selector {
+    inner: std::collections::BTreeMap<String, serde_json::Value>,
+}
+
+impl Selector {
+    /// Constructs an empty selector, which matches all documents.
+    pub fn new() -> Self {
+        Selector { inner: std::collections::BTreeMap::new() }
+    }
+
+    fn with_field_operator<F, V>(mut self, field: F, operator: &str, value: &V) -> Self
+    where
+        F: Into<String>,
+        V: serde::Serialize,
+    {
+        let value = serde_json::to_value(value);
+        let entry = self.inner.entry(field.into()).or_insert_with(|| {
+            serde_json::Value::Object(std::collections::BTreeMap::new())
+        });
+
+        // A field set directly to a value, rather than to an object of
+        // operators, can't also hold an operator, so the value is replaced.
+        match *entry {
+            serde_json::Value::Object(ref mut operators) => {
+                operators.insert(String::from(operator), value);
+                return self;
+            }
+            _ => {}
+        }
+
+        let mut operators = std::collections::BTreeMap::new();
+        operators.insert(String::from(operator), value);
+        *entry = serde_json::Value::Object(operators);
+        self
+    }
+
+    fn with_combination(mut self, operator: &str, value: serde_json::Value) -> Self {
+        self.inner.insert(String::from(operator), value);
+        self
+    }
+
+    /// Requires the field to equal the given value (`$eq`).
+    pub fn eq<F: Into<String>, V: serde::Serialize>(self, field: F, value: V) -> Self {
+        self.with_field_operator(field, "$eq", &value)
+    }
+
+    /// Requires the field to not equal the given value (`$ne`).
+    pub fn ne<F: Into<String>, V: serde::Serialize>(self, field: F, value: V) -> Self {
+        self.with_field_operator(field, "$ne", &value)
+    }
+
+    /// Requires the field to be greater than the given value (`$gt`).
+    pub fn gt<F: Into<String>, V: serde::Serialize>(self, field: F, value: V) -> Self {
+        self.with_field_operator(field, "$gt", &value)
+    }
+
+    /// Requires the field to be greater than or equal to the given value
+    /// (`$gte`).
+    pub fn gte<F: Into<String>, V: serde::Serialize>(self, field: F, value: V) -> Self {
+        self.with_field_operator(field, "$gte", &value)
+    }
+
+    /// Requires the field to be less than the given value (`$lt`).
+    pub fn lt<F: Into<String>, V: serde::Serialize>(self, field: F, value: V) -> Self {
+        self.with_field_operator(field, "$lt", &value)
+    }
+
+    /// Requires the field to be less than or equal to the given value
+    /// (`$lte`).
+    pub fn lte<F: Into<String>, V: serde::Serialize>(self, field: F, value: V) -> Self {
+        self.with_field_operator(field, "$lte", &value)
+    }
+
+    /// Requires the field to exist or not exist (`$exists`).
+    pub fn exists<F: Into<String>>(self, field: F, yes_or_no: bool) -> Self {
+        self.with_field_operator(field, "$exists", &yes_or_no)
+    }
+
+    /// Requires the field to equal one of the given values (`$in`).
+    pub fn in_<F: Into<String>, V: serde::Serialize>(self, field: F, values: V) -> Self {
+        self.with_field_operator(field, "$in", &values)
+    }
+
+    /// Requires the field to equal none of the given values (`$nin`).
+    pub fn nin<F: Into<String>, V: serde::Serialize>(self, field: F, values: V) -> Self {
+        self.with_field_operator(field, "$nin", &values)
+    }
+
+    /// Requires the field to be an array containing all the given values
+    /// (`$all`).
+    pub fn all<F: Into<String>, V: serde::Serialize>(self, field: F, values: V) -> Self {
+        self.with_field_operator(field, "$all", &values)
+    }
+
+    /// Requires the field to be an array of the given length (`$size`).
+    pub fn size<F: Into<String>>(self, field: F, length: u64) -> Self {
+        self.with_field_operator(field, "$size", &length)
+    }
+
+    /// Requires the field to be a string matching the given regular
+    /// expression (`$regex`).
+    pub fn regex<F: Into<String>, R: AsRef<str>>(self, field: F, pattern: R) -> Self {
+        self.with_field_operator(field, "$regex", &pattern.as_ref())
+    }
+
+    /// Requires the field to be an array with at least one element matching
+    /// the given selector (`$elemMatch`).
+    pub fn elem_match<F: Into<String>>(self, field: F, selector: Selector) -> Self {
+        self.with_field_operator(field, "$elemMatch", &selector)
+    }
+
+    /// Requires the field to be an array with every element matching the
+    /// given selector (`$allMatch`).
+    pub fn all_match<F: Into<String>>(self, field: F, selector: Selector) -> Self {
+        self.with_field_operator(field, "$allMatch", &selector)
+    }
+
+    /// Requires documents to match all the given selectors (`$and`).
+    pub fn and(self, selectors: Vec<Selector>) -> Self {
+        self.with_combination("$and", serde_json::to_value(&selectors))
+    }
+
+    /// Requires documents to match at least one of the given selectors
+    /// (`$or`).
+    pub fn or(self, selectors: Vec<Selector>) -> Self {
+        self.with_combination("$or", serde_json::to_value(&selectors))
+    }
+
+    /// Requires documents to match none of the given selectors (`$nor`).
+    pub fn nor(self, selectors: Vec<Selector>) -> Self {
+        self.with_combination("$nor", serde_json::to_value(&selectors))
+    }
+
+    /// Requires documents to not match the given selector (`$not`).
+    pub fn not(self, selector: Selector) -> Self {
+        self.with_combination("$not", serde_json::to_value(&selector))
+    }
+}
+
+impl serde::Serialize for Selector {
+    fn serialize<S>(&self, serializer: &mut S) -> Result<(), S::Error>
+    where
+        S: serde::Serializer,
+    {
+        self.inner.serialize(serializer)
+    }
+}
+
+impl serde::Deserialize for Selector {
+    fn deserialize<D>(deserializer: &mut D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer,
+    {
+        Ok(Selector { inner: try!(serde::Deserialize::deserialize(deserializer)) })
+    }
+}
+
+/// Direction for sorting a Mango query's result by a field.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum SortOrder {
+    Ascending,
+    Descending,
+}
+
+impl SortOrder {
+    #[doc(hidden)]
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            &SortOrder::Ascending => "asc",
+            &SortOrder::Descending => "desc",
+        }
+    }
+}
+
+/// Statistics about the execution of a Mango query.
+///
+/// CouchDB returns execution statistics only if the query requests them—e.g.,
+/// via the `Find::with_execution_stats` method.
+///
+#[derive(Clone, Debug, PartialEq)]
+pub struct ExecutionStats {
+    total_keys_examined: u64,
+    total_docs_examined: u64,
+    total_quorum_docs_examined: u64,
+    results_returned: u64,
+    execution_time_ms: f64,
+}
+
+impl ExecutionStats {
+    /// Returns the number of index keys examined.
+    pub fn total_keys_examined(&self) -> u64 {
+        self.total_keys_examined
+    }
+
+    /// Returns the number of documents fetched from the database or index.
+    pub fn total_docs_examined(&self) -> u64 {
+        self.total_docs_examined
+    }
+
+    /// Returns the number of documents fetched from the database using an
+    /// out-of-band document fetch.
+    pub fn total_quorum_docs_examined(&self) -> u64 {
+        self.total_quorum_docs_examined
+    }
+
+    /// Returns the number of results returned from the query.
+    pub fn results_returned(&self) -> u64 {
+        self.results_returned
+    }
+
+    /// Returns the total execution time, in milliseconds, as measured by the
+    /// database.
+    pub fn execution_time_ms(&self) -> f64 {
+        self.execution_time_ms
+    }
+}
+
+impl serde::Deserialize for ExecutionStats {
+    fn deserialize<D>(deserializer: &mut D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer,
+    {
+        enum Field {
+            ExecutionTimeMs,
+            Ignored,
+            ResultsReturned,
+            TotalDocsExamined,
+            TotalKeysExamined,
+            TotalQuorumDocsExamined,
+        }
+
+        impl serde::Deserialize for Field {
+            fn deserialize<D>(deserializer: &mut D) -> Result<Field, D::Error>
+            where
+                D: serde::Deserializer,
+            {
+                struct Visitor;
+
+                impl serde::de::Visitor for Visitor {
+                    type Value = Field;
+
+                    fn visit_str<E>(&mut self, value: &str) -> Result<Field, E>
+                    where
+                        E: serde::de::Error,
+                    {
+                        match value {
+                            "execution_time_ms" => Ok(Field::ExecutionTimeMs),
+                            "results_returned" => Ok(Field::ResultsReturned),
+                            "total_docs_examined" => Ok(Field::TotalDocsExamined),
+                            "total_keys_examined" => Ok(Field::TotalKeysExamined),
+                            "total_quorum_docs_examined" => Ok(Field::TotalQuorumDocsExamined),
+                            _ => Ok(Field::Ignored),
+                        }
+                    }
+                }
+
+                deserializer.deserialize(Visitor)
+            }
+        }
+
+        struct Visitor;
+
+        impl serde::de::Visitor for Visitor {
+            type Value = ExecutionStats;
+
+            fn visit_map<V>(&mut self, mut visitor: V) -> Result<Self::Value, V::Error>
+            where
+                V: serde::de::MapVisitor,
+            {
+                let mut execution_time_ms = None;
+                let mut results_returned = None;
+                let mut total_docs_examined = None;
+                let mut total_keys_examined = None;
+                let mut total_quorum_docs_examined = None;
+
+                loop {
+                    match try!(visitor.visit_key()) {
+                        Some(Field::ExecutionTimeMs) => {
+                            execution_time_ms = Some(try!(visitor.visit_value()));
+                        }
+                        Some(Field::Ignored) => {
+                            try!(visitor.visit_value::<serde_json::Value>());
+                        }
+                        Some(Field::ResultsReturned) => {
+                            results_returned = Some(try!(visitor.visit_value()));
+                        }
+                        Some(Field::TotalDocsExamined) => {
+                            total_docs_examined = Some(try!(visitor.visit_value()));
+                        }
+                        Some(Field::TotalKeysExamined) => {
+                            total_keys_examined = Some(try!(visitor.visit_value()));
+                        }
+                        Some(Field::TotalQuorumDocsExamined) => {
+                            total_quorum_docs_examined = Some(try!(visitor.visit_value()));
+                        }
+                        None => {
+                            break;
+                        }
+                    }
+                }
+
+                try!(visitor.end());
+
+                Ok(ExecutionStats {
+                    total_keys_examined: total_keys_examined.unwrap_or(0),
+                    total_docs_examined: total_docs_examined.unwrap_or(0),
+                    total_quorum_docs_examined: total_quorum_docs_examined.unwrap_or(0),
+                    results_returned: results_returned.unwrap_or(0),
+                    execution_time_ms: execution_time_ms.unwrap_or(0.0),
+                })
+            }
+        }
+
+        static FIELDS: &'static [&'static str] = &[
+            "execution_time_ms",
+            "results_returned",
+            "total_docs_examined",
+            "total_keys_examined",
+            "total_quorum_docs_examined",
+        ];
+        deserializer.deserialize_struct("ExecutionStats", FIELDS, Visitor)
+    }
+}
+
+/// Contains the result of a Mango query.
+///
+/// A `FindResponse` holds the documents matching the query's selector, plus a
+/// bookmark for retrieving the next page of results, any warning from the
+/// server—e.g., that no index matched the query—and, if requested, execution
+/// statistics.
+///
+#[derive(Clone, Debug, PartialEq)]
+pub struct FindResponse {
+    documents: Vec<Document>,
+    bookmark: Option<String>,
+    warning: Option<String>,
+    execution_stats: Option<ExecutionStats>,
+}
+
+impl FindResponse {
+    #[doc(hidden)]
+    pub fn new_from_decoded(db_name: DatabaseName, response: FindResponseJsonable) -> Self {
+        FindResponse {
+            documents: response
+                .docs
+                .into_iter()
+                .map(|doc| Document::new_from_decoded(db_name.clone(), doc))
+                .collect(),
+            bookmark: response.bookmark,
+            warning: response.warning,
+            execution_stats: response.execution_stats,
+        }
+    }
+
+    /// Borrows the documents matching the query.
+    pub fn documents(&self) -> &Vec<Document> {
+        &self.documents
+    }
+
+    /// Converts the response into the documents matching the query.
+    pub fn into_documents(self) -> Vec<Document> {
+        self.documents
+    }
+
+    /// Returns the bookmark for retrieving the next page of results, if
+    /// available.
+    pub fn bookmark(&self) -> Option<&str> {
+        self.bookmark.as_ref().map(|x| x.as_str())
+    }
+
+    /// Returns the server's warning about the query, if any.
+    pub fn warning(&self) -> Option<&str> {
+        self.warning.as_ref().map(|x| x.as_str())
+    }
+
+    /// Returns the query's execution statistics, if requested.
+    pub fn execution_stats(&self) -> Option<&ExecutionStats> {
+        self.execution_stats.as_ref()
+    }
+}
+
+// FindResponseJsonable is an intermediate type for decoding a FindResponse. The
+// documents it holds lack a database name, which is not known at decode-time.
+#[doc(hidden)]
+#[derive(Debug, PartialEq)]
+pub struct FindResponseJsonable {
+    docs: Vec<JsonDecodableDocument>,
+    bookmark: Option<String>,
+    warning: Option<String>,
+    execution_stats: Option<ExecutionStats>,
+}
+
+impl serde::Deserialize for FindResponseJsonable {
+    fn deserialize<D>(deserializer: &mut D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer,
+    {
+        enum Field {
+            Bookmark,
+            Docs,
+            ExecutionStats,
+            Ignored,
+            Warning,
+        }
+
+        impl serde::Deserialize for Field {
+            fn deserialize<D>(deserializer: &mut D) -> Result<Field, D::Error>
+            where
+                D: serde::Deserializer,
+            {
+                struct Visitor;
+
+                impl serde::de::Visitor for Visitor {
+                    type Value = Field;
+
+                    fn visit_str<E>(&mut self, value: &str) -> Result<Field, E>
+                    where
+                        E: serde::de::Error,
+                    {
+                        match value {
+                            "bookmark" => Ok(Field::Bookmark),
+                            "docs" => Ok(Field::Docs),
+                            "execution_stats" => Ok(Field::ExecutionStats),
+                            "warning" => Ok(Field::Warning),
+                            _ => Ok(Field::Ignored),
+                        }
+                    }
+                }
+
+                deserializer.deserialize(Visitor)
+            }
+        }
+
+        struct Visitor;
+
+        impl serde::de::Visitor for Visitor {
+            type Value = FindResponseJsonable;
+
+            fn visit_map<V>(&mut self, mut visitor: V) -> Result<Self::Value, V::Error>
+            where
+                V: serde::de::MapVisitor,
+            {
+                let mut bookmark = None;
+                let mut docs = None;
+                let mut execution_stats = None;
+                let mut warning = None;
+
+                loop {
+                    match try!(visitor.visit_key()) {
+                        Some(Field::Bookmark) => {
+                            bookmark = Some(try!(visitor.visit_value()));
+                        }
+                        Some(Field::Docs) => {
+                            docs = Some(try!(visitor.visit_value()));
+                        }
+                        Some(Field::ExecutionStats) => {
+                            execution_stats = Some(try!(visitor.visit_value()));
+                        }
+                        Some(Field::Ignored) => {
+                            try!(visitor.visit_value::<serde_json::Value>());
+                        }
+                        Some(Field::Warning) => {
+                            warning = Some(try!(visitor.visit_value()));
+                        }
+                        None => {
+                            break;
+                        }
+                    }
+                }
+
+                try!(visitor.end());
+
+                Ok(FindResponseJsonable {
+                    docs: match docs {
+                        Some(x) => x,
+                        None => try!(visitor.missing_field("docs")),
+                    },
+                    bookmark: bookmark,
+                    warning: warning,
+                    execution_stats: execution_stats,
+                })
+            }
+        }
+
+        static FIELDS: &'static [&'static str] = &["bookmark", "docs", "execution_stats", "warning"];
+        deserializer.deserialize_struct("FindResponseJsonable", FIELDS, Visitor)
+    }
+}
+
+/// Definition of a Mango index—i.e., the fields it indexes.
+///
+/// An `IndexDefinition` is used to create an index, via the `CreateIndex`
+/// action, and is part of each `Index` returned by the server. Optionally, an
+/// index is _partial_, meaning it includes only documents matching a
+/// selector.
+///
+/// # Examples
+///
+/// ```
+/// extern crate chill;
+/// extern crate serde_json;
+///
+/// use chill::mango::{IndexDefinition, Selector, SortOrder};
+///
+/// let definition = IndexDefinition::new()
+///     .with_field("team")
+///     .with_sort_field("home_runs", SortOrder::Descending)
+///     .with_partial_filter_selector(Selector::new().exists("retired", false));
+///
+/// let expected = serde_json::builder::ObjectBuilder::new()
+///     .insert_array("fields", |x| {
+///         x.push_object(|x| x.insert("team", "asc"))
+///          .push_object(|x| x.insert("home_runs", "desc"))
+///     })
+///     .insert_object("partial_filter_selector", |x| {
+///         x.insert_object("retired", |x| x.insert("$exists", false))
+///     })
+///     .build();
+///
+/// assert_eq!(expected, serde_json::to_value(&definition));
+/// ```
+///
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct IndexDefinition {
+    fields: Vec<(String, SortOrder)>,
+    partial_filter_selector: Option<Selector>,
+}
+
+impl IndexDefinition {
+    /// Constructs an index definition with no fields.
+    pub fn new() -> Self {
+        IndexDefinition {
+            fields: Vec::new(),
+            partial_filter_selector: None,
+        }
+    }
+
+    /// Adds a field to the index, sorted in ascending order.
+    pub fn with_field<F: Into<String>>(self, field: F) -> Self {
+        self.with_sort_field(field, SortOrder::Ascending)
+    }
+
+    /// Adds a field to the index, sorted in the given order.
+    pub fn with_sort_field<F: Into<String>>(mut self, field: F, order: SortOrder) -> Self {
+        self.fields.push((field.into(), order));
+        self
+    }
+
+    /// Limits the index to documents matching the given selector.
+    pub fn with_partial_filter_selector(mut self, selector: Selector) -> Self {
+        self.partial_filter_selector = Some(selector);
+        self
+    }
+
+    /// Borrows the index's fields, in order, each with its sort order.
+    pub fn fields(&self) -> &Vec<(String, SortOrder)> {
+        &self.fields
+    }
+
+    /// Borrows the selector limiting which documents the index includes, if
+    /// the index is partial.
+    pub fn partial_filter_selector(&self) -> Option<&Selector> {
+        self.partial_filter_selector.as_ref()
+    }
+}
+
+impl serde::Serialize for IndexDefinition {
+    fn serialize<S>(&self, serializer: &mut S) -> Result<(), S::Error>
+    where
+        S: serde::Serializer,
+    {
+        let len = if self.partial_filter_selector.is_some() { 2 } else { 1 };
+        let mut state = try!(serializer.serialize_map(Some(len)));
+
+        let fields = self.fields
+            .iter()
+            .map(|&(ref field, ref order)| {
+                let mut x = std::collections::BTreeMap::new();
+                x.insert(field.as_str(), order.as_str());
+                x
+            })
+            .collect::<Vec<_>>();
+
+        try!(serializer.serialize_map_key(&mut state, "fields"));
+        try!(serializer.serialize_map_value(&mut state, fields));
+
+        if let Some(ref selector) = self.partial_filter_selector {
+            try!(serializer.serialize_map_key(&mut state, "partial_filter_selector"));
+            try!(serializer.serialize_map_value(&mut state, selector));
+        }
+
+        serializer.serialize_map_end(state)
+    }
+}
+
+impl serde::Deserialize for IndexDefinition {
+    fn deserialize<D>(deserializer: &mut D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer,
+    {
+        enum Field {
+            Fields,
+            Ignored,
+            PartialFilterSelector,
+        }
+
+        impl serde::Deserialize for Field {
+            fn deserialize<D>(deserializer: &mut D) -> Result<Field, D::Error>
+            where
+                D: serde::Deserializer,
+            {
+                struct Visitor;
+
+                impl serde::de::Visitor for Visitor {
+                    type Value = Field;
+
+                    fn visit_str<E>(&mut self, value: &str) -> Result<Field, E>
+                    where
+                        E: serde::de::Error,
+                    {
+                        match value {
+                            "fields" => Ok(Field::Fields),
+                            "partial_filter_selector" => Ok(Field::PartialFilterSelector),
+                            _ => Ok(Field::Ignored),
+                        }
+                    }
+                }
+
+                deserializer.deserialize(Visitor)
+            }
+        }
+
+        struct Visitor;
+
+        impl serde::de::Visitor for Visitor {
+            type Value = IndexDefinition;
+
+            fn visit_map<V>(&mut self, mut visitor: V) -> Result<Self::Value, V::Error>
+            where
+                V: serde::de::MapVisitor,
+            {
+                let mut fields = None;
+                let mut partial_filter_selector = None;
+
+                loop {
+                    match try!(visitor.visit_key()) {
+                        Some(Field::Fields) => {
+                            fields = Some(try!(visitor.visit_value()));
+                        }
+                        Some(Field::Ignored) => {
+                            try!(visitor.visit_value::<serde_json::Value>());
+                        }
+                        Some(Field::PartialFilterSelector) => {
+                            partial_filter_selector = Some(try!(visitor.visit_value()));
+                        }
+                        None => {
+                            break;
+                        }
+                    }
+                }
+
+                try!(visitor.end());
+
+                let fields: Vec<SortField> = match fields {
+                    Some(x) => x,
+                    None => try!(visitor.missing_field("fields")),
+                };
+
+                Ok(IndexDefinition {
+                    fields: fields.into_iter().map(|SortField(field, order)| (field, order)).collect(),
+                    partial_filter_selector: partial_filter_selector,
+                })
+            }
+        }
+
+        static FIELDS: &'static [&'static str] = &["fields", "partial_filter_selector"];
+        deserializer.deserialize_struct("IndexDefinition", FIELDS, Visitor)
+    }
+}
+
+// SortField is an intermediate type for decoding an index field, which CouchDB
+// encodes either as the field name alone or as an object mapping the field
+// name to its sort order.
+struct SortField(String, SortOrder);
+
+impl serde::Deserialize for SortField {
+    fn deserialize<D>(deserializer: &mut D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer,
+    {
+        struct Visitor;
+
+        impl serde::de::Visitor for Visitor {
+            type Value = SortField;
+
+            fn visit_str<E>(&mut self, value: &str) -> Result<Self::Value, E>
+            where
+                E: serde::de::Error,
+            {
+                Ok(SortField(String::from(value), SortOrder::Ascending))
+            }
+
+            fn visit_map<V>(&mut self, mut visitor: V) -> Result<Self::Value, V::Error>
+            where
+                V: serde::de::MapVisitor,
+            {
+                use serde::de::Error;
+
+                let field: String = match try!(visitor.visit_key()) {
+                    Some(x) => x,
+                    None => return Err(V::Error::invalid_length(0)),
+                };
+
+                let order = match try!(visitor.visit_value::<String>()).as_str() {
+                    "asc" => SortOrder::Ascending,
+                    "desc" => SortOrder::Descending,
+                    _ => return Err(V::Error::invalid_value("Sort order is neither \"asc\" nor \"desc\"")),
+                };
+
+                if try!(visitor.visit_key::<String>()).is_some() {
+                    return Err(V::Error::invalid_length(2));
+                }
+
+                try!(visitor.end());
+                Ok(SortField(field, order))
+            }
+        }
+
+        deserializer.deserialize(Visitor)
+    }
+}
+
+/// Mango index stored on the CouchDB server.
+///
+/// An `Index` is returned by the `ListIndexes` action and is part of the
+/// result of the `Explain` action.
+///
+#[derive(Clone, Debug, PartialEq)]
+pub struct Index {
+    ddoc_name: Option<DesignDocumentName>,
+    name: String,
+    index_type: String,
+    definition: IndexDefinition,
+}
+
+impl Index {
+    /// Returns the name of the design document containing the index, if any.
+    ///
+    /// The special index over the `_id` field, which every database has, is
+    /// not contained in a design document.
+    ///
+    pub fn design_document_name(&self) -> Option<&DesignDocumentName> {
+        self.ddoc_name.as_ref()
+    }
+
+    /// Returns the name of the index.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns the type of the index—e.g., `"json"` or `"special"`.
+    pub fn index_type(&self) -> &str {
+        &self.index_type
+    }
+
+    /// Borrows the definition of the index.
+    pub fn definition(&self) -> &IndexDefinition {
+        &self.definition
+    }
+}
+
+impl serde::Deserialize for Index {
+    fn deserialize<D>(deserializer: &mut D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer,
+    {
+        enum Field {
+            Ddoc,
+            Def,
+            Ignored,
+            Name,
+            Type,
+        }
+
+        impl serde::Deserialize for Field {
+            fn deserialize<D>(deserializer: &mut D) -> Result<Field, D::Error>
+            where
+                D: serde::Deserializer,
+            {
+                struct Visitor;
+
+                impl serde::de::Visitor for Visitor {
+                    type Value = Field;
+
+                    fn visit_str<E>(&mut self, value: &str) -> Result<Field, E>
+                    where
+                        E: serde::de::Error,
+                    {
+                        match value {
+                            "ddoc" => Ok(Field::Ddoc),
+                            "def" => Ok(Field::Def),
+                            "name" => Ok(Field::Name),
+                            "type" => Ok(Field::Type),
+                            _ => Ok(Field::Ignored),
+                        }
+                    }
+                }
+
+                deserializer.deserialize(Visitor)
+            }
+        }
+
+        struct Visitor;
+
+        impl serde::de::Visitor for Visitor {
+            type Value = Index;
+
+            fn visit_map<V>(&mut self, mut visitor: V) -> Result<Self::Value, V::Error>
+            where
+                V: serde::de::MapVisitor,
+            {
+                let mut ddoc = None;
+                let mut def = None;
+                let mut name = None;
+                let mut index_type = None;
+
+                loop {
+                    match try!(visitor.visit_key()) {
+                        Some(Field::Ddoc) => {
+                            ddoc = try!(visitor.visit_value::<Option<DocumentId>>());
+                        }
+                        Some(Field::Def) => {
+                            def = Some(try!(visitor.visit_value()));
+                        }
+                        Some(Field::Ignored) => {
+                            try!(visitor.visit_value::<serde_json::Value>());
+                        }
+                        Some(Field::Name) => {
+                            name = Some(try!(visitor.visit_value()));
+                        }
+                        Some(Field::Type) => {
+                            index_type = Some(try!(visitor.visit_value()));
+                        }
+                        None => {
+                            break;
+                        }
+                    }
+                }
+
+                try!(visitor.end());
+
+                Ok(Index {
+                    ddoc_name: ddoc.map(|x| match x {
+                        DocumentId::Design(x) => x,
+                        x @ _ => DesignDocumentName::from(x.name_as_str()),
+                    }),
+                    name: match name {
+                        Some(x) => x,
+                        None => try!(visitor.missing_field("name")),
+                    },
+                    index_type: match index_type {
+                        Some(x) => x,
+                        None => try!(visitor.missing_field("type")),
+                    },
+                    definition: match def {
+                        Some(x) => x,
+                        None => try!(visitor.missing_field("def")),
+                    },
+                })
+            }
+        }
+
+        static FIELDS: &'static [&'static str] = &["ddoc", "def", "name", "type"];
+        deserializer.deserialize_struct("Index", FIELDS, Visitor)
+    }
+}
+
+// IndexListJsonable is an intermediate type for decoding the list of indexes
+// in a database.
+#[doc(hidden)]
+#[derive(Debug, PartialEq)]
+pub struct IndexListJsonable {
+    indexes: Vec<Index>,
+}
+
+impl IndexListJsonable {
+    #[doc(hidden)]
+    pub fn into_indexes(self) -> Vec<Index> {
+        self.indexes
+    }
+}
+
+impl serde::Deserialize for IndexListJsonable {
+    fn deserialize<D>(deserializer: &mut D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer,
+    {
+        enum Field {
+            Ignored,
+            Indexes,
+        }
+
+        impl serde::Deserialize for Field {
+            fn deserialize<D>(deserializer: &mut D) -> Result<Field, D::Error>
+            where
+                D: serde::Deserializer,
+            {
+                struct Visitor;
+
+                impl serde::de::Visitor for Visitor {
+                    type Value = Field;
+
+                    fn visit_str<E>(&mut self, value: &str) -> Result<Field, E>
+                    where
+                        E: serde::de::Error,
+                    {
+                        match value {
+                            "indexes" => Ok(Field::Indexes),
+                            _ => Ok(Field::Ignored),
+                        }
+                    }
+                }
+
+                deserializer.deserialize(Visitor)
+            }
+        }
+
+        struct Visitor;
+
+        impl serde::de::Visitor for Visitor {
+            type Value = IndexListJsonable;
+
+            fn visit_map<V>(&mut self, mut visitor: V) -> Result<Self::Value, V::Error>
+            where
+                V: serde::de::MapVisitor,
+            {
+                let mut indexes = None;
+
+                loop {
+                    match try!(visitor.visit_key()) {
+                        Some(Field::Ignored) => {
+                            try!(visitor.visit_value::<serde_json::Value>());
+                        }
+                        Some(Field::Indexes) => {
+                            indexes = Some(try!(visitor.visit_value()));
+                        }
+                        None => {
+                            break;
+                        }
+                    }
+                }
+
+                try!(visitor.end());
+
+                Ok(IndexListJsonable {
+                    indexes: match indexes {
+                        Some(x) => x,
+                        None => try!(visitor.missing_field("indexes")),
+                    },
+                })
+            }
+        }
+
+        static FIELDS: &'static [&'static str] = &["indexes"];
+        deserializer.deserialize_struct("IndexListJsonable", FIELDS, Visitor)
+    }
+}
+
+/// Outcome of creating a Mango index.
+///
+/// CouchDB does not create an index if an identical index already exists, in
+/// which case the outcome is `Exists`. Either way, the outcome identifies the
+/// index by its design document and name.
+///
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub enum IndexCreation {
+    /// The server created the index.
+    Created(DesignDocumentName, String),
+
+    /// The index already existed.
+    Exists(DesignDocumentName, String),
+}
+
+impl IndexCreation {
+    /// Returns the name of the design document containing the index.
+    pub fn design_document_name(&self) -> &DesignDocumentName {
+        match self {
+            &IndexCreation::Created(ref x, _) => x,
+            &IndexCreation::Exists(ref x, _) => x,
+        }
+    }
+
+    /// Returns the name of the index.
+    pub fn index_name(&self) -> &str {
+        match self {
+            &IndexCreation::Created(_, ref x) => x,
+            &IndexCreation::Exists(_, ref x) => x,
+        }
+    }
+}
+
+#[doc(hidden)]
+impl serde::Deserialize for IndexCreation {
+    fn deserialize<D>(deserializer: &mut D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer,
+    {
+        enum Field {
+            Id,
+            Ignored,
+            Name,
+            Result,
+        }
+
+        impl serde::Deserialize for Field {
+            fn deserialize<D>(deserializer: &mut D) -> Result<Field, D::Error>
+            where
+                D: serde::Deserializer,
+            {
+                struct Visitor;
+
+                impl serde::de::Visitor for Visitor {
+                    type Value = Field;
+
+                    fn visit_str<E>(&mut self, value: &str) -> Result<Field, E>
+                    where
+                        E: serde::de::Error,
+                    {
+                        match value {
+                            "id" => Ok(Field::Id),
+                            "name" => Ok(Field::Name),
+                            "result" => Ok(Field::Result),
+                            _ => Ok(Field::Ignored),
+                        }
+                    }
+                }
+
+                deserializer.deserialize(Visitor)
+            }
+        }
+
+        struct Visitor;
+
+        impl serde::de::Visitor for Visitor {
+            type Value = IndexCreation;
+
+            fn visit_map<V>(&mut self, mut visitor: V) -> Result<Self::Value, V::Error>
+            where
+                V: serde::de::MapVisitor,
+            {
+                use serde::de::Error;
+
+                let mut id = None;
+                let mut name = None;
+                let mut result = None;
+
+                loop {
+                    match try!(visitor.visit_key()) {
+                        Some(Field::Id) => {
+                            id = Some(try!(visitor.visit_value::<DocumentId>()));
+                        }
+                        Some(Field::Ignored) => {
+                            try!(visitor.visit_value::<serde_json::Value>());
+                        }
+                        Some(Field::Name) => {
+                            name = Some(try!(visitor.visit_value()));
+                        }
+                        Some(Field::Result) => {
+                            result = Some(try!(visitor.visit_value()));
+                        }
+                        None => {
+                            break;
+                        }
+                    }
+                }
+
+                try!(visitor.end());
+
+                let ddoc_name = match id {
+                    Some(DocumentId::Design(x)) => x,
+                    Some(x) => DesignDocumentName::from(x.name_as_str()),
+                    None => try!(visitor.missing_field("id")),
+                };
+
+                let name = match name {
+                    Some(x) => x,
+                    None => try!(visitor.missing_field("name")),
+                };
+
+                let result: String = match result {
+                    Some(x) => x,
+                    None => try!(visitor.missing_field("result")),
+                };
+
+                match result.as_str() {
+                    "created" => Ok(IndexCreation::Created(ddoc_name, name)),
+                    "exists" => Ok(IndexCreation::Exists(ddoc_name, name)),
+                    _ => Err(V::Error::invalid_value("Result is neither \"created\" nor \"exists\"")),
+                }
+            }
+        }
+
+        static FIELDS: &'static [&'static str] = &["id", "name", "result"];
+        deserializer.deserialize_struct("IndexCreation", FIELDS, Visitor)
+    }
+}
+
+/// Contains the server's plan for executing a Mango query.
+///
+/// An `ExplainResult` is returned by the `Explain` action and describes which
+/// index the server would use to execute the query, plus the query's options
+/// as the server interprets them.
+///
+#[derive(Clone, Debug, PartialEq)]
+pub struct ExplainResult {
+    index: Index,
+    selector: serde_json::Value,
+    options: serde_json::Value,
+    limit: Option<u64>,
+    skip: Option<u64>,
+    fields: Option<Vec<String>>,
+    range: Option<serde_json::Value>,
+}
+
+impl ExplainResult {
+    /// Borrows the index the server would use to execute the query.
+    pub fn index(&self) -> &Index {
+        &self.index
+    }
+
+    /// Borrows the query's selector, as normalized by the server.
+    pub fn selector(&self) -> &serde_json::Value {
+        &self.selector
+    }
+
+    /// Borrows the query's options, as interpreted by the server.
+    pub fn options(&self) -> &serde_json::Value {
+        &self.options
+    }
+
+    /// Returns the maximum number of documents the query would return, if
+    /// available.
+    pub fn limit(&self) -> Option<u64> {
+        self.limit
+    }
+
+    /// Returns the number of matching documents the query would skip, if
+    /// available.
+    pub fn skip(&self) -> Option<u64> {
+        self.skip
+    }
+
+    /// Borrows the fields the query would return, or returns `None` if the
+    /// query would return all fields.
+    pub fn fields(&self) -> Option<&Vec<String>> {
+        self.fields.as_ref()
+    }
+
+    /// Borrows the range of index keys the query would scan, if available.
+    pub fn range(&self) -> Option<&serde_json::Value> {
+        self.range.as_ref()
+    }
+}
+
+impl serde::Deserialize for ExplainResult {
+    fn deserialize<D>(deserializer: &mut D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer,
+    {
+        enum Field {
+            Fields,
+            Ignored,
+            Index,
+            Limit,
+            Opts,
+            Range,
+            Selector,
+            Skip,
+        }
+
+        impl serde::Deserialize for Field {
+            fn deserialize<D>(deserializer: &mut D) -> Result<Field, D::Error>
+            where
+                D: serde::Deserializer,
+            {
+                struct Visitor;
+
+                impl serde::de::Visitor for Visitor {
+                    type Value = Field;
+
+                    fn visit_str<E>(&mut self, value: &str) -> Result<Field, E>
+                    where
+                        E: serde::de::Error,
+                    {
+                        match value {
+                            "fields" => Ok(Field::Fields),
+                            "index" => Ok(Field::Index),
+                            "limit" => Ok(Field::Limit),
+                            "opts" => Ok(Field::Opts),
+                            "range" => Ok(Field::Range),
+                            "selector" => Ok(Field::Selector),
+                            "skip" => Ok(Field::Skip),
+                            _ => Ok(Field::Ignored),
+                        }
+                    }
+                }
+
+                deserializer.deserialize(Visitor)
+            }
+        }
+
+        struct Visitor;
+
+        impl serde::de::Visitor for Visitor {
+            type Value = ExplainResult;
+
+            fn visit_map<V>(&mut self, mut visitor: V) -> Result<Self::Value, V::Error>
+            where
+                V: serde::de::MapVisitor,
+            {
+                let mut fields = None;
+                let mut index = None;
+                let mut limit = None;
+                let mut opts = None;
+                let mut range = None;
+                let mut selector = None;
+                let mut skip = None;
+
+                loop {
+                    match try!(visitor.visit_key()) {
+                        // CouchDB encodes "all fields" as the string
+                        // "all_fields" instead of as a list of fields.
+                        Some(Field::Fields) => {
+                            fields = match try!(visitor.visit_value::<serde_json::Value>()) {
+                                serde_json::Value::Array(x) => Some(
+                                    x.into_iter()
+                                        .filter_map(|x| match x {
+                                            serde_json::Value::String(x) => Some(x),
+                                            _ => None,
+                                        })
+                                        .collect(),
+                                ),
+                                _ => None,
+                            };
+                        }
+                        Some(Field::Ignored) => {
+                            try!(visitor.visit_value::<serde_json::Value>());
+                        }
+                        Some(Field::Index) => {
+                            index = Some(try!(visitor.visit_value()));
+                        }
+                        Some(Field::Limit) => {
+                            limit = Some(try!(visitor.visit_value()));
+                        }
+                        Some(Field::Opts) => {
+                            opts = Some(try!(visitor.visit_value()));
+                        }
+                        Some(Field::Range) => {
+                            range = Some(try!(visitor.visit_value()));
+                        }
+                        Some(Field::Selector) => {
+                            selector = Some(try!(visitor.visit_value()));
+                        }
+                        Some(Field::Skip) => {
+                            skip = Some(try!(visitor.visit_value()));
+                        }
+                        None => {
+                            break;
+                        }
+                    }
+                }
+
+                try!(visitor.end());
+
+                Ok(ExplainResult {
+                    index: match index {
+                        Some(x) => x,
+                        None => try!(visitor.missing_field("index")),
+                    },
+                    selector: match selector {
+                        Some(x) => x,
+                        None => try!(visitor.missing_field("selector")),
+                    },
+                    options: opts.unwrap_or(serde_json::Value::Null),
+                    limit: limit,
+                    skip: skip,
+                    fields: fields,
+                    range: range,
+                })
+            }
+        }
+
+        static FIELDS: &'static [&'static str] = &["fields", "index", "limit", "opts", "range", "selector", "skip"];
+        deserializer.deserialize_struct("ExplainResult", FIELDS, Visitor)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use {DatabaseName, Revision, serde_json};
+    use document::DocumentBuilder;
+
+    #[test]
+    fn selector_new_is_empty_object() {
+        let expected = serde_json::builder::ObjectBuilder::new().build();
+        assert_eq!(expected, serde_json::to_value(&Selector::new()));
+    }
+
+    #[test]
+    fn selector_field_operators() {
+
+        let selector = Selector::new()
+            .eq("alpha", 1)
+            .ne("bravo", 2)
+            .gt("charlie", 3)
+            .gte("delta", 4)
+            .lt("echo", 5)
+            .lte("foxtrot", 6)
+            .exists("golf", true)
+            .in_("hotel", vec![7, 8])
+            .nin("india", vec![9, 10])
+            .all("juliett", vec!["a", "b"])
+            .size("kilo", 11)
+            .regex("lima", "^m")
+            .elem_match("mike", Selector::new().eq("november", 12))
+            .all_match("oscar", Selector::new().gt("papa", 13));
+
+        let expected = serde_json::builder::ObjectBuilder::new()
+            .insert_object("alpha", |x| x.insert("$eq", 1))
+            .insert_object("bravo", |x| x.insert("$ne", 2))
+            .insert_object("charlie", |x| x.insert("$gt", 3))
+            .insert_object("delta", |x| x.insert("$gte", 4))
+            .insert_object("echo", |x| x.insert("$lt", 5))
+            .insert_object("foxtrot", |x| x.insert("$lte", 6))
+            .insert_object("golf", |x| x.insert("$exists", true))
+            .insert_object("hotel", |x| x.insert_array("$in", |x| x.push(7).push(8)))
+            .insert_object("india", |x| x.insert_array("$nin", |x| x.push(9).push(10)))
+            .insert_object("juliett", |x| x.insert_array("$all", |x| x.push("a").push("b")))
+            .insert_object("kilo", |x| x.insert("$size", 11))
+            .insert_object("lima", |x| x.insert("$regex", "^m"))
+            .insert_object("mike", |x| {
+                x.insert_object("$elemMatch", |x| x.insert_object("november", |x| x.insert("$eq", 12)))
+            })
+            .insert_object("oscar", |x| {
+                x.insert_object("$allMatch", |x| x.insert_object("papa", |x| x.insert("$gt", 13)))
+            })
+            .build();
+
+        assert_eq!(expected, serde_json::to_value(&selector));
+    }
+
+    #[test]
+    fn selector_multiple_operators_on_same_field() {
+
+        let selector = Selector::new().gte("home_runs", 600).lt("home_runs", 700);
+
+        let expected = serde_json::builder::ObjectBuilder::new()
+            .insert_object("home_runs", |x| x.insert("$gte", 600).insert("$lt", 700))
+            .build();
+
+        assert_eq!(expected, serde_json::to_value(&selector));
+    }
+
+    #[test]
+    fn selector_combination_operators() {
+
+        let selector = Selector::new()
+            .and(vec![Selector::new().eq("alpha", 1), Selector::new().eq("bravo", 2)])
+            .or(vec![Selector::new().eq("charlie", 3)])
+            .nor(vec![Selector::new().eq("delta", 4)])
+            .not(Selector::new().eq("echo", 5));
+
+        let expected = serde_json::builder::ObjectBuilder::new()
+            .insert_array("$and", |x| {
+                x.push_object(|x| x.insert_object("alpha", |x| x.insert("$eq", 1)))
+                    .push_object(|x| x.insert_object("bravo", |x| x.insert("$eq", 2)))
+            })
+            .insert_array("$or", |x| {
+                x.push_object(|x| x.insert_object("charlie", |x| x.insert("$eq", 3)))
+            })
+            .insert_array("$nor", |x| {
+                x.push_object(|x| x.insert_object("delta", |x| x.insert("$eq", 4)))
+            })
+            .insert_object("$not", |x| x.insert_object("echo", |x| x.insert("$eq", 5)))
+            .build();
+
+        assert_eq!(expected, serde_json::to_value(&selector));
+    }
+
+    #[test]
+    fn find_response_deserialize_ok_minimal() {
+
+        let source = r#"{"docs":[{"_id":"babe_ruth","_rev":"1-1234567890abcdef1234567890abcdef",
+            "name":"Babe Ruth"}]}"#;
+
+        let decoded: FindResponseJsonable = serde_json::from_str(source).unwrap();
+        let got = FindResponse::new_from_decoded(DatabaseName::from("baseball"), decoded);
+
+        let expected_doc = DocumentBuilder::new(
+            "/baseball/babe_ruth",
+            Revision::parse("1-1234567890abcdef1234567890abcdef").unwrap(),
+        ).build_content(|x| x.insert("name", "Babe Ruth"))
+            .unwrap();
+
+        assert_eq!(&vec![expected_doc], got.documents());
+        assert_eq!(None, got.bookmark());
+        assert_eq!(None, got.warning());
+        assert_eq!(None, got.execution_stats());
+    }
+
+    #[test]
+    fn find_response_deserialize_ok_full() {
+
+        let source = r#"{"docs":[],"bookmark":"nil","warning":"no matching index found",
+            "execution_stats":{"total_keys_examined":1,"total_docs_examined":2,
+            "total_quorum_docs_examined":3,"results_returned":4,"execution_time_ms":5.5},
+            "unknown_field":true}"#;
+
+        let decoded: FindResponseJsonable = serde_json::from_str(source).unwrap();
+        let got = FindResponse::new_from_decoded(DatabaseName::from("baseball"), decoded);
+
+        assert!(got.documents().is_empty());
+        assert_eq!(Some("nil"), got.bookmark());
+        assert_eq!(Some("no matching index found"), got.warning());
+
+        let stats = got.execution_stats().unwrap();
+        assert_eq!(1, stats.total_keys_examined());
+        assert_eq!(2, stats.total_docs_examined());
+        assert_eq!(3, stats.total_quorum_docs_examined());
+        assert_eq!(4, stats.results_returned());
+        assert_eq!(5.5, stats.execution_time_ms());
+    }
+
+    #[test]
+    fn find_response_deserialize_nok_missing_docs() {
+        let source = r#"{"bookmark":"nil"}"#;
+        let got = serde_json::from_str::<FindResponseJsonable>(source);
+        expect_json_error_missing_field!(got, "docs");
+    }
+
+    #[test]
+    fn selector_deserialize_ok() {
+        let source = r#"{"home_runs":{"$gte":500}}"#;
+        let got: Selector = serde_json::from_str(source).unwrap();
+        assert_eq!(Selector::new().gte("home_runs", 500), got);
+    }
+
+    #[test]
+    fn index_definition_serialize_without_partial_filter_selector() {
+
+        let definition = IndexDefinition::new()
+            .with_field("name")
+            .with_sort_field("home_runs", SortOrder::Descending);
+
+        let expected = serde_json::builder::ObjectBuilder::new()
+            .insert_array("fields", |x| {
+                x.push_object(|x| x.insert("name", "asc"))
+                    .push_object(|x| x.insert("home_runs", "desc"))
+            })
+            .build();
+
+        assert_eq!(expected, serde_json::to_value(&definition));
+    }
+
+    #[test]
+    fn index_definition_deserialize_ok_mixed_fields() {
+
+        let source = r#"{"fields":["name",{"home_runs":"desc"}],"partial_filter_selector":{"retired":{"$exists":false}},
+            "unknown_field":true}"#;
+
+        let expected = IndexDefinition::new()
+            .with_field("name")
+            .with_sort_field("home_runs", SortOrder::Descending)
+            .with_partial_filter_selector(Selector::new().exists("retired", false));
+
+        let got: IndexDefinition = serde_json::from_str(source).unwrap();
+        assert_eq!(expected, got);
+    }
+
+    #[test]
+    fn index_definition_deserialize_nok_bad_sort_order() {
+        let source = r#"{"fields":[{"home_runs":"sideways"}]}"#;
+        let got = serde_json::from_str::<IndexDefinition>(source);
+        expect_json_error_invalid_value!(got);
+    }
+
+    #[test]
+    fn index_definition_deserialize_nok_missing_fields() {
+        let source = r#"{}"#;
+        let got = serde_json::from_str::<IndexDefinition>(source);
+        expect_json_error_missing_field!(got, "fields");
+    }
+
+    #[test]
+    fn index_creation_deserialize_nok_bad_result() {
+        let source = r#"{"result":"bogus","id":"_design/stats","name":"by_name"}"#;
+        let got = serde_json::from_str::<IndexCreation>(source);
+        expect_json_error_invalid_value!(got);
+    }
+
+    #[test]
+    fn index_deserialize_nok_missing_def() {
+        let source = r#"{"ddoc":null,"name":"_all_docs","type":"special"}"#;
+        let got = serde_json::from_str::<Index>(source);
+        expect_json_error_missing_field!(got, "def");
+    }
+
+    #[test]
+    fn explain_result_deserialize_nok_missing_index() {
+        let source = r#"{"selector":{}}"#;
+        let got = serde_json::from_str::<ExplainResult>(source);
+        expect_json_error_missing_field!(got, "index");
+    }
+}
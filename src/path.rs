@@ -358,8 +358,12 @@ macro_rules! define_name_type {
 define_name_type!(AttachmentName, att_name, /** an attachment */);
 define_name_type!(DatabaseName, db_name, /** a database */);
 define_name_type!(DesignDocumentName, ddoc_name, /** a design document */);
+define_name_type!(FilterName, filter_name, /** a filter function */);
+define_name_type!(ListName, list_name, /** a list function */);
 define_name_type!(LocalDocumentName, ldoc_name, /** a local document */);
 define_name_type!(NormalDocumentName, ndoc_name, /** a normal document */);
+define_name_type!(ShowName, show_name, /** a show function */);
+define_name_type!(UpdateName, update_name, /** an update handler */);
 define_name_type!(ViewName, view_name, /** a view */);
 
 #[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
@@ -409,7 +413,8 @@ impl DocumentId {
         }
     }
 
-    fn percent_encoded(&self) -> String {
+    #[doc(hidden)]
+    pub fn percent_encoded(&self) -> String {
         let name_part = percent_encode(self.name_as_str());
         match self.prefix() {
             None => format!("{}", name_part),
@@ -1,7 +1,6 @@
-use Error;
-use serde;
-use std;
-use uuid;
+use {Attachment, Document, Error};
+use {base64, md5, serde, serde_json, std, uuid};
+use term::{OrderedJson, Term};
 
 /// A document revision, which uniquely identifies a version of a document.
 ///
@@ -13,6 +12,9 @@ use uuid;
 /// In serialized form, a revision looks like
 /// `1-9c65296036141e575d32ba9c034dd3ee`.
 ///
+/// Revisions are ordered the same way the CouchDB server orders them: first by
+/// sequence number and then by digest.
+///
 #[derive(Clone, Debug, Eq, Hash, PartialEq)]
 pub struct Revision {
     sequence_number: u64,
@@ -37,6 +39,152 @@ impl Revision {
     pub fn sequence_number(&self) -> u64 {
         self.sequence_number
     }
+
+    /// Computes the revision that the CouchDB server would assign when
+    /// writing the given content as a child of the given parent revision.
+    ///
+    /// The `compute` method follows the CouchDB server's algorithm: the
+    /// digest is an MD5 hash of the deleted flag, the parent revision, and
+    /// the content, encoded in Erlang's external term format. Thus, the result
+    /// is deterministic, and it matches the revision the server would generate
+    /// for a document without attachments. Top-level content fields beginning
+    /// with an underscore, such as `_id`, are ignored, as the server ignores
+    /// them.
+    ///
+    /// The server hashes an object's fields in the order it receives them, so
+    /// Chill hashes the content's fields in the order they're encoded—i.e.,
+    /// the order in which Chill sends them when writing the content. The
+    /// result is an error if the content fails to encode as JSON.
+    ///
+    /// This method is useful for writing documents with explicit revisions,
+    /// such as when restoring a backup.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// extern crate chill;
+    /// extern crate serde_json;
+    ///
+    /// let content = serde_json::builder::ObjectBuilder::new().build();
+    /// let rev = chill::Revision::compute(None, &content, false).unwrap();
+    /// assert_eq!("1-967a00dff5e02add41819138abb3284d", rev.to_string());
+    /// ```
+    ///
+    pub fn compute<C>(parent: Option<&Revision>, content: &C, deleted: bool) -> Result<Self, Error>
+    where
+        C: serde::Serialize,
+    {
+        let content = try!(encode_ordered(content));
+        Ok(Revision::compute_with_attachments(parent, &content, deleted, Vec::new()))
+    }
+
+    /// Computes the revision that the CouchDB server would assign when
+    /// updating the given document—i.e., when writing the document's content
+    /// and attachments as a child of the document's revision.
+    ///
+    /// The result is `None` if any of the document's attachments has a digest
+    /// other than an MD5 digest, in which case the CouchDB server generates a
+    /// random revision. Otherwise, the result matches the revision the server
+    /// would generate, provided the server stores each attachment's content
+    /// type exactly as Chill formats it.
+    ///
+    pub fn compute_for_document(doc: &Document) -> Option<Self> {
+
+        let mut attachments = Vec::new();
+        for (att_path, att) in doc.attachments() {
+            let digest = match att {
+                &Attachment::Saved(ref inner) => {
+                    match parse_md5_digest(inner.digest()) {
+                        None => return None,
+                        Some(x) => x,
+                    }
+                }
+                &Attachment::Unsaved(..) => md5::compute(att.content().unwrap()).0.to_vec(),
+            };
+            attachments.push((
+                String::from(att_path.attachment_name().clone()),
+                att.content_type().to_string(),
+                digest,
+            ));
+        }
+
+        // The server receives attachments ordered by name, because Chill
+        // encodes them that way, and it reverses their order when computing
+        // the digest.
+        attachments.sort_by(|a, b| b.0.cmp(&a.0));
+
+        let content = encode_ordered(&doc.get_content::<serde_json::Value>().unwrap()).unwrap();
+        Some(Revision::compute_with_attachments(
+            Some(doc.revision()),
+            &content,
+            doc.is_deleted(),
+            attachments,
+        ))
+    }
+
+    fn compute_with_attachments(
+        parent: Option<&Revision>,
+        content: &OrderedJson,
+        deleted: bool,
+        attachments: Vec<(String, String, Vec<u8>)>,
+    ) -> Self {
+
+        let body = match content {
+            &OrderedJson::Object(ref fields) => {
+                Term::from_fields(fields.iter().filter(|&&(ref k, _)| !k.starts_with('_')))
+            }
+            _ => content.to_term(),
+        };
+
+        let (parent_sequence_number, parent_digest) = match parent {
+            None => (0, Term::Integer(0)),
+            Some(x) => (x.sequence_number, Term::Binary(x.digest.as_bytes())),
+        };
+
+        let attachments = attachments
+            .iter()
+            .map(|&(ref name, ref content_type, ref digest)| {
+                Term::Tuple(vec![
+                    Term::Binary(name.as_bytes()),
+                    Term::Binary(content_type.as_bytes()),
+                    Term::Binary(digest),
+                ])
+            })
+            .collect();
+
+        let term = Term::List(vec![
+            Term::Atom(if deleted { "true" } else { "false" }),
+            Term::UnsignedInteger(parent_sequence_number),
+            parent_digest,
+            body,
+            Term::List(attachments),
+        ]);
+
+        let digest = md5::compute(term.encode());
+
+        Revision {
+            sequence_number: parent_sequence_number + 1,
+            digest: uuid::Uuid::from_bytes(&digest.0).unwrap(),
+        }
+    }
+}
+
+// Encodes the content as JSON, the same way Chill does when sending it to the
+// server, and decodes it back with its object fields in the encoded order.
+fn encode_ordered<C: serde::Serialize>(content: &C) -> Result<OrderedJson, Error> {
+    let encoded = try!(serde_json::to_vec(content).map_err(|e| Error::JsonEncode { cause: e }));
+    serde_json::from_slice(&encoded).map_err(|e| Error::JsonDecode { cause: e })
+}
+
+// Decodes an attachment digest of the form "md5-<base64>" into its bytes.
+fn parse_md5_digest(digest: &str) -> Option<Vec<u8>> {
+    if !digest.starts_with("md5-") {
+        return None;
+    }
+    match base64::decode(&digest[4..]) {
+        Ok(ref x) if x.len() == 16 => Some(x.clone()),
+        _ => None,
+    }
 }
 
 impl std::fmt::Display for Revision {
@@ -89,6 +237,18 @@ impl std::str::FromStr for Revision {
     }
 }
 
+impl PartialOrd for Revision {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Revision {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        (self.sequence_number, self.digest.as_bytes()).cmp(&(other.sequence_number, other.digest.as_bytes()))
+    }
+}
+
 impl From<Revision> for String {
     fn from(revision: Revision) -> Self {
         revision.to_string()
@@ -128,12 +288,336 @@ impl serde::Deserialize for Revision {
     }
 }
 
+/// A local document revision.
+///
+/// Local documents—i.e., documents whose id begins with `_local/`—don't
+/// replicate and have no revision history. Instead of a digest, the CouchDB
+/// server gives a local document a revision of the form `0-N`, where `N`
+/// increments by one each time the document is written. Because `Revision`
+/// rejects such revisions, Chill represents them with this separate type.
+///
+/// Local revisions are ordered by their sequence number.
+///
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct LocalRevision {
+    sequence_number: u64,
+}
+
+impl LocalRevision {
+    /// Constructs a new `LocalRevision` from the given string.
+    ///
+    /// The string must be of the form `0-42`.
+    ///
+    pub fn parse(s: &str) -> Result<Self, Error> {
+        use std::str::FromStr;
+        LocalRevision::from_str(s)
+    }
+
+    /// Returns the sequence number part of the local revision.
+    ///
+    /// The sequence number is the `42` part of the local revision `0-42`.
+    ///
+    pub fn sequence_number(&self) -> u64 {
+        self.sequence_number
+    }
+}
+
+impl std::fmt::Display for LocalRevision {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "0-{}", self.sequence_number)
+    }
+}
+
+impl std::str::FromStr for LocalRevision {
+    type Err = Error;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+
+        use error::RevisionParseErrorKind;
+
+        let mut parts = s.splitn(2, '-');
+
+        match parts.next() {
+            Some("0") => (),
+            _ => {
+                return Err(Error::RevisionParse {
+                    kind: RevisionParseErrorKind::NonzeroLocalPrefix,
+                });
+            }
+        }
+
+        let sequence_number_str = try!(parts.next().ok_or(Error::RevisionParse {
+            kind: RevisionParseErrorKind::TooFewParts,
+        }));
+
+        let sequence_number = try!(u64::from_str_radix(sequence_number_str, 10).map_err(|e| {
+            Error::RevisionParse { kind: RevisionParseErrorKind::NumberParse(e) }
+        }));
+
+        Ok(LocalRevision { sequence_number: sequence_number })
+    }
+}
+
+impl serde::Serialize for LocalRevision {
+    fn serialize<S>(&self, serializer: &mut S) -> Result<(), S::Error>
+    where
+        S: serde::Serializer,
+    {
+        let s = self.to_string();
+        serializer.serialize_str(&s)
+    }
+}
+
+impl serde::Deserialize for LocalRevision {
+    fn deserialize<D>(deserializer: &mut D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer,
+    {
+        struct Visitor;
+
+        impl serde::de::Visitor for Visitor {
+            type Value = LocalRevision;
+
+            fn visit_str<E>(&mut self, v: &str) -> Result<Self::Value, E>
+            where
+                E: serde::de::Error,
+            {
+                use std::error::Error;
+                LocalRevision::parse(v).map_err(|e| E::invalid_value(e.description()))
+            }
+        }
+
+        deserializer.deserialize(Visitor)
+    }
+}
+
+/// The availability of a revision of a document, as reported by the CouchDB
+/// server.
+///
+/// A `RevisionStatus` is part of a document's revision information—see the
+/// `Document::revisions_info` method.
+///
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum RevisionStatus {
+    /// The revision's content is available.
+    Available,
+
+    /// The revision's content is no longer available—e.g., due to
+    /// compaction.
+    Missing,
+
+    /// The revision marks when the document was deleted.
+    Deleted,
+}
+
+impl serde::Deserialize for RevisionStatus {
+    fn deserialize<D>(deserializer: &mut D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer,
+    {
+        struct Visitor;
+
+        impl serde::de::Visitor for Visitor {
+            type Value = RevisionStatus;
+
+            fn visit_str<E>(&mut self, v: &str) -> Result<Self::Value, E>
+            where
+                E: serde::de::Error,
+            {
+                match v {
+                    "available" => Ok(RevisionStatus::Available),
+                    "missing" => Ok(RevisionStatus::Missing),
+                    "deleted" => Ok(RevisionStatus::Deleted),
+                    _ => Err(E::invalid_value("Unknown revision status")),
+                }
+            }
+        }
+
+        deserializer.deserialize(Visitor)
+    }
+}
+
+/// Models the relationships between revisions of a single document.
+///
+/// A `RevisionTree` is built from revision histories—e.g., from documents read
+/// with the `ReadDocument::with_revisions` method, such as each leaf returned
+/// by the `ReadOpenRevisions` action with revisions enabled. Each history
+/// links a revision to its ancestors. Histories may be partial because the
+/// CouchDB server forgets old revisions, in which case the tree knows nothing
+/// about the missing ancestors.
+///
+/// # Examples
+///
+/// ```
+/// extern crate chill;
+///
+/// let rev = |s| chill::Revision::parse(s).unwrap();
+///
+/// let mut tree = chill::RevisionTree::new();
+/// tree.insert_path(&[rev("2-bbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbb"), rev("1-aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa")], false);
+/// tree.insert_path(&[rev("2-cccccccccccccccccccccccccccccccc"), rev("1-aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa")], false);
+///
+/// assert_eq!(2, tree.leaves().len());
+/// assert_eq!(Some(&rev("2-cccccccccccccccccccccccccccccccc")), tree.winner());
+/// assert_eq!(Some(&rev("1-aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa")),
+///            tree.common_ancestor(&rev("2-bbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbb"),
+///                                 &rev("2-cccccccccccccccccccccccccccccccc")));
+/// ```
+///
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct RevisionTree {
+    // Maps each known revision to its parent, if the parent is known.
+    parents: std::collections::HashMap<Revision, Option<Revision>>,
+    deleted: std::collections::HashSet<Revision>,
+}
+
+impl RevisionTree {
+    /// Constructs an empty revision tree.
+    pub fn new() -> Self {
+        RevisionTree::default()
+    }
+
+    /// Inserts a revision history into the tree.
+    ///
+    /// The history is ordered newest first, as returned by the
+    /// `Document::revisions` method, and `deleted` specifies whether the
+    /// newest revision marks when the document was deleted.
+    ///
+    pub fn insert_path(&mut self, path: &[Revision], deleted: bool) {
+
+        for (i, rev) in path.iter().enumerate() {
+            let parent = path.get(i + 1).cloned();
+            let entry = self.parents.entry(rev.clone()).or_insert(None);
+            if parent.is_some() {
+                *entry = parent;
+            }
+        }
+
+        if let Some(rev) = path.first() {
+            if deleted {
+                self.deleted.insert(rev.clone());
+            } else {
+                self.deleted.remove(rev);
+            }
+        }
+    }
+
+    /// Inserts a document's revision history into the tree.
+    ///
+    /// If the document has no revision history—i.e., it was read without the
+    /// `ReadDocument::with_revisions` method—then only the document's own
+    /// revision is inserted.
+    ///
+    pub fn insert_document(&mut self, doc: &Document) {
+        if doc.revisions().is_empty() {
+            self.insert_path(&[doc.revision().clone()], doc.is_deleted());
+        } else {
+            self.insert_path(doc.revisions(), doc.is_deleted());
+        }
+    }
+
+    /// Returns `true` if and only if the tree contains the given revision.
+    pub fn contains(&self, rev: &Revision) -> bool {
+        self.parents.contains_key(rev)
+    }
+
+    /// Returns the parent of the given revision, if known.
+    pub fn parent(&self, rev: &Revision) -> Option<&Revision> {
+        self.parents.get(rev).and_then(|x| x.as_ref())
+    }
+
+    /// Returns all leaves of the tree—i.e., all revisions having no children—in
+    /// descending order.
+    pub fn leaves(&self) -> Vec<&Revision> {
+        let parents = self.parents
+            .values()
+            .filter_map(|x| x.as_ref())
+            .collect::<std::collections::HashSet<_>>();
+        let mut leaves = self.parents
+            .keys()
+            .filter(|x| !parents.contains(x))
+            .collect::<Vec<_>>();
+        leaves.sort_by(|a, b| b.cmp(a));
+        leaves
+    }
+
+    /// Returns `true` if and only if the given revision is known to mark when
+    /// the document was deleted.
+    pub fn is_deleted(&self, rev: &Revision) -> bool {
+        self.deleted.contains(rev)
+    }
+
+    /// Returns the leaf the CouchDB server picks as the document's winning
+    /// revision, or `None` if the tree is empty.
+    ///
+    /// The server picks the greatest undeleted leaf, or, if all leaves are
+    /// deleted, the greatest deleted leaf.
+    ///
+    pub fn winner(&self) -> Option<&Revision> {
+        self.leaves().into_iter().max_by(|a, b| {
+            (!self.is_deleted(a), a).cmp(&(!self.is_deleted(b), b))
+        })
+    }
+
+    /// Returns `true` if and only if the first revision descends—directly or
+    /// indirectly—from the second revision.
+    ///
+    /// A revision does not descend from itself.
+    ///
+    pub fn is_descendant_of(&self, rev: &Revision, ancestor: &Revision) -> bool {
+        let mut cursor = self.parent(rev);
+        while let Some(x) = cursor {
+            if x == ancestor {
+                return true;
+            }
+            cursor = self.parent(x);
+        }
+        false
+    }
+
+    /// Returns the newest revision from which both given revisions
+    /// descend—or which is one of the given revisions—if the tree knows of
+    /// one.
+    pub fn common_ancestor(&self, a: &Revision, b: &Revision) -> Option<&Revision> {
+
+        if !self.contains(a) || !self.contains(b) {
+            return None;
+        }
+
+        let mut ancestors = std::collections::HashSet::new();
+        let mut cursor = self.parents.get_key_value(a).map(|(k, _)| k);
+        while let Some(x) = cursor {
+            ancestors.insert(x);
+            cursor = self.parent(x);
+        }
+
+        let mut cursor = self.parents.get_key_value(b).map(|(k, _)| k);
+        while let Some(x) = cursor {
+            if ancestors.contains(x) {
+                return Some(x);
+            }
+            cursor = self.parent(x);
+        }
+
+        None
+    }
+}
+
+impl<'a> std::iter::FromIterator<&'a Document> for RevisionTree {
+    fn from_iter<I: IntoIterator<Item = &'a Document>>(iter: I) -> Self {
+        let mut tree = RevisionTree::new();
+        for doc in iter {
+            tree.insert_document(doc);
+        }
+        tree
+    }
+}
+
 #[cfg(test)]
 mod tests {
 
-    use super::Revision;
+    use super::{LocalRevision, Revision, RevisionStatus, RevisionTree};
     use Error;
-    use serde_json;
+    use {serde, serde_json};
 
     #[test]
     fn parse_ok() {
@@ -273,4 +757,311 @@ mod tests {
         let got = serde_json::from_str::<Revision>(&s);
         expect_json_error_invalid_value!(got);
     }
+
+    #[test]
+    fn revision_status_deserialize_ok() {
+        let got: Vec<RevisionStatus> = serde_json::from_str(r#"["available","missing","deleted"]"#).unwrap();
+        assert_eq!(
+            vec![RevisionStatus::Available, RevisionStatus::Missing, RevisionStatus::Deleted],
+            got
+        );
+    }
+
+    #[test]
+    fn revision_status_deserialize_nok_unknown() {
+        let got = serde_json::from_str::<RevisionStatus>(r#""bogus""#);
+        expect_json_error_invalid_value!(got);
+    }
+
+    fn rev(s: &str) -> Revision {
+        Revision::parse(s).unwrap()
+    }
+
+    #[test]
+    fn ord_by_sequence_number_then_digest() {
+        assert!(rev("2-00000000000000000000000000000000") > rev("1-ffffffffffffffffffffffffffffffff"));
+        assert!(rev("10-00000000000000000000000000000000") > rev("9-00000000000000000000000000000000"));
+        assert!(rev("2-abcdef1234567890abcdef1234567890") > rev("2-1234567890abcdef1234567890abcdef"));
+        assert_eq!(
+            std::cmp::Ordering::Equal,
+            rev("2-1234567890abcdef1234567890abcdef").cmp(&rev("2-1234567890abcdef1234567890abcdef"))
+        );
+    }
+
+    // Builds the following tree, in which 3-d is deleted:
+    //
+    //   1-a -- 2-b -- 3-c
+    //      \
+    //       -- 2-e -- 3-d
+    //
+    fn make_tree() -> RevisionTree {
+        let mut tree = RevisionTree::new();
+        tree.insert_path(
+            &[
+                rev("3-cccccccccccccccccccccccccccccccc"),
+                rev("2-bbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbb"),
+                rev("1-aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa"),
+            ],
+            false,
+        );
+        tree.insert_path(
+            &[
+                rev("3-dddddddddddddddddddddddddddddddd"),
+                rev("2-eeeeeeeeeeeeeeeeeeeeeeeeeeeeeeee"),
+                rev("1-aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa"),
+            ],
+            true,
+        );
+        tree
+    }
+
+    #[test]
+    fn revision_tree_leaves() {
+        let tree = make_tree();
+        let expected = vec![
+            rev("3-dddddddddddddddddddddddddddddddd"),
+            rev("3-cccccccccccccccccccccccccccccccc"),
+        ];
+        let got = tree.leaves().into_iter().cloned().collect::<Vec<_>>();
+        assert_eq!(expected, got);
+    }
+
+    #[test]
+    fn revision_tree_winner_prefers_undeleted_leaf() {
+        let tree = make_tree();
+        assert_eq!(Some(&rev("3-cccccccccccccccccccccccccccccccc")), tree.winner());
+    }
+
+    #[test]
+    fn revision_tree_winner_all_deleted() {
+        let mut tree = make_tree();
+        tree.insert_path(&[rev("3-cccccccccccccccccccccccccccccccc")], true);
+        assert_eq!(Some(&rev("3-dddddddddddddddddddddddddddddddd")), tree.winner());
+    }
+
+    #[test]
+    fn revision_tree_winner_empty() {
+        assert_eq!(None, RevisionTree::new().winner());
+    }
+
+    #[test]
+    fn revision_tree_is_descendant_of() {
+        let tree = make_tree();
+        let a = rev("1-aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa");
+        let b = rev("2-bbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbb");
+        let c = rev("3-cccccccccccccccccccccccccccccccc");
+        let d = rev("3-dddddddddddddddddddddddddddddddd");
+        assert!(tree.is_descendant_of(&c, &a));
+        assert!(tree.is_descendant_of(&c, &b));
+        assert!(!tree.is_descendant_of(&c, &c));
+        assert!(!tree.is_descendant_of(&d, &b));
+        assert!(!tree.is_descendant_of(&a, &c));
+    }
+
+    #[test]
+    fn revision_tree_common_ancestor() {
+        let tree = make_tree();
+        let a = rev("1-aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa");
+        let b = rev("2-bbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbb");
+        let c = rev("3-cccccccccccccccccccccccccccccccc");
+        let d = rev("3-dddddddddddddddddddddddddddddddd");
+        assert_eq!(Some(&a), tree.common_ancestor(&c, &d));
+        assert_eq!(Some(&b), tree.common_ancestor(&b, &c));
+        assert_eq!(None, tree.common_ancestor(&c, &rev("1-ffffffffffffffffffffffffffffffff")));
+    }
+
+    #[test]
+    fn revision_tree_common_ancestor_unknown_due_to_stemming() {
+        let mut tree = RevisionTree::new();
+        tree.insert_path(
+            &[rev("5-cccccccccccccccccccccccccccccccc"), rev("4-bbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbb")],
+            false,
+        );
+        tree.insert_path(
+            &[rev("5-dddddddddddddddddddddddddddddddd"), rev("4-eeeeeeeeeeeeeeeeeeeeeeeeeeeeeeee")],
+            false,
+        );
+        assert_eq!(
+            None,
+            tree.common_ancestor(&rev("5-cccccccccccccccccccccccccccccccc"), &rev("5-dddddddddddddddddddddddddddddddd"))
+        );
+    }
+
+    #[test]
+    fn revision_tree_from_documents() {
+
+        use document::DocumentBuilder;
+
+        let docs = vec![
+            DocumentBuilder::new("/foo/bar", rev("2-bbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbb")).unwrap(),
+            DocumentBuilder::new("/foo/bar", rev("2-cccccccccccccccccccccccccccccccc")).unwrap(),
+        ];
+
+        let tree = docs.iter().collect::<RevisionTree>();
+        assert!(tree.contains(&rev("2-bbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbb")));
+        assert_eq!(Some(&rev("2-cccccccccccccccccccccccccccccccc")), tree.winner());
+    }
+
+    #[test]
+    fn compute_ok_new_empty_document() {
+        let content = serde_json::builder::ObjectBuilder::new().build();
+        let got = Revision::compute(None, &content, false).unwrap();
+        assert_eq!(rev("1-967a00dff5e02add41819138abb3284d"), got);
+    }
+
+    #[test]
+    fn compute_ok_deleted_empty_document() {
+        let content = serde_json::builder::ObjectBuilder::new().build();
+        let parent = rev("1-967a00dff5e02add41819138abb3284d");
+        let got = Revision::compute(Some(&parent), &content, true).unwrap();
+        assert_eq!(rev("2-eec205a9d413992850a6e32678485900"), got);
+    }
+
+    #[test]
+    fn compute_ignores_underscore_fields() {
+        let content = serde_json::builder::ObjectBuilder::new()
+            .insert("_id", "foo")
+            .insert("_rev", "1-967a00dff5e02add41819138abb3284d")
+            .build();
+        let got = Revision::compute(None, &content, false).unwrap();
+        assert_eq!(rev("1-967a00dff5e02add41819138abb3284d"), got);
+    }
+
+    #[test]
+    fn compute_is_deterministic_and_content_sensitive() {
+        let content_1 = serde_json::builder::ObjectBuilder::new()
+            .insert("name", "Babe Ruth")
+            .build();
+        let content_2 = serde_json::builder::ObjectBuilder::new()
+            .insert("name", "Lou Gehrig")
+            .build();
+        let a = Revision::compute(None, &content_1, false).unwrap();
+        let b = Revision::compute(None, &content_1, false).unwrap();
+        let c = Revision::compute(None, &content_2, false).unwrap();
+        assert_eq!(a, b);
+        assert!(a != c);
+    }
+
+    #[test]
+    fn compute_hashes_fields_in_encoded_order() {
+
+        // The fields are declared in non-alphabetical order, which
+        // serde_json::Value would sort.
+
+        struct Player {
+            name: &'static str,
+            home_runs: u64,
+        }
+
+        impl serde::Serialize for Player {
+            fn serialize<S>(&self, serializer: &mut S) -> Result<(), S::Error>
+            where
+                S: serde::Serializer,
+            {
+                let mut state = try!(serializer.serialize_struct("Player", 2));
+                try!(serializer.serialize_struct_elt(&mut state, "name", self.name));
+                try!(serializer.serialize_struct_elt(&mut state, "home_runs", self.home_runs));
+                serializer.serialize_struct_end(state)
+            }
+        }
+
+        let content = Player {
+            name: "Babe Ruth",
+            home_runs: 714,
+        };
+
+        let sent = serde_json::from_str(r#"{"name":"Babe Ruth","home_runs":714}"#).unwrap();
+        let expected = Revision::compute_with_attachments(None, &sent, false, Vec::new());
+        let got = Revision::compute(None, &content, false).unwrap();
+        assert_eq!(expected, got);
+
+        let sorted = Revision::compute(None, &serde_json::to_value(&content), false).unwrap();
+        assert!(sorted != got);
+    }
+
+    #[test]
+    fn compute_for_document_ok() {
+
+        use document::DocumentBuilder;
+
+        let doc = DocumentBuilder::new("/foo/bar", rev("1-967a00dff5e02add41819138abb3284d")).unwrap();
+        let got = Revision::compute_for_document(&doc).unwrap();
+        let expected = Revision::compute(Some(&rev("1-967a00dff5e02add41819138abb3284d")), &doc, false).unwrap();
+        assert_eq!(expected, got);
+        assert_eq!(2, got.sequence_number());
+    }
+
+    #[test]
+    fn compute_for_document_includes_attachments() {
+
+        use document::DocumentBuilder;
+
+        let parent = rev("1-967a00dff5e02add41819138abb3284d");
+        let doc = DocumentBuilder::new("/foo/bar", parent.clone()).unwrap();
+        let mut doc_with_attachment = doc.clone();
+        doc_with_attachment.insert_attachment("foo.txt", mime!(Text / Plain), b"hello".to_vec());
+
+        let without = Revision::compute_for_document(&doc).unwrap();
+        let with = Revision::compute_for_document(&doc_with_attachment).unwrap();
+        assert!(without != with);
+    }
+
+    #[test]
+    fn parse_md5_digest_ok() {
+        let expected = vec![
+            0x88, 0xc6, 0xa2, 0x0b, 0xcc, 0x2a, 0x88, 0x59, 0x43, 0xd8, 0xd8, 0xcb, 0x4d, 0xe9, 0xaf, 0x09
+        ];
+        assert_eq!(Some(expected), super::parse_md5_digest("md5-iMaiC8wqiFlD2NjLTemvCQ=="));
+        assert_eq!(None, super::parse_md5_digest("sha-iMaiC8wqiFlD2NjLTemvCQ=="));
+        assert_eq!(None, super::parse_md5_digest("md5-AAAA"));
+    }
+
+    #[test]
+    fn local_revision_parse_ok() {
+        let expected = LocalRevision { sequence_number: 42 };
+        let got = LocalRevision::parse("0-42").unwrap();
+        assert_eq!(expected, got);
+    }
+
+    #[test]
+    fn local_revision_parse_nok() {
+        LocalRevision::parse("").unwrap_err();
+        LocalRevision::parse("0").unwrap_err();
+        LocalRevision::parse("0-").unwrap_err();
+        LocalRevision::parse("0-bad").unwrap_err();
+        LocalRevision::parse("1-42").unwrap_err();
+        LocalRevision::parse("42-1234567890abcdef1234567890abcdef").unwrap_err();
+    }
+
+    #[test]
+    fn local_revision_display() {
+        let rev = LocalRevision { sequence_number: 42 };
+        assert_eq!("0-42", rev.to_string());
+    }
+
+    #[test]
+    fn local_revision_ordering() {
+        assert!(LocalRevision::parse("0-2").unwrap() < LocalRevision::parse("0-10").unwrap());
+    }
+
+    #[test]
+    fn local_revision_serialize() {
+        let rev = LocalRevision { sequence_number: 42 };
+        let expected = serde_json::Value::String(String::from("0-42"));
+        let got = serde_json::to_value(&rev);
+        assert_eq!(expected, got);
+    }
+
+    #[test]
+    fn local_revision_deserialize_ok() {
+        let expected = LocalRevision { sequence_number: 42 };
+        let got = serde_json::from_str(r#""0-42""#).unwrap();
+        assert_eq!(expected, got);
+    }
+
+    #[test]
+    fn local_revision_deserialize_nok() {
+        let got = serde_json::from_str::<LocalRevision>(r#""1-42""#);
+        expect_json_error_invalid_value!(got);
+    }
 }
@@ -0,0 +1,466 @@
+use {Error, serde, serde_json, std, uuid};
+
+/// Version of a CouchDB server, comprising major, minor, and patch numbers.
+///
+/// A `ServerVersion` is ordered by its major number, then minor number, then
+/// patch number. This lets an application compare the server's version to a
+/// known version to decide which behavior to use—e.g., to use Mango queries
+/// only with CouchDB 2.0 or later.
+///
+/// # Examples
+///
+/// ```
+/// use chill::ServerVersion;
+///
+/// let v = ServerVersion::parse("2.1.0").unwrap();
+/// assert_eq!(2, v.major());
+/// assert!(v >= ServerVersion::new(2, 0, 0));
+/// assert!(v < ServerVersion::new(3, 0, 0));
+/// ```
+///
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct ServerVersion {
+    major: u32,
+    minor: u32,
+    patch: u32,
+}
+
+impl ServerVersion {
+    /// Constructs a `ServerVersion` from its parts.
+    pub fn new(major: u32, minor: u32, patch: u32) -> Self {
+        ServerVersion {
+            major: major,
+            minor: minor,
+            patch: patch,
+        }
+    }
+
+    /// Constructs a `ServerVersion` from the given string.
+    ///
+    /// The string must begin with a major number and may continue with a minor
+    /// number and a patch number, separated by dots—e.g., `2`, `2.1`, or
+    /// `2.1.0`. A missing number is zero. Any suffix after a number—e.g., the
+    /// `-RC1` in `2.0.0-RC1`—is ignored.
+    ///
+    pub fn parse(s: &str) -> Result<Self, Error> {
+        use std::str::FromStr;
+        ServerVersion::from_str(s)
+    }
+
+    /// Returns the major number—e.g., the `2` in `2.1.0`.
+    pub fn major(&self) -> u32 {
+        self.major
+    }
+
+    /// Returns the minor number—e.g., the `1` in `2.1.0`.
+    pub fn minor(&self) -> u32 {
+        self.minor
+    }
+
+    /// Returns the patch number—e.g., the `0` in `2.1.0`.
+    pub fn patch(&self) -> u32 {
+        self.patch
+    }
+}
+
+impl std::fmt::Display for ServerVersion {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}.{}.{}", self.major, self.minor, self.patch)
+    }
+}
+
+impl std::str::FromStr for ServerVersion {
+    type Err = Error;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+
+        fn leading_number(s: &str) -> Option<u32> {
+            let end = s.find(|c: char| !c.is_digit(10)).unwrap_or(s.len());
+            u32::from_str_radix(&s[..end], 10).ok()
+        }
+
+        let mut parts = s.splitn(3, '.');
+
+        let major = match parts.next().and_then(leading_number) {
+            Some(x) => x,
+            None => return Err(Error::ServerVersionParse(String::from(s))),
+        };
+
+        let minor = parts.next().and_then(leading_number).unwrap_or(0);
+        let patch = parts.next().and_then(leading_number).unwrap_or(0);
+
+        Ok(ServerVersion::new(major, minor, patch))
+    }
+}
+
+impl serde::Deserialize for ServerVersion {
+    fn deserialize<D>(deserializer: &mut D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer,
+    {
+        struct Visitor;
+
+        impl serde::de::Visitor for Visitor {
+            type Value = ServerVersion;
+
+            fn visit_str<E>(&mut self, v: &str) -> Result<Self::Value, E>
+            where
+                E: serde::de::Error,
+            {
+                use std::error::Error;
+                ServerVersion::parse(v).map_err(|e| E::invalid_value(e.description()))
+            }
+        }
+
+        deserializer.deserialize(Visitor)
+    }
+}
+
+/// Vendor of a CouchDB server.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct Vendor {
+    name: String,
+    version: Option<String>,
+}
+
+impl Vendor {
+    /// Returns the vendor's name—e.g., `The Apache Software Foundation`.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns the vendor's version, if available.
+    ///
+    /// CouchDB 1.x reports a vendor version, whereas later versions do not.
+    ///
+    pub fn version(&self) -> Option<&str> {
+        self.version.as_ref().map(|x| x.as_str())
+    }
+}
+
+impl serde::Deserialize for Vendor {
+    fn deserialize<D>(deserializer: &mut D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer,
+    {
+        enum Field {
+            Ignored,
+            Name,
+            Version,
+        }
+
+        impl serde::Deserialize for Field {
+            fn deserialize<D>(deserializer: &mut D) -> Result<Field, D::Error>
+            where
+                D: serde::Deserializer,
+            {
+                struct Visitor;
+
+                impl serde::de::Visitor for Visitor {
+                    type Value = Field;
+
+                    fn visit_str<E>(&mut self, value: &str) -> Result<Field, E>
+                    where
+                        E: serde::de::Error,
+                    {
+                        match value {
+                            "name" => Ok(Field::Name),
+                            "version" => Ok(Field::Version),
+                            _ => Ok(Field::Ignored),
+                        }
+                    }
+                }
+
+                deserializer.deserialize(Visitor)
+            }
+        }
+
+        struct Visitor;
+
+        impl serde::de::Visitor for Visitor {
+            type Value = Vendor;
+
+            fn visit_map<V>(&mut self, mut visitor: V) -> Result<Self::Value, V::Error>
+            where
+                V: serde::de::MapVisitor,
+            {
+                let mut name = None;
+                let mut version = None;
+
+                loop {
+                    match try!(visitor.visit_key()) {
+                        Some(Field::Ignored) => {
+                            try!(visitor.visit_value::<serde_json::Value>());
+                        }
+                        Some(Field::Name) => {
+                            name = Some(try!(visitor.visit_value()));
+                        }
+                        Some(Field::Version) => {
+                            version = Some(try!(visitor.visit_value()));
+                        }
+                        None => {
+                            break;
+                        }
+                    }
+                }
+
+                try!(visitor.end());
+
+                Ok(Vendor {
+                    name: match name {
+                        Some(x) => x,
+                        None => try!(visitor.missing_field("name")),
+                    },
+                    version: version,
+                })
+            }
+        }
+
+        static FIELDS: &'static [&'static str] = &["name", "version"];
+        deserializer.deserialize_struct("Vendor", FIELDS, Visitor)
+    }
+}
+
+/// Information about a CouchDB server, as returned by the server's root
+/// resource.
+///
+/// Fields that only some versions of CouchDB report—e.g., the server's
+/// UUID—are optional.
+///
+#[derive(Clone, Debug, PartialEq)]
+pub struct ServerInfo {
+    couchdb: String,
+    version: ServerVersion,
+    vendor: Option<Vendor>,
+    uuid: Option<uuid::Uuid>,
+    features: Vec<String>,
+}
+
+impl ServerInfo {
+    /// Returns the server's welcome message, which is usually `Welcome`.
+    pub fn couchdb(&self) -> &str {
+        &self.couchdb
+    }
+
+    /// Returns the server's version.
+    pub fn version(&self) -> ServerVersion {
+        self.version
+    }
+
+    /// Borrows the server's vendor, if available.
+    pub fn vendor(&self) -> Option<&Vendor> {
+        self.vendor.as_ref()
+    }
+
+    /// Returns the server's UUID, if available.
+    pub fn uuid(&self) -> Option<&uuid::Uuid> {
+        self.uuid.as_ref()
+    }
+
+    /// Borrows the server's optional features—e.g., `scheduler`.
+    ///
+    /// CouchDB 1.x reports no features.
+    ///
+    pub fn features(&self) -> &Vec<String> {
+        &self.features
+    }
+}
+
+impl serde::Deserialize for ServerInfo {
+    fn deserialize<D>(deserializer: &mut D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer,
+    {
+        enum Field {
+            Couchdb,
+            Features,
+            Ignored,
+            Uuid,
+            Vendor,
+            Version,
+        }
+
+        impl serde::Deserialize for Field {
+            fn deserialize<D>(deserializer: &mut D) -> Result<Field, D::Error>
+            where
+                D: serde::Deserializer,
+            {
+                struct Visitor;
+
+                impl serde::de::Visitor for Visitor {
+                    type Value = Field;
+
+                    fn visit_str<E>(&mut self, value: &str) -> Result<Field, E>
+                    where
+                        E: serde::de::Error,
+                    {
+                        match value {
+                            "couchdb" => Ok(Field::Couchdb),
+                            "features" => Ok(Field::Features),
+                            "uuid" => Ok(Field::Uuid),
+                            "vendor" => Ok(Field::Vendor),
+                            "version" => Ok(Field::Version),
+                            _ => Ok(Field::Ignored),
+                        }
+                    }
+                }
+
+                deserializer.deserialize(Visitor)
+            }
+        }
+
+        struct Visitor;
+
+        impl serde::de::Visitor for Visitor {
+            type Value = ServerInfo;
+
+            fn visit_map<V>(&mut self, mut visitor: V) -> Result<Self::Value, V::Error>
+            where
+                V: serde::de::MapVisitor,
+            {
+                let mut couchdb = None;
+                let mut features = None;
+                let mut uuid = None;
+                let mut vendor = None;
+                let mut version = None;
+
+                loop {
+                    match try!(visitor.visit_key()) {
+                        Some(Field::Couchdb) => {
+                            couchdb = Some(try!(visitor.visit_value()));
+                        }
+                        Some(Field::Features) => {
+                            features = Some(try!(visitor.visit_value()));
+                        }
+                        Some(Field::Ignored) => {
+                            try!(visitor.visit_value::<serde_json::Value>());
+                        }
+                        Some(Field::Uuid) => {
+                            use serde::de::Error;
+                            let x: String = try!(visitor.visit_value());
+                            uuid = Some(try!(
+                                uuid::Uuid::parse_str(&x).map_err(|_| V::Error::invalid_value("Bad UUID string"))
+                            ));
+                        }
+                        Some(Field::Vendor) => {
+                            vendor = Some(try!(visitor.visit_value()));
+                        }
+                        Some(Field::Version) => {
+                            version = Some(try!(visitor.visit_value()));
+                        }
+                        None => {
+                            break;
+                        }
+                    }
+                }
+
+                try!(visitor.end());
+
+                Ok(ServerInfo {
+                    couchdb: match couchdb {
+                        Some(x) => x,
+                        None => try!(visitor.missing_field("couchdb")),
+                    },
+                    version: match version {
+                        Some(x) => x,
+                        None => try!(visitor.missing_field("version")),
+                    },
+                    vendor: vendor,
+                    uuid: uuid,
+                    features: features.unwrap_or_else(Vec::new),
+                })
+            }
+        }
+
+        static FIELDS: &'static [&'static str] = &["couchdb", "features", "uuid", "vendor", "version"];
+        deserializer.deserialize_struct("ServerInfo", FIELDS, Visitor)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use {Error, serde_json, uuid};
+
+    #[test]
+    fn server_version_parse_ok() {
+        assert_eq!(ServerVersion::new(2, 1, 0), ServerVersion::parse("2.1.0").unwrap());
+        assert_eq!(ServerVersion::new(1, 6, 0), ServerVersion::parse("1.6").unwrap());
+        assert_eq!(ServerVersion::new(3, 0, 0), ServerVersion::parse("3").unwrap());
+        assert_eq!(ServerVersion::new(2, 0, 0), ServerVersion::parse("2.0.0-RC1").unwrap());
+        assert_eq!(ServerVersion::new(3, 2, 2), ServerVersion::parse("3.2.2.3").unwrap());
+    }
+
+    #[test]
+    fn server_version_parse_nok() {
+        match ServerVersion::parse("") {
+            Err(Error::ServerVersionParse(ref x)) if x == "" => (),
+            x @ _ => unexpected_result!(x),
+        }
+        match ServerVersion::parse("v2.1.0") {
+            Err(Error::ServerVersionParse(ref x)) if x == "v2.1.0" => (),
+            x @ _ => unexpected_result!(x),
+        }
+    }
+
+    #[test]
+    fn server_version_ordering() {
+        assert!(ServerVersion::new(1, 6, 1) < ServerVersion::new(2, 0, 0));
+        assert!(ServerVersion::new(2, 0, 0) < ServerVersion::new(2, 1, 0));
+        assert!(ServerVersion::new(2, 1, 0) < ServerVersion::new(2, 1, 1));
+        assert!(ServerVersion::new(1, 10, 0) > ServerVersion::new(1, 9, 0));
+    }
+
+    #[test]
+    fn server_version_display() {
+        assert_eq!("2.1.0", ServerVersion::new(2, 1, 0).to_string());
+    }
+
+    #[test]
+    fn server_info_deserialize_ok_couchdb_1() {
+
+        let source = r#"{"couchdb":"Welcome","uuid":"85fb71bf700c17267fef77535820e371",
+            "version":"1.6.1","vendor":{"name":"The Apache Software Foundation","version":"1.6.1"}}"#;
+
+        let got: ServerInfo = serde_json::from_str(source).unwrap();
+        assert_eq!("Welcome", got.couchdb());
+        assert_eq!(ServerVersion::new(1, 6, 1), got.version());
+        assert_eq!(
+            Some(&uuid::Uuid::parse_str("85fb71bf700c17267fef77535820e371").unwrap()),
+            got.uuid()
+        );
+
+        let vendor = got.vendor().unwrap();
+        assert_eq!("The Apache Software Foundation", vendor.name());
+        assert_eq!(Some("1.6.1"), vendor.version());
+
+        assert!(got.features().is_empty());
+    }
+
+    #[test]
+    fn server_info_deserialize_ok_couchdb_2() {
+
+        let source = r#"{"couchdb":"Welcome","version":"2.1.0","features":["scheduler"],
+            "vendor":{"name":"The Apache Software Foundation"},"git_sha":"7d67ab8"}"#;
+
+        let got: ServerInfo = serde_json::from_str(source).unwrap();
+        assert_eq!(ServerVersion::new(2, 1, 0), got.version());
+        assert_eq!(None, got.uuid());
+        assert_eq!(None, got.vendor().unwrap().version());
+        assert_eq!(&vec![String::from("scheduler")], got.features());
+    }
+
+    #[test]
+    fn server_info_deserialize_nok_missing_version() {
+        let source = r#"{"couchdb":"Welcome"}"#;
+        let got = serde_json::from_str::<ServerInfo>(source);
+        expect_json_error_missing_field!(got, "version");
+    }
+
+    #[test]
+    fn server_info_deserialize_nok_bad_version() {
+        let source = r#"{"couchdb":"Welcome","version":"bogus"}"#;
+        let got = serde_json::from_str::<ServerInfo>(source);
+        expect_json_error_invalid_value!(got);
+    }
+}
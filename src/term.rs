@@ -0,0 +1,324 @@
+// This module encodes values in Erlang's external term format, the same way
+// Erlang's term_to_binary/2 function does with the {minor_version, 1} option.
+// The CouchDB server computes a revision's digest by hashing such an encoding,
+// so Chill needs the same encoding to compute revisions locally.
+//
+// See http://erlang.org/doc/apps/erts/erl_ext_dist.html for the format.
+
+use serde;
+
+const VERSION: u8 = 131;
+const NEW_FLOAT_EXT: u8 = 70;
+const SMALL_INTEGER_EXT: u8 = 97;
+const INTEGER_EXT: u8 = 98;
+const ATOM_EXT: u8 = 100;
+const SMALL_TUPLE_EXT: u8 = 104;
+const NIL_EXT: u8 = 106;
+const LIST_EXT: u8 = 108;
+const BINARY_EXT: u8 = 109;
+const SMALL_BIG_EXT: u8 = 110;
+
+// A Term is the subset of Erlang terms needed for computing revisions.
+#[derive(Debug, PartialEq)]
+pub enum Term<'a> {
+    Atom(&'static str),
+    Binary(&'a [u8]),
+    Float(f64),
+    Integer(i64),
+    UnsignedInteger(u64),
+    List(Vec<Term<'a>>),
+    Tuple(Vec<Term<'a>>),
+}
+
+impl<'a> Term<'a> {
+    pub fn encode(&self) -> Vec<u8> {
+        let mut buf = vec![VERSION];
+        self.encode_into(&mut buf);
+        buf
+    }
+
+    fn encode_into(&self, buf: &mut Vec<u8>) {
+        match self {
+            &Term::Atom(x) => {
+                buf.push(ATOM_EXT);
+                push_u16(buf, x.len() as u16);
+                buf.extend_from_slice(x.as_bytes());
+            }
+            &Term::Binary(x) => {
+                buf.push(BINARY_EXT);
+                push_u32(buf, x.len() as u32);
+                buf.extend_from_slice(x);
+            }
+            &Term::Float(x) => {
+                buf.push(NEW_FLOAT_EXT);
+                push_u64(buf, x.to_bits());
+            }
+            &Term::Integer(x) if 0 <= x && x <= 255 => {
+                buf.push(SMALL_INTEGER_EXT);
+                buf.push(x as u8);
+            }
+            &Term::Integer(x) if -(1 << 31) <= x && x < (1 << 31) => {
+                buf.push(INTEGER_EXT);
+                push_u32(buf, x as i32 as u32);
+            }
+            &Term::Integer(x) => {
+                let magnitude = if x < 0 { (x as i128).abs() as u64 } else { x as u64 };
+                encode_big(buf, x < 0, magnitude);
+            }
+            &Term::UnsignedInteger(x) if x <= i64::max_value() as u64 => {
+                Term::Integer(x as i64).encode_into(buf);
+            }
+            &Term::UnsignedInteger(x) => {
+                encode_big(buf, false, x);
+            }
+            &Term::List(ref x) if x.is_empty() => {
+                buf.push(NIL_EXT);
+            }
+            &Term::List(ref x) => {
+                buf.push(LIST_EXT);
+                push_u32(buf, x.len() as u32);
+                for term in x {
+                    term.encode_into(buf);
+                }
+                buf.push(NIL_EXT);
+            }
+            &Term::Tuple(ref x) => {
+                buf.push(SMALL_TUPLE_EXT);
+                buf.push(x.len() as u8);
+                for term in x {
+                    term.encode_into(buf);
+                }
+            }
+        }
+    }
+}
+
+// An OrderedJson is a JSON value whose objects keep their members in the
+// order they were decoded. Unlike a serde_json::Value, whose objects are
+// sorted by key, it matches the order in which the CouchDB server receives the
+// members--and the server hashes them in that order.
+#[derive(Debug, PartialEq)]
+pub enum OrderedJson {
+    Null,
+    Bool(bool),
+    I64(i64),
+    U64(u64),
+    F64(f64),
+    String(String),
+    Array(Vec<OrderedJson>),
+    Object(Vec<(String, OrderedJson)>),
+}
+
+impl OrderedJson {
+    // Converts the JSON value into a term the same way CouchDB's JSON decoder
+    // does--e.g., an object becomes {[{Key, Value}, ...]}.
+    pub fn to_term(&self) -> Term {
+        match self {
+            &OrderedJson::Null => Term::Atom("null"),
+            &OrderedJson::Bool(true) => Term::Atom("true"),
+            &OrderedJson::Bool(false) => Term::Atom("false"),
+            &OrderedJson::I64(x) => Term::Integer(x),
+            &OrderedJson::U64(x) => Term::UnsignedInteger(x),
+            &OrderedJson::F64(x) => Term::Float(x),
+            &OrderedJson::String(ref x) => Term::Binary(x.as_bytes()),
+            &OrderedJson::Array(ref x) => Term::List(x.iter().map(OrderedJson::to_term).collect()),
+            &OrderedJson::Object(ref x) => Term::from_fields(x.iter()),
+        }
+    }
+}
+
+impl<'a> Term<'a> {
+    pub fn from_fields<I>(fields: I) -> Self
+    where
+        I: Iterator<Item = &'a (String, OrderedJson)>,
+    {
+        Term::Tuple(vec![
+            Term::List(
+                fields
+                    .map(|&(ref k, ref v)| Term::Tuple(vec![Term::Binary(k.as_bytes()), v.to_term()]))
+                    .collect(),
+            ),
+        ])
+    }
+}
+
+impl serde::Deserialize for OrderedJson {
+    fn deserialize<D>(deserializer: &mut D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer,
+    {
+        struct Visitor;
+
+        impl serde::de::Visitor for Visitor {
+            type Value = OrderedJson;
+
+            fn visit_unit<E>(&mut self) -> Result<Self::Value, E>
+            where
+                E: serde::de::Error,
+            {
+                Ok(OrderedJson::Null)
+            }
+
+            fn visit_bool<E>(&mut self, value: bool) -> Result<Self::Value, E>
+            where
+                E: serde::de::Error,
+            {
+                Ok(OrderedJson::Bool(value))
+            }
+
+            fn visit_i64<E>(&mut self, value: i64) -> Result<Self::Value, E>
+            where
+                E: serde::de::Error,
+            {
+                Ok(OrderedJson::I64(value))
+            }
+
+            fn visit_u64<E>(&mut self, value: u64) -> Result<Self::Value, E>
+            where
+                E: serde::de::Error,
+            {
+                Ok(OrderedJson::U64(value))
+            }
+
+            fn visit_f64<E>(&mut self, value: f64) -> Result<Self::Value, E>
+            where
+                E: serde::de::Error,
+            {
+                Ok(OrderedJson::F64(value))
+            }
+
+            fn visit_str<E>(&mut self, value: &str) -> Result<Self::Value, E>
+            where
+                E: serde::de::Error,
+            {
+                Ok(OrderedJson::String(String::from(value)))
+            }
+
+            fn visit_string<E>(&mut self, value: String) -> Result<Self::Value, E>
+            where
+                E: serde::de::Error,
+            {
+                Ok(OrderedJson::String(value))
+            }
+
+            fn visit_seq<V>(&mut self, mut visitor: V) -> Result<Self::Value, V::Error>
+            where
+                V: serde::de::SeqVisitor,
+            {
+                let mut items = Vec::new();
+                while let Some(item) = try!(visitor.visit()) {
+                    items.push(item);
+                }
+                try!(visitor.end());
+                Ok(OrderedJson::Array(items))
+            }
+
+            fn visit_map<V>(&mut self, mut visitor: V) -> Result<Self::Value, V::Error>
+            where
+                V: serde::de::MapVisitor,
+            {
+                let mut fields = Vec::new();
+                while let Some(key) = try!(visitor.visit_key::<String>()) {
+                    let value = try!(visitor.visit_value());
+                    fields.push((key, value));
+                }
+                try!(visitor.end());
+                Ok(OrderedJson::Object(fields))
+            }
+        }
+
+        deserializer.deserialize(Visitor)
+    }
+}
+
+fn encode_big(buf: &mut Vec<u8>, negative: bool, magnitude: u64) {
+    let mut digits = Vec::new();
+    let mut rest = magnitude;
+    while rest != 0 {
+        digits.push(rest as u8);
+        rest >>= 8;
+    }
+    buf.push(SMALL_BIG_EXT);
+    buf.push(digits.len() as u8);
+    buf.push(if negative { 1 } else { 0 });
+    buf.extend_from_slice(&digits);
+}
+
+fn push_u16(buf: &mut Vec<u8>, x: u16) {
+    buf.push((x >> 8) as u8);
+    buf.push(x as u8);
+}
+
+fn push_u32(buf: &mut Vec<u8>, x: u32) {
+    push_u16(buf, (x >> 16) as u16);
+    push_u16(buf, x as u16);
+}
+
+fn push_u64(buf: &mut Vec<u8>, x: u64) {
+    push_u32(buf, (x >> 32) as u32);
+    push_u32(buf, x as u32);
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use serde_json;
+
+    #[test]
+    fn encode_atom() {
+        assert_eq!(vec![131, 100, 0, 4, b't', b'r', b'u', b'e'], Term::Atom("true").encode());
+    }
+
+    #[test]
+    fn encode_binary() {
+        assert_eq!(vec![131, 109, 0, 0, 0, 2, b'h', b'i'], Term::Binary(b"hi").encode());
+    }
+
+    #[test]
+    fn encode_float() {
+        assert_eq!(vec![131, 70, 63, 248, 0, 0, 0, 0, 0, 0], Term::Float(1.5).encode());
+    }
+
+    #[test]
+    fn encode_integer() {
+        assert_eq!(vec![131, 97, 0], Term::Integer(0).encode());
+        assert_eq!(vec![131, 97, 255], Term::Integer(255).encode());
+        assert_eq!(vec![131, 98, 0, 0, 1, 0], Term::Integer(256).encode());
+        assert_eq!(vec![131, 98, 255, 255, 255, 255], Term::Integer(-1).encode());
+        assert_eq!(vec![131, 110, 5, 0, 0, 0, 0, 0, 1], Term::Integer(1 << 32).encode());
+        assert_eq!(vec![131, 110, 4, 1, 1, 0, 0, 128], Term::Integer(-(1 << 31) - 1).encode());
+        assert_eq!(
+            vec![131, 110, 8, 0, 255, 255, 255, 255, 255, 255, 255, 255],
+            Term::UnsignedInteger(u64::max_value()).encode()
+        );
+    }
+
+    #[test]
+    fn encode_list() {
+        assert_eq!(vec![131, 106], Term::List(vec![]).encode());
+        assert_eq!(
+            vec![131, 108, 0, 0, 0, 2, 97, 1, 97, 2, 106],
+            Term::List(vec![Term::Integer(1), Term::Integer(2)]).encode()
+        );
+    }
+
+    #[test]
+    fn encode_json_object() {
+        let value = OrderedJson::Object(vec![(String::from("a"), OrderedJson::Null)]);
+        let expected = vec![
+            131, 104, 1, 108, 0, 0, 0, 1, 104, 2, 109, 0, 0, 0, 1, b'a', 100, 0, 4, b'n', b'u', b'l', b'l', 106
+        ];
+        assert_eq!(expected, value.to_term().encode());
+    }
+
+    #[test]
+    fn ordered_json_deserialize_preserves_member_order() {
+        let expected = OrderedJson::Object(vec![
+            (String::from("b"), OrderedJson::U64(1)),
+            (String::from("a"), OrderedJson::Array(vec![OrderedJson::Bool(true), OrderedJson::Null])),
+        ]);
+        let got = serde_json::from_str(r#"{"b":1,"a":[true,null]}"#).unwrap();
+        assert_eq!(expected, got);
+    }
+}
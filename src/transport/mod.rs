@@ -3,8 +3,8 @@ mod testing;
 
 
 #[cfg(test)]
-pub use self::testing::{JsonResponseBuilder, MockTransport};
-use {Error, hyper, serde, serde_json, std, url};
+pub use self::testing::{JsonResponseBuilder, MockTransport, RawResponseBuilder};
+use {Error, hyper, mime, serde, serde_json, std, url};
 use error::TransportErrorKind;
 pub use hyper::method::Method;
 pub use hyper::status::StatusCode;
@@ -59,6 +59,17 @@ impl Request {
         Ok(self)
     }
 
+    pub fn with_destination<D: Into<String>>(mut self, destination: D) -> Self {
+        self.headers.set_raw("Destination", vec![destination.into().into_bytes()]);
+        self
+    }
+
+    pub fn with_content(mut self, content_type: mime::Mime, content: Vec<u8>) -> Self {
+        self.headers.set(hyper::header::ContentType(content_type));
+        self.body = content;
+        self
+    }
+
     pub fn with_query<K, V>(mut self, key: K, value: &V) -> Self
     where
         K: AsQueryKey,
@@ -83,7 +94,6 @@ impl Request {
         Ok(self)
     }
 
-    #[cfg(test)]
     pub fn with_query_literal<K, V>(mut self, key: K, value: V) -> Self
     where
         K: AsRef<str>,
@@ -177,6 +187,76 @@ where
     }
 }
 
+pub trait RawResponseHandler<T> {
+    fn handle_raw_response(self, response: RawResponse) -> Result<T, Error>;
+}
+
+impl<F, T> RawResponseHandler<T> for F
+where
+    F: FnOnce(RawResponse) -> Result<T, Error>,
+{
+    fn handle_raw_response(self, response: RawResponse) -> Result<T, Error> {
+        self(response)
+    }
+}
+
+// A RawResponseDecoder is like a JsonResponseDecoder except that it accepts
+// content of any type. This is for responses generated by design document
+// functions, which may return HTML, CSV, etc.
+pub struct RawResponseDecoder<H, T>
+where
+    H: RawResponseHandler<T>,
+{
+    handler: H,
+    status_code: StatusCode,
+    headers: ResponseHeaders,
+    content: Vec<u8>,
+    _phantom: std::marker::PhantomData<T>,
+}
+
+impl<H, T> RawResponseDecoder<H, T>
+where
+    H: RawResponseHandler<T>,
+{
+    pub fn new(handler: H) -> Self {
+        RawResponseDecoder {
+            handler: handler,
+            status_code: StatusCode::Ok,
+            headers: ResponseHeaders::new(),
+            content: Vec::new(),
+            _phantom: std::marker::PhantomData,
+        }
+    }
+}
+
+impl<H, T> ResponseHandler<T> for RawResponseDecoder<H, T>
+where
+    H: RawResponseHandler<T>,
+{
+    fn handle_response_status_and_headers(
+        &mut self,
+        status_code: StatusCode,
+        headers: ResponseHeaders,
+    ) -> Result<(), Error> {
+        self.status_code = status_code;
+        self.headers = headers;
+        Ok(())
+    }
+
+    fn handle_response_content(&mut self, content: Vec<u8>) -> Result<(), Error> {
+        self.content.extend_from_slice(&content);
+        Ok(())
+    }
+
+    fn handle_response_eof(mut self) -> Result<T, Error> {
+        self.handler.handle_raw_response(RawResponse {
+            status_code: self.status_code,
+            headers: std::mem::replace(&mut self.headers, ResponseHeaders::new()),
+            content: std::mem::replace(&mut self.content, Vec::new()),
+        })
+    }
+}
+
 pub struct ResponseHeaders {
     headers: hyper::header::Headers,
 }
@@ -186,6 +266,18 @@ impl ResponseHeaders {
         ResponseHeaders { headers: hyper::header::Headers::new() }
     }
 
+    pub fn get_raw(&self, name: &str) -> Option<&[u8]> {
+        self.headers.get_raw(name).and_then(|x| x.first()).map(|x| &x[..])
+    }
+
+    pub fn content_type(&self) -> Option<mime::Mime> {
+        self.headers.get::<hyper::header::ContentType>().map(|x| x.0.clone())
+    }
+
+    pub fn content_length(&self) -> Option<u64> {
+        self.headers.get::<hyper::header::ContentLength>().map(|x| x.0)
+    }
+
     fn extract_content_type_as_json(&mut self) -> Result<(), Error> {
 
         use hyper::header::ContentType;
@@ -233,6 +325,77 @@ impl JsonResponse {
     }
 }
 
+pub struct RawResponse {
+    status_code: StatusCode,
+    headers: ResponseHeaders,
+    content: Vec<u8>,
+}
+
+impl RawResponse {
+    pub fn status_code(&self) -> StatusCode {
+        self.status_code
+    }
+
+    pub fn headers(&self) -> &ResponseHeaders {
+        &self.headers
+    }
+
+    pub fn into_content(self) -> Vec<u8> {
+        self.content
+    }
+
+    // Returns a copy of the response as a JSON response, if the response's
+    // content type is JSON.
+    pub fn to_json_response(&self) -> Option<JsonResponse> {
+        let mut headers = ResponseHeaders::from(self.headers.headers.clone());
+        match headers.extract_content_type_as_json() {
+            Ok(()) => Some(JsonResponse {
+                status_code: self.status_code,
+                headers: headers,
+                content: self.content.clone(),
+            }),
+            Err(_) => None,
+        }
+    }
+}
+
+// A StreamingResponse holds the response content as an unread stream, for
+// responses that may be too large to hold in memory all at once.
+pub struct StreamingResponse {
+    status_code: StatusCode,
+    headers: ResponseHeaders,
+    content: Box<std::io::Read>,
+}
+
+impl StreamingResponse {
+    pub fn status_code(&self) -> StatusCode {
+        self.status_code
+    }
+
+    pub fn headers(&self) -> &ResponseHeaders {
+        &self.headers
+    }
+
+    pub fn into_content(self) -> Box<std::io::Read> {
+        self.content
+    }
+
+    pub fn into_raw_response(mut self) -> Result<RawResponse, Error> {
+        let mut content = Vec::new();
+        try!(self.content.read_to_end(&mut content).map_err(|e| {
+            Error::Io {
+                cause: e,
+                description: "Failed to read response from server",
+            }
+        }));
+        Ok(RawResponse {
+            status_code: self.status_code,
+            headers: self.headers,
+            content: content,
+        })
+    }
+}
+
 pub trait Transport {
     fn send<H, T>(&self, request: Request, response_handler: H) -> Result<T, Error>
     where
@@ -241,6 +404,7 @@ pub trait Transport {
     where
         A: AsyncActionHandler<T>,
         H: ResponseHandler<U>;
+    fn send_streaming(&self, request: Request) -> Result<StreamingResponse, Error>;
 
     fn make_base_url(&self) -> url::Url;
 
@@ -260,6 +424,14 @@ pub trait Transport {
         Request::new(method, url)
     }
 
+    fn copy<P>(&self, path_segments: P) -> Request
+    where
+        P: IntoIterator,
+        P::Item: AsRef<str>,
+    {
+        self.request(hyper::method::Method::Extension(String::from("COPY")), path_segments)
+    }
+
     fn delete<P>(&self, path_segments: P) -> Request
     where
         P: IntoIterator,
@@ -276,6 +448,14 @@ pub trait Transport {
         self.request(hyper::method::Method::Get, path_segments)
     }
 
+    fn head<P>(&self, path_segments: P) -> Request
+    where
+        P: IntoIterator,
+        P::Item: AsRef<str>,
+    {
+        self.request(hyper::method::Method::Head, path_segments)
+    }
+
     fn post<P>(&self, path_segments: P) -> Request
     where
         P: IntoIterator,
@@ -321,26 +501,31 @@ impl HyperTransport {
     }
 }
 
+impl HyperTransport {
+    fn send_request(&self, request: Request) -> Result<hyper::client::Response, Error> {
+
+        let requester = self.hyper_client
+            .request(request.method, request.url)
+            .headers(request.headers);
+
+        let requester = if request.body.is_empty() {
+            requester
+        } else {
+            requester.body(&request.body[..])
+        };
+
+        requester.send().map_err(|e| {
+            Error::Transport { kind: TransportErrorKind::Hyper(e) }
+        })
+    }
+}
+
 impl Transport for HyperTransport {
     fn send<H, T>(&self, request: Request, mut response_handler: H) -> Result<T, Error>
     where
         H: ResponseHandler<T>,
     {
-        let mut response = {
-            let requester = self.hyper_client
-                .request(request.method, request.url)
-                .headers(request.headers);
-
-            let requester = if request.body.is_empty() {
-                requester
-            } else {
-                requester.body(&request.body[..])
-            };
-
-            try!(requester.send().map_err(|e| {
-                Error::Transport { kind: TransportErrorKind::Hyper(e) }
-            }))
-        };
+        let mut response = try!(self.send_request(request));
 
         let headers = std::mem::replace(&mut response.headers, hyper::header::Headers::new());
         let headers = ResponseHeaders::from(headers);
@@ -369,6 +554,16 @@ impl Transport for HyperTransport {
         unimplemented!();
     }
 
+    fn send_streaming(&self, request: Request) -> Result<StreamingResponse, Error> {
+        let mut response = try!(self.send_request(request));
+        let headers = std::mem::replace(&mut response.headers, hyper::header::Headers::new());
+        Ok(StreamingResponse {
+            status_code: response.status,
+            headers: ResponseHeaders::from(headers),
+            content: Box::new(response),
+        })
+    }
+
     fn make_base_url(&self) -> url::Url {
         self.server_base_url.clone()
     }
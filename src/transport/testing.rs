@@ -1,6 +1,7 @@
 
-use super::{AsyncActionHandler, JsonResponse, Request, ResponseHandler, ResponseHeaders, StatusCode, Transport};
-use {Error, url};
+use super::{AsyncActionHandler, JsonResponse, RawResponse, Request, ResponseHandler, ResponseHeaders, StatusCode,
+            StreamingResponse, Transport};
+use {Error, hyper, mime, std, url};
 
 pub struct JsonResponseBuilder {
     inner: JsonResponse,
@@ -27,6 +28,47 @@ impl JsonResponseBuilder {
     }
 }
 
+pub struct RawResponseBuilder {
+    inner: RawResponse,
+}
+
+impl RawResponseBuilder {
+    pub fn new(status_code: StatusCode) -> Self {
+        RawResponseBuilder {
+            inner: RawResponse {
+                status_code: status_code,
+                headers: ResponseHeaders::new(),
+                content: Vec::new(),
+            },
+        }
+    }
+
+    pub fn unwrap(self) -> RawResponse {
+        self.inner
+    }
+
+    pub fn unwrap_streaming(self) -> StreamingResponse {
+        StreamingResponse {
+            status_code: self.inner.status_code,
+            headers: self.inner.headers,
+            content: Box::new(std::io::Cursor::new(self.inner.content)),
+        }
+    }
+
+    pub fn with_header<H: hyper::header::Header + hyper::header::HeaderFormat>(mut self, header: H) -> Self {
+        self.inner.headers.headers.set(header);
+        self
+    }
+
+    pub fn with_content<C: Into<Vec<u8>>>(mut self, content_type: mime::Mime, content: C) -> Self {
+        self.inner.headers.headers.set(
+            hyper::header::ContentType(content_type),
+        );
+        self.inner.content = content.into();
+        self
+    }
+}
+
 pub struct MockTransport;
 
 impl MockTransport {
@@ -51,6 +93,10 @@ impl Transport for MockTransport {
         unimplemented!();
     }
 
+    fn send_streaming(&self, _request: Request) -> Result<StreamingResponse, Error> {
+        unimplemented!();
+    }
+
     fn make_base_url(&self) -> url::Url {
         url::Url::parse("http://example.com:5984").unwrap()
     }
@@ -0,0 +1,214 @@
+use {Error, action, serde, serde_json, std};
+use transport::Transport;
+
+/// Cache of server-generated UUIDs, for use as document ids.
+///
+/// A `UuidPool` reads UUIDs from the CouchDB server in batches and hands them
+/// out one at a time, reading another batch only when it runs out.
+///
+/// When a client has a `UuidPool`—via the `Client::with_uuid_pool`
+/// method—the `CreateDocument` action assigns each new document an id from the
+/// pool unless the application sets the id explicitly. The action then creates
+/// the document via `PUT` instead of `POST`. The action takes the id from the
+/// pool when it runs or, beforehand, when the application calls the
+/// `CreateDocument::assign_document_id` method. With the id known beforehand,
+/// after a network failure, the application may safely retry with the same id,
+/// via `CreateDocument::with_document_id`: the retry either creates the
+/// document or fails with `Error::DocumentConflict` because the document
+/// already exists.
+///
+/// Note that running a second action built by `Client::create_document` takes
+/// a _new_ id from the pool, so such a retry would not be safe.
+///
+/// A pool may be shared between threads. It doesn't hold its lock while
+/// reading UUIDs from the server, so threads that find the pool empty at the
+/// same time may each read a batch.
+///
+/// # Examples
+///
+/// ```no_run
+/// extern crate chill;
+/// extern crate serde_json;
+///
+/// let client = chill::Client::new("http://localhost:5984")
+///                  .unwrap()
+///                  .with_uuid_pool(chill::UuidPool::new(100));
+///
+/// let content = serde_json::builder::ObjectBuilder::new()
+///                   .insert("name", "Babe Ruth")
+///                   .build();
+///
+/// // Sent via PUT, with an id taken from the pool.
+/// let mut action = client.create_document("/baseball", &content);
+/// let doc_id = action.assign_document_id().unwrap().unwrap();
+///
+/// match action.run() {
+///     Ok(..) => (),
+///     Err(chill::Error::Transport { .. }) => {
+///         // Retry with the same id.
+///         client.create_document("/baseball", &content)
+///               .with_document_id(doc_id)
+///               .run()
+///               .unwrap();
+///     }
+///     Err(e) => panic!("Failed to create document: {}", e),
+/// }
+/// ```
+///
+#[derive(Debug)]
+pub struct UuidPool {
+    batch_size: u64,
+    uuids: std::sync::Mutex<std::collections::VecDeque<String>>,
+}
+
+impl UuidPool {
+    /// Constructs an empty pool that reads UUIDs from the server in batches of
+    /// the given size.
+    ///
+    /// A batch size of zero is treated as one.
+    ///
+    pub fn new(batch_size: u64) -> Self {
+        UuidPool {
+            batch_size: std::cmp::max(batch_size, 1),
+            uuids: std::sync::Mutex::new(std::collections::VecDeque::new()),
+        }
+    }
+
+    /// Returns the number of UUIDs the pool reads from the server at a time.
+    pub fn batch_size(&self) -> u64 {
+        self.batch_size
+    }
+
+    #[doc(hidden)]
+    pub fn next_uuid<T: Transport>(&self, transport: &T) -> Result<String, Error> {
+
+        if let Some(uuid) = self.lock_uuids().pop_front() {
+            return Ok(uuid);
+        }
+
+        // Don't hold the lock while reading from the server, so that other
+        // threads may keep taking UUIDs, e.g., from a batch read concurrently.
+
+        let mut batch = try!(action::ReadUuids::new(transport, self.batch_size).run()).into_iter();
+        let uuid = try!(batch.next().ok_or(Error::UnexpectedResponse("The server returned no UUIDs")));
+        self.lock_uuids().extend(batch);
+        Ok(uuid)
+    }
+
+    // A poisoned lock still holds valid UUIDs because each change to the
+    // queue is a single step.
+    fn lock_uuids(&self) -> std::sync::MutexGuard<std::collections::VecDeque<String>> {
+        self.uuids.lock().unwrap_or_else(std::sync::PoisonError::into_inner)
+    }
+}
+
+// UuidsJsonable is an intermediate type for decoding the list of UUIDs
+// returned by the server.
+#[doc(hidden)]
+#[derive(Debug, PartialEq)]
+pub struct UuidsJsonable {
+    uuids: Vec<String>,
+}
+
+impl UuidsJsonable {
+    #[doc(hidden)]
+    pub fn into_uuids(self) -> Vec<String> {
+        self.uuids
+    }
+}
+
+impl serde::Deserialize for UuidsJsonable {
+    fn deserialize<D>(deserializer: &mut D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer,
+    {
+        enum Field {
+            Ignored,
+            Uuids,
+        }
+
+        impl serde::Deserialize for Field {
+            fn deserialize<D>(deserializer: &mut D) -> Result<Field, D::Error>
+            where
+                D: serde::Deserializer,
+            {
+                struct Visitor;
+
+                impl serde::de::Visitor for Visitor {
+                    type Value = Field;
+
+                    fn visit_str<E>(&mut self, value: &str) -> Result<Field, E>
+                    where
+                        E: serde::de::Error,
+                    {
+                        match value {
+                            "uuids" => Ok(Field::Uuids),
+                            _ => Ok(Field::Ignored),
+                        }
+                    }
+                }
+
+                deserializer.deserialize(Visitor)
+            }
+        }
+
+        struct Visitor;
+
+        impl serde::de::Visitor for Visitor {
+            type Value = UuidsJsonable;
+
+            fn visit_map<V>(&mut self, mut visitor: V) -> Result<Self::Value, V::Error>
+            where
+                V: serde::de::MapVisitor,
+            {
+                let mut uuids = None;
+
+                loop {
+                    match try!(visitor.visit_key()) {
+                        Some(Field::Ignored) => {
+                            try!(visitor.visit_value::<serde_json::Value>());
+                        }
+                        Some(Field::Uuids) => {
+                            uuids = Some(try!(visitor.visit_value()));
+                        }
+                        None => {
+                            break;
+                        }
+                    }
+                }
+
+                try!(visitor.end());
+
+                Ok(UuidsJsonable {
+                    uuids: match uuids {
+                        Some(x) => x,
+                        None => try!(visitor.missing_field("uuids")),
+                    },
+                })
+            }
+        }
+
+        static FIELDS: &'static [&'static str] = &["uuids"];
+        deserializer.deserialize_struct("UuidsJsonable", FIELDS, Visitor)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use serde_json;
+
+    #[test]
+    fn uuid_pool_new_clamps_batch_size() {
+        assert_eq!(1, UuidPool::new(0).batch_size());
+        assert_eq!(100, UuidPool::new(100).batch_size());
+    }
+
+    #[test]
+    fn uuids_deserialize_nok_missing_uuids() {
+        let source = r#"{}"#;
+        let got = serde_json::from_str::<UuidsJsonable>(source);
+        expect_json_error_missing_field!(got, "uuids");
+    }
+}
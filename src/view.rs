@@ -136,6 +136,166 @@ impl ViewRow {
     }
 }
 
+/// Contains the result of executing a view, with each row's key, value, and
+/// document already decoded into Rust types.
+///
+/// A `TypedViewResponse` is the result of the `ExecuteView::run_typed` method.
+/// Unlike a `ViewResponse`, whose rows decode their keys and values on each
+/// access, a `TypedViewResponse` decodes all rows once, when the response
+/// arrives. If any row fails to decode then the whole action fails with an
+/// `Error::ViewRowDecode` error that identifies the offending row's document,
+/// if available.
+///
+#[derive(Clone, Debug, PartialEq)]
+pub struct TypedViewResponse<K, V, D> {
+    total_rows: Option<u64>,
+    offset: Option<u64>,
+    rows: Vec<TypedViewRow<K, V, D>>,
+    update_seq: Option<u64>,
+}
+
+impl<K, V, D> TypedViewResponse<K, V, D>
+where
+    D: serde::Deserialize,
+    K: serde::Deserialize,
+    V: serde::Deserialize,
+{
+    #[doc(hidden)]
+    pub fn new_from_decoded(db_name: DatabaseName, decoded: ViewResponseJsonable) -> Result<Self, Error> {
+
+        let mut rows = Vec::with_capacity(decoded.rows.len());
+        for x in decoded.rows.into_iter() {
+            rows.push(try!(TypedViewRow::new_from_decoded(db_name.clone(), x)));
+        }
+
+        Ok(TypedViewResponse {
+            total_rows: decoded.total_rows,
+            offset: decoded.offset,
+            rows: rows,
+            update_seq: decoded.update_seq,
+        })
+    }
+}
+
+impl<K, V, D> TypedViewResponse<K, V, D> {
+    /// Returns how many rows are in the view, including rows excluded in the
+    /// response, if available.
+    ///
+    /// The total number of rows is available if and only if the view is
+    /// unreduced.
+    ///
+    pub fn total_rows(&self) -> Option<u64> {
+        self.total_rows
+    }
+
+    /// Returns how many rows are excluded from the view response that are
+    /// ordered before the first row in the response, if available.
+    ///
+    /// The offset is available if and only if the view is unreduced.
+    ///
+    pub fn offset(&self) -> Option<u64> {
+        self.offset
+    }
+
+    /// Returns the update sequence number that the view reflects, if available.
+    pub fn update_sequence_number(&self) -> Option<u64> {
+        self.update_seq
+    }
+
+    /// Returns the vector containing all rows in the view response.
+    pub fn rows(&self) -> &Vec<TypedViewRow<K, V, D>> {
+        &self.rows
+    }
+
+    /// Converts the view response into its rows.
+    pub fn into_rows(self) -> Vec<TypedViewRow<K, V, D>> {
+        self.rows
+    }
+}
+
+/// Contains a single row of a `TypedViewResponse`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct TypedViewRow<K, V, D> {
+    key: Option<K>,
+    value: V,
+    doc_path: Option<DocumentPath>,
+    doc: Option<D>,
+}
+
+impl<K, V, D> TypedViewRow<K, V, D>
+where
+    D: serde::Deserialize,
+    K: serde::Deserialize,
+    V: serde::Deserialize,
+{
+    fn new_from_decoded(db_name: DatabaseName, decoded: ViewRowJsonable) -> Result<Self, Error> {
+
+        let doc_path = decoded.id.map(
+            |doc_id| DocumentPath::from((db_name, doc_id)),
+        );
+
+        let make_error = |e| {
+            Error::ViewRowDecode {
+                doc_path: doc_path.clone(),
+                cause: e,
+            }
+        };
+
+        let key = match decoded.key {
+            None => None,
+            Some(key) => Some(try!(serde_json::from_value(key).map_err(&make_error))),
+        };
+
+        let value = try!(serde_json::from_value(decoded.value).map_err(&make_error));
+
+        let doc = match decoded.doc {
+            None => None,
+            Some(doc) => Some(try!(serde_json::from_value(doc.content).map_err(&make_error))),
+        };
+
+        Ok(TypedViewRow {
+            key: key,
+            value: value,
+            doc_path: doc_path,
+            doc: doc,
+        })
+    }
+}
+
+impl<K, V, D> TypedViewRow<K, V, D> {
+    /// Returns the row's key, if available.
+    ///
+    /// The key is available if and only if the view is unreduced or if the view
+    /// is reduced but grouped.
+    ///
+    pub fn key(&self) -> Option<&K> {
+        self.key.as_ref()
+    }
+
+    /// Returns the row's value.
+    pub fn value(&self) -> &V {
+        &self.value
+    }
+
+    /// Returns the row's related document path, if available.
+    ///
+    /// The document path is available if and only if the view is unreduced.
+    ///
+    pub fn document_path(&self) -> Option<&DocumentPath> {
+        self.doc_path.as_ref()
+    }
+
+    /// Returns the content of the row's related document, if available.
+    ///
+    /// The document content is available if and only if the view is unreduced
+    /// and the action was modified to include documents—i.e., via the
+    /// `ExecuteView::with_documents` method.
+    ///
+    pub fn document(&self) -> Option<&D> {
+        self.doc.as_ref()
+    }
+}
+
 #[derive(Debug, PartialEq)]
 pub struct ViewResponseJsonable {
     total_rows: Option<u64>,
@@ -479,7 +639,7 @@ mod tests {
 
     use super::*;
     use super::ViewRowJsonable;
-    use {DocumentId, Error, IntoDocumentPath, Revision, serde_json, std};
+    use {DatabaseName, DocumentId, Error, IntoDocumentPath, Revision, serde_json, std};
     use document::JsonDecodableDocument;
 
     #[test]
@@ -558,6 +718,115 @@ mod tests {
         }
     }
 
+    #[test]
+    fn typed_view_row_new_from_decoded_ok() {
+
+        let decoded = ViewRowJsonable {
+            id: Some(DocumentId::from("foo")),
+            key: Some(serde_json::Value::String(String::from("bar"))),
+            value: serde_json::Value::U64(42),
+            doc: Some(JsonDecodableDocument {
+                doc_id: DocumentId::from("foo"),
+                revision: Revision::parse("1-1234567890abcdef1234567890abcdef").unwrap(),
+                deleted: false,
+                conflicts: Vec::new(),
+                deleted_conflicts: Vec::new(),
+                revisions: Vec::new(),
+                revisions_info: Vec::new(),
+                attachments: std::collections::HashMap::new(),
+                content: serde_json::builder::ObjectBuilder::new()
+                    .insert("doc_field", 17)
+                    .build(),
+            }),
+        };
+
+        let expected = TypedViewRow {
+            key: Some(String::from("bar")),
+            value: 42,
+            doc_path: Some("/db/foo".into_document_path().unwrap()),
+            doc: Some(
+                serde_json::builder::ObjectBuilder::new()
+                    .insert("doc_field", 17)
+                    .build(),
+            ),
+        };
+
+        let got = TypedViewRow::<String, u64, serde_json::Value>::new_from_decoded(DatabaseName::from("db"), decoded)
+            .unwrap();
+        assert_eq!(expected, got);
+    }
+
+    #[test]
+    fn typed_view_row_new_from_decoded_ok_reduced() {
+
+        let decoded = ViewRowJsonable {
+            id: None,
+            key: None,
+            value: serde_json::Value::U64(42),
+            doc: None,
+        };
+
+        let expected = TypedViewRow {
+            key: None,
+            value: 42,
+            doc_path: None,
+            doc: None,
+        };
+
+        let got = TypedViewRow::<String, u64, serde_json::Value>::new_from_decoded(DatabaseName::from("db"), decoded)
+            .unwrap();
+        assert_eq!(expected, got);
+    }
+
+    #[test]
+    fn typed_view_row_new_from_decoded_nok_bad_value() {
+
+        let decoded = ViewRowJsonable {
+            id: Some(DocumentId::from("foo")),
+            key: Some(serde_json::Value::String(String::from("bar"))),
+            value: serde_json::Value::U64(42),
+            doc: None,
+        };
+
+        let got = TypedViewRow::<String, String, serde_json::Value>::new_from_decoded(DatabaseName::from("db"), decoded);
+        match got {
+            Err(Error::ViewRowDecode { doc_path: Some(ref doc_path), .. })
+                if *doc_path == "/db/foo".into_document_path().unwrap() => (),
+            x @ _ => unexpected_result!(x),
+        }
+    }
+
+    #[test]
+    fn typed_view_response_new_from_decoded_nok_bad_key() {
+
+        let decoded = ViewResponseJsonable {
+            total_rows: Some(10),
+            offset: Some(5),
+            update_seq: None,
+            rows: vec![
+                ViewRowJsonable {
+                    id: Some(DocumentId::from("foo")),
+                    key: Some(serde_json::Value::U64(1)),
+                    value: serde_json::Value::U64(42),
+                    doc: None,
+                },
+                ViewRowJsonable {
+                    id: Some(DocumentId::from("qux")),
+                    key: Some(serde_json::Value::String(String::from("baz"))),
+                    value: serde_json::Value::U64(17),
+                    doc: None,
+                },
+            ],
+        };
+
+        let got = TypedViewResponse::<u64, u64, serde_json::Value>::new_from_decoded(DatabaseName::from("db"), decoded);
+        match got {
+            Err(Error::ViewRowDecode { doc_path: Some(ref doc_path), .. })
+                if *doc_path == "/db/qux".into_document_path().unwrap() => (),
+            x @ _ => unexpected_result!(x),
+        }
+    }
+
     #[test]
     fn view_row_deserialize_ok_reduced() {
 
@@ -601,6 +870,10 @@ mod tests {
                 doc_id: DocumentId::from("foo"),
                 revision: Revision::parse("1-1234567890abcdef1234567890abcdef").unwrap(),
                 deleted: false,
+                conflicts: Vec::new(),
+                deleted_conflicts: Vec::new(),
+                revisions: Vec::new(),
+                revisions_info: Vec::new(),
                 attachments: std::collections::HashMap::new(),
                 content: serde_json::builder::ObjectBuilder::new()
                     .insert("doc_field", 17)
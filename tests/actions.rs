@@ -143,6 +143,85 @@ fn read_document_ok_with_revision() {
     assert_eq!(up_content, down_content);
 }
 
+#[test]
+fn read_document_ok_with_conflicts() {
+
+    let (_server, client) = make_server_and_client();
+    client.create_database("/baseball").run().unwrap();
+
+    let content = serde_json::builder::ObjectBuilder::new()
+        .insert("name", "Babe Ruth")
+        .build();
+
+    let (doc_id, _rev) = client.create_document("/baseball", &content).run().unwrap();
+
+    let doc = client.read_document(("/baseball", doc_id))
+        .with_conflicts(true)
+        .with_deleted_conflicts(true)
+        .run()
+        .unwrap();
+    assert!(doc.conflicts().is_empty());
+    assert!(doc.deleted_conflicts().is_empty());
+    assert_eq!(content, doc.get_content::<serde_json::Value>().unwrap());
+}
+
+#[test]
+fn read_document_ok_with_open_revisions() {
+
+    let (_server, client) = make_server_and_client();
+    client.create_database("/baseball").run().unwrap();
+
+    let content = serde_json::builder::ObjectBuilder::new()
+        .insert("name", "Babe Ruth")
+        .build();
+
+    let (doc_id, rev) = client.create_document("/baseball", &content).run().unwrap();
+
+    let leaves = client.read_document(("/baseball", doc_id))
+        .with_open_revisions(chill::action::OpenRevs::All)
+        .run()
+        .unwrap();
+
+    assert_eq!(1, leaves.len());
+    match leaves[0] {
+        chill::action::OpenRevision::Found(ref doc) => {
+            assert_eq!(&rev, doc.revision());
+            assert_eq!(content, doc.get_content::<serde_json::Value>().unwrap());
+        }
+        ref x @ _ => panic!("Got unexpected open revision {:?}", x),
+    }
+}
+
+#[test]
+fn read_document_ok_with_revisions() {
+
+    let (_server, client) = make_server_and_client();
+    client.create_database("/baseball").run().unwrap();
+
+    let content = serde_json::builder::ObjectBuilder::new()
+        .insert("name", "Babe Ruth")
+        .build();
+
+    let (doc_id, rev1) = client.create_document("/baseball", &content).run().unwrap();
+    let doc = client.read_document(("/baseball", doc_id.clone())).run().unwrap();
+    let rev2 = client.update_document(&doc).run().unwrap();
+
+    let doc = client.read_document(("/baseball", doc_id))
+        .with_revisions(true)
+        .with_revisions_info(true)
+        .run()
+        .unwrap();
+
+    assert_eq!(&[rev2.clone(), rev1.clone()], doc.revisions());
+    assert_eq!(
+        &[
+            (rev2, chill::RevisionStatus::Available),
+            (rev1, chill::RevisionStatus::Available),
+        ],
+        doc.revisions_info()
+    );
+}
+
 #[test]
 fn read_document_nok_not_found() {
 
@@ -156,6 +235,56 @@ fn read_document_nok_not_found() {
 }
 
 
+#[test]
+fn head_document_ok() {
+
+    let (_server, client) = make_server_and_client();
+    client.create_database("/baseball").run().unwrap();
+
+    let content = serde_json::builder::ObjectBuilder::new()
+        .insert("name", "Babe Ruth")
+        .build();
+
+    let (doc_id, rev) = client.create_document("/baseball", &content).run().unwrap();
+
+    let got = client.head_document(("/baseball", doc_id)).run().unwrap();
+    assert_eq!(Some(rev), got);
+
+    let got = client.head_document("/baseball/nonexistent").run().unwrap();
+    assert_eq!(None, got);
+}
+
+#[test]
+fn head_attachment_ok() {
+
+    let (_server, client) = make_server_and_client();
+    client.create_database("/baseball").run().unwrap();
+
+    let up_content = serde_json::builder::ObjectBuilder::new()
+        .insert("name", "Babe Ruth")
+        .insert_object("_attachments", |x| {
+            x.insert_object("photo.png", |x| {
+                x.insert("content_type", "image/png")
+                    .insert("data",
+                            base64::encode("Pretend this is a PNG file.".as_bytes()))
+            })
+        })
+        .build();
+
+    let (doc_id, _rev) = client.create_document("/baseball", &up_content).run().unwrap();
+
+    let info = client.head_attachment((("/baseball", doc_id.clone()), "photo.png"))
+        .run()
+        .unwrap()
+        .unwrap();
+    assert_eq!(Some(&mime!(Image / Png)), info.content_type());
+    assert_eq!("Pretend this is a PNG file.".len() as u64, info.content_length());
+    assert!(info.digest().unwrap().starts_with("md5-"));
+
+    let got = client.head_attachment((("/baseball", doc_id), "nonexistent.png")).run().unwrap();
+    assert_eq!(None, got);
+}
+
 #[test]
 fn read_document_ok_with_attachment_stubs() {
 
@@ -289,6 +418,33 @@ fn update_document_ok_default() {
     assert_eq!(&updated_rev, doc.revision());
 }
 
+#[test]
+fn update_document_ok_typed() {
+
+    let (_server, client) = make_server_and_client();
+    client.create_database("/baseball").run().unwrap();
+
+    let up_content = serde_json::builder::ObjectBuilder::new()
+        .insert("home_runs", 714)
+        .build();
+
+    let (doc_id, _rev1) = client.create_document("/baseball", &up_content).run().unwrap();
+
+    let mut doc = client.read_document(("/baseball", doc_id.clone()))
+        .run_typed::<std::collections::BTreeMap<String, u64>>()
+        .unwrap();
+    assert_eq!(Some(&714), doc.content().get("home_runs"));
+
+    doc.content_mut().insert(String::from("home_runs"), 715);
+    let rev2 = client.update_document(&doc).run().unwrap();
+
+    let doc = client.read_document(("/baseball", doc_id))
+        .run_typed::<std::collections::BTreeMap<String, u64>>()
+        .unwrap();
+    assert_eq!(&rev2, doc.revision());
+    assert_eq!(Some(&715), doc.content().get("home_runs"));
+}
+
 #[test]
 fn update_document_ok_create_attachment() {
 
@@ -429,49 +585,428 @@ fn update_document_ok_delete_attachment() {
         .run()
         .unwrap();
 
-    let expected_attachments = std::collections::HashMap::<chill::AttachmentPath, (mime::Mime, Vec<u8>)>::new();
+    let expected_attachments = std::collections::HashMap::<chill::AttachmentPath, (mime::Mime, Vec<u8>)>::new();
+
+    let got_attachments = doc.attachments()
+        .map(|(name, attachment)| {
+            let path = chill::AttachmentPath::from(name);
+            let content_type = attachment.content_type().clone();
+            let content = attachment.content().unwrap().clone();
+            (path, (content_type, content))
+        })
+        .collect();
+
+    assert_eq!(expected_attachments, got_attachments);
+}
+
+#[test]
+fn delete_document_ok() {
+
+    let (_server, client) = make_server_and_client();
+    client.create_database("/baseball").run().unwrap();
+
+    let up_content = serde_json::builder::ObjectBuilder::new()
+        .insert("name", "Babe Ruth")
+        .insert("nickname", "The Bambino")
+        .build();
+
+    let (doc_id, rev1) = client.create_document("/baseball", &up_content).run().unwrap();
+
+    let rev2 = client.delete_document(("/baseball", doc_id.clone()), &rev1)
+        .run()
+        .unwrap();
+
+    match client.read_document(("/baseball", doc_id.clone())).run() {
+        Err(chill::Error::NotFound(..)) => (),
+        x @ _ => {
+            panic!("Unexpected result: {:?}", x);
+        }
+    }
+
+    let doc = client.read_document(("/baseball", doc_id))
+        .with_revision(&rev2)
+        .run()
+        .unwrap();
+    assert!(doc.is_deleted());
+}
+
+#[test]
+fn copy_document_ok() {
+
+    let (_server, client) = make_server_and_client();
+    client.create_database("/baseball").run().unwrap();
+
+    let template = serde_json::builder::ObjectBuilder::new()
+        .insert("team", "New York Yankees")
+        .build();
+
+    let (template_id, _rev) = client.create_document("/baseball", &template).run().unwrap();
+
+    let (doc_id, rev1) = client.copy_document(("/baseball", template_id.clone()), "babe_ruth")
+        .run()
+        .unwrap();
+    assert_eq!(chill::DocumentId::from("babe_ruth"), doc_id);
+
+    let doc = client.read_document("/baseball/babe_ruth").run().unwrap();
+    assert_eq!(rev1, *doc.revision());
+    assert_eq!(template, doc.get_content().unwrap());
+
+    match client.copy_document(("/baseball", template_id.clone()), "babe_ruth").run() {
+        Err(chill::Error::DocumentConflict(..)) => (),
+        x @ _ => unexpected_result!(x),
+    }
+
+    let (_doc_id, rev2) = client.copy_document(("/baseball", template_id), "babe_ruth")
+        .with_destination_revision(&rev1)
+        .run()
+        .unwrap();
+    assert_eq!(2, rev2.sequence_number());
+}
+
+#[test]
+fn local_document_ok_write_read_list_delete() {
+
+    let (_server, client) = make_server_and_client();
+    client.create_database("/baseball").run().unwrap();
+
+    let content1 = serde_json::builder::ObjectBuilder::new()
+        .insert("seq", 1)
+        .build();
+
+    let rev1 = client.write_local_document("/baseball", "checkpoint", &content1)
+        .run()
+        .unwrap();
+
+    let content2 = serde_json::builder::ObjectBuilder::new()
+        .insert("seq", 2)
+        .build();
+
+    let rev2 = client.write_local_document("/baseball", "checkpoint", &content2)
+        .with_revision(&rev1)
+        .run()
+        .unwrap();
+    assert!(rev1 < rev2);
+
+    let doc = client.read_local_document("/baseball", "checkpoint").run().unwrap();
+    assert_eq!(&rev2, doc.revision());
+    assert_eq!(content2, doc.get_content().unwrap());
+
+    let got = client.list_local_documents("/baseball").run().unwrap();
+    assert_eq!(vec![(chill::LocalDocumentName::from("checkpoint"), rev2)], got);
+
+    client.delete_local_document("/baseball", "checkpoint", &rev2).run().unwrap();
+
+    match client.read_local_document("/baseball", "checkpoint").run() {
+        Err(chill::Error::NotFound(..)) => (),
+        x @ _ => unexpected_result!(x),
+    }
+}
+
+#[test]
+fn modify_document_ok_create_then_update() {
+
+    use chill::action::Modification;
+
+    let (_server, client) = make_server_and_client();
+    client.create_database("/baseball").run().unwrap();
+
+    let increment = |doc: Option<&mut chill::Document>| -> Result<Modification, chill::Error> {
+        match doc {
+            None => {
+                let content = serde_json::builder::ObjectBuilder::new()
+                    .insert("home_runs", 1)
+                    .build();
+                Ok(Modification::Create(content))
+            }
+            Some(doc) => {
+                let content: serde_json::Value = try!(doc.get_content());
+                let home_runs = content.find("home_runs").and_then(|x| x.as_u64()).unwrap();
+                let content = serde_json::builder::ObjectBuilder::new()
+                    .insert("home_runs", home_runs + 1)
+                    .build();
+                doc.set_content(&content).unwrap();
+                Ok(Modification::Update)
+            }
+        }
+    };
+
+    let rev1 = client.modify_document("/baseball/babe_ruth", &increment).run().unwrap();
+    assert_eq!(1, rev1.sequence_number());
+
+    let rev2 = client.modify_document("/baseball/babe_ruth", &increment).run().unwrap();
+    assert_eq!(2, rev2.sequence_number());
+
+    let doc = client.read_document("/baseball/babe_ruth").run().unwrap();
+    let expected = serde_json::builder::ObjectBuilder::new()
+        .insert("home_runs", 2)
+        .build();
+    assert_eq!(expected, doc.get_content().unwrap());
+}
+
+#[test]
+fn modify_document_nok_not_found() {
+
+    use chill::action::Modification;
+
+    let (_server, client) = make_server_and_client();
+    client.create_database("/baseball").run().unwrap();
+
+    match client.modify_document("/baseball/babe_ruth", |_doc: Option<&mut chill::Document>| {
+        Ok(Modification::Unchanged)
+    }).run() {
+        Err(chill::Error::NotFound(..)) => (),
+        x @ _ => unexpected_result!(x),
+    }
+}
+
+#[test]
+fn resolve_conflicts_ok_no_conflicts() {
+
+    let (_server, client) = make_server_and_client();
+    client.create_database("/baseball").run().unwrap();
+
+    let content = serde_json::builder::ObjectBuilder::new()
+        .insert("name", "Babe Ruth")
+        .build();
+
+    let (doc_id, rev) = client.create_document("/baseball", &content).run().unwrap();
+
+    let got = client.resolve_conflicts(("/baseball", doc_id), chill::HighestSequenceWins::new())
+        .run()
+        .unwrap();
+    assert_eq!(rev, got);
+}
+
+#[test]
+fn deploy_design_ok_created_then_unchanged() {
+
+    let (_server, client) = make_server_and_client();
+    client.create_database("/baseball").run().unwrap();
+
+    let design = chill::DesignBuilder::new()
+        .insert_view("home_runs",
+                     chill::ViewFunction::new("function(doc) { emit(doc.home_runs, doc.home_runs) }"))
+        .insert_show("player", "function(doc, req) { return doc.name; }")
+        .unwrap();
+
+    let created = client.deploy_design("/baseball/_design/stats", &design).run().unwrap();
+    let revision = match created {
+        chill::Deployment::Created(ref x) => x.clone(),
+        x @ _ => panic!("Unexpected deployment: {:?}", x),
+    };
+
+    let doc = client.read_document("/baseball/_design/stats").run().unwrap();
+    assert_eq!(&revision, doc.revision());
+    assert_eq!(design, doc.get_content().unwrap());
+
+    let unchanged = client.deploy_design("/baseball/_design/stats", &design).run().unwrap();
+    assert_eq!(chill::Deployment::Unchanged(revision), unchanged);
+}
+
+#[test]
+fn deploy_design_ok_updated_with_staging() {
+
+    let (_server, client) = make_server_and_client();
+    client.create_database("/baseball").run().unwrap();
+
+    let up_content = serde_json::builder::ObjectBuilder::new()
+        .insert("name", "Babe Ruth")
+        .insert("home_runs", 714)
+        .build();
+
+    let (babe_id, _) = client.create_document("/baseball", &up_content).run().unwrap();
+
+    let design = chill::DesignBuilder::new()
+        .insert_view("home_runs",
+                     chill::ViewFunction::new("function(doc) { emit(doc.home_runs, doc.home_runs) }"))
+        .unwrap();
+
+    client.deploy_design("/baseball/_design/stats", &design).run().unwrap();
+
+    let design = chill::DesignBuilder::new()
+        .insert_view("home_runs",
+                     chill::ViewFunction::new("function(doc) { emit(doc.home_runs, doc.name) }"))
+        .unwrap();
+
+    let updated = client.deploy_design("/baseball/_design/stats", &design)
+        .with_staging("stats_staging")
+        .run()
+        .unwrap();
+
+    match updated {
+        chill::Deployment::Updated(..) => (),
+        x @ _ => panic!("Unexpected deployment: {:?}", x),
+    }
+
+    match client.read_document("/baseball/_design/stats_staging").run() {
+        Err(chill::Error::NotFound(..)) => (),
+        x @ _ => unexpected_result!(x),
+    }
+
+    let expected = chill::testing::ViewResponseBuilder::new_unreduced("baseball", 1, 0)
+        .with_row(babe_id, 714, "Babe Ruth")
+        .unwrap();
+
+    let got = client.execute_view("/baseball/_design/stats/_view/home_runs")
+        .run()
+        .unwrap();
+
+    assert_eq!(expected, got);
+}
+
+#[test]
+fn execute_list_ok_csv() {
+
+    use std::io::Read;
+
+    let (_server, client) = make_server_and_client();
+    client.create_database("/baseball").run().unwrap();
+
+    let create_player = |name, home_runs| {
+        let up_content = serde_json::builder::ObjectBuilder::new()
+            .insert("name", name)
+            .insert("home_runs", home_runs)
+            .build();
+        client.create_document("/baseball", &up_content).run().unwrap();
+    };
+
+    create_player("Babe Ruth", 714);
+    create_player("Hank Aaron", 755);
+    create_player("Willie Mays", 660);
+
+    let design = chill::DesignBuilder::new()
+        .insert_view("home_runs",
+                     chill::ViewFunction::new("function(doc) { emit(doc.home_runs, doc.name) }"))
+        .unwrap();
+
+    client.deploy_design("/baseball/_design/stats", &design).run().unwrap();
+
+    let design = chill::DesignBuilder::new()
+        .insert_list("csv",
+                     "function(head, req) { start({ headers: { 'Content-Type': 'text/csv' } }); var row; \
+                      while (row = getRow()) { send(row.value + ',' + row.key + '\\n'); } }")
+        .unwrap();
+
+    client.deploy_design("/baseball/_design/lists", &design).run().unwrap();
+
+    let mut stream = client.execute_list("/baseball/_design/stats/_view/home_runs", "csv")
+        .with_list_design_document("lists")
+        .with_start_key(&700)
+        .run()
+        .unwrap();
+
+    assert_eq!(200, stream.status_code());
+    assert_eq!(Some(&mime!(Text / ("csv"))), stream.content_type());
+
+    let mut got = String::new();
+    stream.read_to_string(&mut got).unwrap();
+    assert_eq!("Babe Ruth,714\nHank Aaron,755\n", got);
+}
+
+#[test]
+fn execute_list_nok_not_found() {
+
+    let (_server, client) = make_server_and_client();
+    client.create_database("/baseball").run().unwrap();
+
+    match client.execute_list("/baseball/_design/stats/_view/home_runs", "csv").run() {
+        Err(chill::Error::NotFound(..)) => (),
+        x @ _ => unexpected_result!(x),
+    }
+}
+
+#[test]
+fn execute_show_ok_with_document_id() {
+
+    let (_server, client) = make_server_and_client();
+    client.create_database("/baseball").run().unwrap();
+
+    let up_content = serde_json::builder::ObjectBuilder::new()
+        .insert("name", "Babe Ruth")
+        .build();
+
+    client.create_document("/baseball", &up_content)
+        .with_document_id("babe_ruth")
+        .run()
+        .unwrap();
+
+    let design = chill::DesignBuilder::new()
+        .insert_show("player",
+                     "function(doc, req) { return { headers: { 'Content-Type': 'text/html' }, body: '<p>' + \
+                      doc.name + '</p>' }; }")
+        .insert_show("player_json", "function(doc, req) { return { json: { name: doc.name } }; }")
+        .unwrap();
+
+    client.deploy_design("/baseball/_design/stats", &design).run().unwrap();
+
+    let got = client.execute_show("/baseball/_design/stats", "player")
+        .with_document_id("babe_ruth")
+        .run()
+        .unwrap();
+
+    assert_eq!(200, got.status_code());
+    assert_eq!(Some(&mime!(Text / Html)), got.content_type());
+    assert_eq!(b"<p>Babe Ruth</p>", got.content());
+
+    let got = client.execute_show("/baseball/_design/stats", "player_json")
+        .with_document_id("babe_ruth")
+        .run()
+        .unwrap();
+
+    let got: serde_json::Value = got.decode_json().unwrap();
+    assert_eq!(up_content, got);
+}
 
-    let got_attachments = doc.attachments()
-        .map(|(name, attachment)| {
-            let path = chill::AttachmentPath::from(name);
-            let content_type = attachment.content_type().clone();
-            let content = attachment.content().unwrap().clone();
-            (path, (content_type, content))
-        })
-        .collect();
+#[test]
+fn execute_show_nok_not_found() {
 
-    assert_eq!(expected_attachments, got_attachments);
+    let (_server, client) = make_server_and_client();
+    client.create_database("/baseball").run().unwrap();
+
+    match client.execute_show("/baseball/_design/stats", "player").run() {
+        Err(chill::Error::NotFound(..)) => (),
+        x @ _ => unexpected_result!(x),
+    }
 }
 
 #[test]
-fn delete_document_ok() {
+fn execute_update_handler_ok_with_document_id() {
 
     let (_server, client) = make_server_and_client();
     client.create_database("/baseball").run().unwrap();
 
     let up_content = serde_json::builder::ObjectBuilder::new()
-        .insert("name", "Babe Ruth")
-        .insert("nickname", "The Bambino")
+        .insert("count", 0)
         .build();
 
-    let (doc_id, rev1) = client.create_document("/baseball", &up_content).run().unwrap();
-
-    let rev2 = client.delete_document(("/baseball", doc_id.clone()), &rev1)
+    client.create_document("/baseball", &up_content)
+        .with_document_id("counter")
         .run()
         .unwrap();
 
-    match client.read_document(("/baseball", doc_id.clone())).run() {
-        Err(chill::Error::NotFound(..)) => (),
-        x @ _ => {
-            panic!("Unexpected result: {:?}", x);
-        }
-    }
+    let design = chill::DesignBuilder::new()
+        .insert_update("increment",
+                       "function(doc, req) { doc.count += parseInt(req.query.by); return [doc, 'incremented']; }")
+        .unwrap();
 
-    let doc = client.read_document(("/baseball", doc_id))
-        .with_revision(&rev2)
+    client.deploy_design("/baseball/_design/stats", &design).run().unwrap();
+
+    let (revision, response) = client.execute_update_handler("/baseball/_design/stats", "increment")
+        .with_document_id("counter")
+        .with_query_param("by", "2")
         .run()
         .unwrap();
-    assert!(doc.is_deleted());
+
+    assert_eq!(b"incremented", response.content());
+
+    let doc = client.read_document("/baseball/counter").run().unwrap();
+    assert_eq!(Some(doc.revision()), revision.as_ref());
+
+    let expected = serde_json::builder::ObjectBuilder::new()
+        .insert("count", 2)
+        .build();
+    let got: serde_json::Value = doc.get_content().unwrap();
+    assert_eq!(expected, got);
 }
 
 #[test]
@@ -685,6 +1220,63 @@ fn execute_view_ok_unreduced_with_start_key() {
     assert_eq!(expected, got);
 }
 
+#[test]
+fn execute_view_ok_unreduced_with_key_prefix() {
+
+    let (_server, client) = make_server_and_client();
+    client.create_database("/baseball").run().unwrap();
+
+    let create_player = |name, team, year| {
+        let content = serde_json::builder::ObjectBuilder::new()
+            .insert("name", name)
+            .insert("team", team)
+            .insert("year", year)
+            .build();
+        let (doc_id, _) = client.create_document("/baseball", &content).run().unwrap();
+        doc_id
+    };
+
+    create_player("Babe Ruth", "Yankees", 1927);
+    let lou_id = create_player("Lou Gehrig", "Yankees", 1934);
+    let mickey_id = create_player("Mickey Mantle", "Yankees", 1934);
+    create_player("Hank Aaron", "Braves", 1934);
+
+    let up_content = chill::DesignBuilder::new()
+        .insert_view("by_team_year",
+                     chill::ViewFunction::new("function(doc) { emit([doc.team, doc.year, doc.name], null) }"))
+        .unwrap();
+
+    client.create_document("/baseball", &up_content)
+        .with_document_id("_design/stats")
+        .run()
+        .unwrap();
+
+    let expected = chill::testing::ViewResponseBuilder::new_unreduced("baseball", 4, 2)
+        .with_row(lou_id.clone(), ("Yankees", 1934, "Lou Gehrig"), ())
+        .with_row(mickey_id.clone(), ("Yankees", 1934, "Mickey Mantle"), ())
+        .unwrap();
+
+    let got = client.execute_view("/baseball/_design/stats/_view/by_team_year")
+        .with_key_prefix(("Yankees", 1934))
+        .run()
+        .unwrap();
+
+    assert_eq!(expected, got);
+
+    let expected = chill::testing::ViewResponseBuilder::new_unreduced("baseball", 4, 0)
+        .with_row(mickey_id, ("Yankees", 1934, "Mickey Mantle"), ())
+        .with_row(lou_id, ("Yankees", 1934, "Lou Gehrig"), ())
+        .unwrap();
+
+    let got = client.execute_view("/baseball/_design/stats/_view/by_team_year")
+        .with_key_prefix(("Yankees", 1934))
+        .with_descending(true)
+        .run()
+        .unwrap();
+
+    assert_eq!(expected, got);
+}
+
 #[test]
 fn execute_view_ok_unreduced_with_reduce_false() {
 
@@ -830,6 +1422,90 @@ fn execute_view_ok_unreduced_with_documents() {
     assert_eq!(expected, got);
 }
 
+#[test]
+fn execute_view_ok_typed_with_documents() {
+
+    let (_server, client) = make_server_and_client();
+    client.create_database("/baseball").run().unwrap();
+
+    let babe_content = serde_json::builder::ObjectBuilder::new()
+        .insert("name", "Babe Ruth")
+        .insert("home_runs", 714)
+        .build();
+
+    let (babe_id, _babe_rev) = client.create_document("/baseball", &babe_content).run().unwrap();
+
+    let hank_content = serde_json::builder::ObjectBuilder::new()
+        .insert("name", "Hank Aaron")
+        .insert("home_runs", 755)
+        .build();
+
+    let (hank_id, _hank_rev) = client.create_document("/baseball", &hank_content).run().unwrap();
+
+    let design_content = chill::DesignBuilder::new()
+        .insert_view("home_runs",
+                     chill::ViewFunction::new("function(doc) { emit(doc.home_runs, doc.name) }"))
+        .unwrap();
+
+    client.create_document("/baseball", &design_content)
+        .with_document_id("_design/stats")
+        .run()
+        .unwrap();
+
+    let got = client.execute_view("/baseball/_design/stats/_view/home_runs")
+        .with_documents(true)
+        .run_typed::<u32, String, serde_json::Value>()
+        .unwrap();
+
+    let expected = vec![
+        (babe_id, 714, String::from("Babe Ruth"), babe_content),
+        (hank_id, 755, String::from("Hank Aaron"), hank_content),
+    ];
+
+    let got = got.into_rows()
+        .into_iter()
+        .map(|x| {
+            (x.document_path().unwrap().document_id().clone(),
+             *x.key().unwrap(),
+             x.value().clone(),
+             x.document().unwrap().clone())
+        })
+        .collect::<Vec<_>>();
+
+    assert_eq!(expected, got);
+}
+
+#[test]
+fn execute_view_nok_typed_row_decode() {
+
+    let (_server, client) = make_server_and_client();
+    client.create_database("/baseball").run().unwrap();
+
+    let content = serde_json::builder::ObjectBuilder::new()
+        .insert("name", "Babe Ruth")
+        .insert("home_runs", 714)
+        .build();
+
+    let (doc_id, _rev) = client.create_document("/baseball", &content).run().unwrap();
+
+    let design_content = chill::DesignBuilder::new()
+        .insert_view("home_runs",
+                     chill::ViewFunction::new("function(doc) { emit(doc.home_runs, doc.name) }"))
+        .unwrap();
+
+    client.create_document("/baseball", &design_content)
+        .with_document_id("_design/stats")
+        .run()
+        .unwrap();
+
+    match client.execute_view("/baseball/_design/stats/_view/home_runs")
+        .run_typed::<u32, u32, serde_json::Value>() {
+        Err(chill::Error::ViewRowDecode { doc_path: Some(ref doc_path), .. })
+            if *doc_path.document_id() == doc_id => (),
+        x @ _ => unexpected_result!(x),
+    }
+}
+
 #[test]
 fn execute_view_ok_reduced() {
 
@@ -1007,3 +1683,208 @@ fn execute_view_ok_grouped_with_level() {
 
     assert_eq!(expected, got);
 }
+
+#[test]
+fn find_ok() {
+
+    let (_server, client) = make_server_and_client();
+    client.create_database("/baseball").run().unwrap();
+
+    let up_content = serde_json::builder::ObjectBuilder::new()
+        .insert("name", "Babe Ruth")
+        .insert("home_runs", 714)
+        .build();
+
+    client.create_document("/baseball", &up_content)
+        .with_document_id("babe_ruth")
+        .run()
+        .unwrap();
+
+    let up_content = serde_json::builder::ObjectBuilder::new()
+        .insert("name", "Roger Maris")
+        .insert("home_runs", 275)
+        .build();
+
+    client.create_document("/baseball", &up_content)
+        .with_document_id("roger_maris")
+        .run()
+        .unwrap();
+
+    let got = client.find("/baseball", chill::mango::Selector::new().gte("home_runs", 500))
+        .with_fields(vec!["name"])
+        .run()
+        .unwrap();
+
+    assert_eq!(1, got.documents().len());
+    assert_eq!("/baseball/babe_ruth", got.documents()[0].path().to_string());
+    let expected = serde_json::builder::ObjectBuilder::new()
+        .insert("name", "Babe Ruth")
+        .build();
+
+    let down_content: serde_json::Value = got.documents()[0].get_content().unwrap();
+    assert_eq!(expected, down_content);
+}
+
+#[test]
+fn create_index_ok_and_list_and_explain_and_delete() {
+
+    let (_server, client) = make_server_and_client();
+    client.create_database("/baseball").run().unwrap();
+
+    let definition = chill::mango::IndexDefinition::new()
+        .with_sort_field("home_runs", chill::mango::SortOrder::Descending);
+
+    let creation = client.create_index("/baseball", definition.clone())
+        .with_name("by_home_runs")
+        .with_design_document("stats")
+        .run()
+        .unwrap();
+
+    assert_eq!(chill::mango::IndexCreation::Created(chill::DesignDocumentName::from("stats"),
+                                                    String::from("by_home_runs")),
+               creation);
+
+    let creation = client.create_index("/baseball", definition.clone())
+        .with_name("by_home_runs")
+        .with_design_document("stats")
+        .run()
+        .unwrap();
+
+    assert_eq!(chill::mango::IndexCreation::Exists(chill::DesignDocumentName::from("stats"),
+                                                   String::from("by_home_runs")),
+               creation);
+
+    let indexes = client.list_indexes("/baseball").run().unwrap();
+    let index = indexes.iter().find(|x| x.name() == "by_home_runs").unwrap();
+    assert_eq!(&definition, index.definition());
+
+    let result = client.explain("/baseball",
+                                chill::mango::Selector::new().gte("home_runs", 500))
+        .with_sort("home_runs", chill::mango::SortOrder::Descending)
+        .run()
+        .unwrap();
+
+    assert_eq!("by_home_runs", result.index().name());
+
+    client.delete_index("/baseball", "stats", "by_home_runs").run().unwrap();
+
+    match client.delete_index("/baseball", "stats", "by_home_runs").run() {
+        Err(chill::Error::NotFound(..)) => (),
+        x @ _ => unexpected_result!(x),
+    }
+}
+
+#[test]
+fn find_ok_run_iter_follows_bookmarks() {
+
+    let (_server, client) = make_server_and_client();
+    client.create_database("/baseball").run().unwrap();
+
+    for &(doc_id, name) in &[("babe_ruth", "Babe Ruth"), ("hank_aaron", "Hank Aaron"), ("willie_mays", "Willie Mays")] {
+        let up_content = serde_json::builder::ObjectBuilder::new()
+            .insert("name", name)
+            .build();
+        client.create_document("/baseball", &up_content)
+            .with_document_id(doc_id)
+            .run()
+            .unwrap();
+    }
+
+    let mut got = client.find("/baseball", chill::mango::Selector::new().exists("name", true))
+        .with_limit(2)
+        .run_iter_typed::<serde_json::Value>()
+        .unwrap()
+        .map(|x| x.unwrap().find("name").unwrap().as_str().unwrap().to_string())
+        .collect::<Vec<_>>();
+
+    got.sort();
+    assert_eq!(vec!["Babe Ruth", "Hank Aaron", "Willie Mays"], got);
+}
+
+#[test]
+fn server_info_ok() {
+    let (_server, client) = make_server_and_client();
+    let info = client.server_info().run().unwrap();
+    assert_eq!("Welcome", info.couchdb());
+    assert_eq!(info.version(), client.server_version().unwrap());
+    assert_eq!(info.version(), client.server_version().unwrap());
+}
+
+#[test]
+fn uuids_ok() {
+    let (_server, client) = make_server_and_client();
+    let uuids = client.uuids(3).run().unwrap();
+    assert_eq!(3, uuids.len());
+    assert!(uuids[0] != uuids[1] && uuids[1] != uuids[2]);
+}
+
+#[test]
+fn create_document_ok_with_uuid_pool() {
+
+    let (_server, client) = make_server_and_client();
+    let client = client.with_uuid_pool(chill::UuidPool::new(2));
+    client.create_database("/baseball").run().unwrap();
+
+    let up_content = serde_json::builder::ObjectBuilder::new()
+        .insert("name", "Babe Ruth")
+        .build();
+
+    let (doc_id_1, _rev) = client.create_document("/baseball", &up_content).run().unwrap();
+    let (doc_id_2, _rev) = client.create_document("/baseball", &up_content).run().unwrap();
+    let (doc_id_3, _rev) = client.create_document("/baseball", &up_content).run().unwrap();
+    assert!(doc_id_1 != doc_id_2 && doc_id_2 != doc_id_3);
+
+    let (doc_id, _rev) = client.create_document("/baseball", &up_content)
+        .with_document_id("babe_ruth")
+        .run()
+        .unwrap();
+    assert_eq!(chill::DocumentId::from("babe_ruth"), doc_id);
+}
+
+#[test]
+fn create_document_nok_retry_with_pooled_document_id() {
+
+    let (_server, client) = make_server_and_client();
+    let client = client.with_uuid_pool(chill::UuidPool::new(2));
+    client.create_database("/baseball").run().unwrap();
+
+    let up_content = serde_json::builder::ObjectBuilder::new()
+        .insert("name", "Babe Ruth")
+        .build();
+
+    let mut action = client.create_document("/baseball", &up_content);
+    let expected = action.assign_document_id().unwrap().unwrap();
+    assert_eq!(Some(&expected), action.document_id());
+    let (doc_id, _rev) = action.run().unwrap();
+    assert_eq!(expected, doc_id);
+
+    match client.create_document("/baseball", &up_content).with_document_id(doc_id).run() {
+        Err(chill::Error::DocumentConflict(..)) => (),
+        x @ _ => unexpected_result!(x),
+    }
+}
+
+#[test]
+fn create_document_ok_with_document_id_generator() {
+
+    let (_server, client) = make_server_and_client();
+    client.create_database("/baseball").run().unwrap();
+
+    let up_content = serde_json::builder::ObjectBuilder::new()
+        .insert("name", "Babe Ruth")
+        .build();
+
+    let generator = chill::UlidIdGenerator::new();
+
+    let (doc_id_1, _rev) = client.create_document("/baseball", &up_content)
+        .with_document_id_generator(&generator)
+        .run()
+        .unwrap();
+
+    let (doc_id_2, _rev) = client.create_document("/baseball", &up_content)
+        .with_document_id_generator(&generator)
+        .run()
+        .unwrap();
+
+    assert!(doc_id_1 < doc_id_2);
+}